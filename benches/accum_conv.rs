@@ -50,7 +50,7 @@ impl Circuit<Fr> for MyCircuit {
 
         let output = VarTensor::new_advice(cs, K, 1, (len + 1) * len);
 
-        Self::Config::configure(cs, &[a, b], &output, CheckMode::UNSAFE)
+        Self::Config::configure(cs, &[a, b], &output, CheckMode::UNSAFE).unwrap()
     }
 
     fn synthesize(
@@ -50,7 +50,7 @@ impl Circuit<Fr> for MyCircuit {
         let output = VarTensor::new_advice(cs, k, 1, len);
 
         let mut base_config =
-            BaseConfig::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::UNSAFE);
+            BaseConfig::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::UNSAFE).unwrap();
 
         // sets up a new relu table
         base_config
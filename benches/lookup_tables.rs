@@ -0,0 +1,120 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use ezkl::circuit::region::RegionCtx;
+use ezkl::circuit::{ops::lookup::LookupOp, utils, BaseConfig as Config};
+use ezkl::pfsys::{create_keys, srs::gen_srs};
+use ezkl::tensor::*;
+use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use halo2curves::bn256::{Bn256, Fr};
+
+const BITS: (i128, i128) = (-32768, 32768);
+static mut NUM_TABLES: usize = 1;
+const LEN: usize = 4;
+const K: usize = 16;
+
+fn lookups(num_tables: usize) -> Vec<LookupOp> {
+    let scale = utils::F32(128.0);
+    vec![
+        LookupOp::ReLU,
+        LookupOp::Sigmoid { scale },
+        LookupOp::Exp { scale },
+        LookupOp::Tanh { scale },
+        LookupOp::Erf { scale },
+        LookupOp::Sqrt { scale },
+        LookupOp::Rsqrt { scale },
+        LookupOp::Gelu { scale },
+    ]
+    .into_iter()
+    .take(num_tables)
+    .collect()
+}
+
+#[derive(Clone)]
+struct LookupTablesCircuit {
+    pub input: ValTensor<Fr>,
+}
+
+impl Circuit<Fr> for LookupTablesCircuit {
+    type Config = Config<Fr>;
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(cs: &mut ConstraintSystem<Fr>) -> Self::Config {
+        unsafe {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, LEN))
+                .collect::<Vec<_>>();
+
+            let mut config = Config::default();
+
+            for nl in lookups(NUM_TABLES) {
+                config
+                    .configure_lookup(cs, &advices[0], &advices[1], &advices[2], BITS, K, &nl)
+                    .unwrap();
+            }
+
+            config
+        }
+    }
+
+    fn synthesize(
+        &self,
+        mut config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.layout_tables(&mut layouter).unwrap();
+        layouter.assign_region(
+            || "",
+            |region| {
+                let mut region = RegionCtx::new(region, 0, 1);
+                config
+                    .layout(&mut region, &[self.input.clone()], Box::new(LookupOp::ReLU))
+                    .unwrap();
+                Ok(())
+            },
+        )?;
+        Ok(())
+    }
+}
+
+fn runlookuptables(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lookup_tables");
+
+    let params = gen_srs::<KZGCommitmentScheme<_>>(17);
+    for &num_tables in [1, 8].iter() {
+        unsafe {
+            NUM_TABLES = num_tables;
+        };
+
+        let input: Tensor<Value<Fr>> = Tensor::<i32>::from(0..LEN as i32).into();
+
+        let circuit = LookupTablesCircuit {
+            input: ValTensor::from(input),
+        };
+
+        group.throughput(Throughput::Elements(num_tables as u64));
+        group.bench_with_input(BenchmarkId::new("pk", num_tables), &num_tables, |b, &_| {
+            b.iter(|| {
+                create_keys::<KZGCommitmentScheme<Bn256>, Fr, LookupTablesCircuit>(
+                    &circuit, &params,
+                )
+                .unwrap();
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+  name = benches;
+  config = Criterion::default().with_plots();
+  targets = runlookuptables
+}
+criterion_main!(benches);
@@ -42,7 +42,7 @@ impl Circuit<Fr> for MyCircuit {
         let b = VarTensor::new_advice(cs, K, 1, len);
         let output = VarTensor::new_advice(cs, K, 1, len);
 
-        Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+        Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
     }
 
     fn synthesize(
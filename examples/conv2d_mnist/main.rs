@@ -153,7 +153,8 @@ where
             &[input.clone(), params.clone()],
             &output,
             CheckMode::SAFE,
-        );
+        )
+        .unwrap();
 
         layer_config
             .configure_lookup(
@@ -58,7 +58,8 @@ impl<const LEN: usize, const LOOKUP_MIN: i128, const LOOKUP_MAX: i128> Circuit<F
             &[input.clone(), params.clone()],
             &output,
             CheckMode::SAFE,
-        );
+        )
+        .unwrap();
 
         // sets up a new ReLU table and resuses it for l1 and l3 non linearities
         layer_config
@@ -1,12 +1,27 @@
-use crate::tensor::TensorType;
+use crate::{
+    circuit::{utils, CircuitError},
+    fieldutils::i128_to_felt,
+    tensor::TensorType,
+};
+use halo2_proofs::plonk::Expression;
+use halo2curves::ff::PrimeField;
+#[cfg(feature = "python-bindings")]
+use pyo3::{
+    conversion::{FromPyObject, PyTryFrom},
+    exceptions::PyValueError,
+    prelude::*,
+    types::PyString,
+};
+use serde::{Deserialize, Serialize};
 use std::{
     fmt,
     ops::{Add, Mul, Neg, Sub},
+    str::FromStr,
 };
 
 #[allow(missing_docs)]
 /// An enum representing the operations that can be used to express more complex operations via accumulation
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum BaseOp {
     Dot,
     CumProd,
@@ -19,6 +34,28 @@ pub enum BaseOp {
     Range { tol: i32 },
     IsZero,
     IsBoolean,
+    Div,
+    Max,
+    Min,
+    Abs,
+    Square,
+    Clamp { min: i128, max: i128 },
+    Select,
+    Greater,
+    Less,
+    ReduceMax,
+    ReduceMin,
+    Mod { modulus: i128 },
+    CumProdInit { init: i128 },
+    IsZeroInv,
+    MulAdd,
+    AddConst { c: i128 },
+    MultConst { c: i128 },
+    And,
+    Or,
+    Xor,
+    SaturatingAdd { min: i128, max: i128 },
+    SaturatingSub { min: i128, max: i128 },
 }
 
 /// Matches a [BaseOp] to an operation over inputs
@@ -43,6 +80,41 @@ impl BaseOp {
             BaseOp::Range { .. } => b,
             BaseOp::IsZero => b,
             BaseOp::IsBoolean => b,
+            // reconstructs the numerator from the witnessed quotient, divisor, and remainder
+            BaseOp::Div => a * b + m,
+            // selects a when the witnessed bit is set and b otherwise
+            BaseOp::Max | BaseOp::Min | BaseOp::Select => {
+                m.clone() * a + (T::one().unwrap() - m) * b
+            }
+            // flips the sign of b when the witnessed sign bit is set
+            BaseOp::Abs => b.clone() - (m.clone() + m) * b,
+            // wraps around the field modulus like any other product
+            BaseOp::Square => b.clone() * b,
+            // the bounds are folded into the gate as constants
+            BaseOp::Clamp { .. } => b,
+            // the non-negative slack implied by the witnessed comparison bit
+            BaseOp::Greater => (m.clone() + m.clone() - T::one().unwrap()) * (a - b) - m,
+            BaseOp::Less => (m.clone() + m.clone() - T::one().unwrap()) * (b - a) - m,
+            // the accumulator either takes the new element b or keeps the previous value m
+            BaseOp::ReduceMax | BaseOp::ReduceMin => a.clone() * b + (T::one().unwrap() - a) * m,
+            // the modulus is folded into the gate as a constant
+            BaseOp::Mod { .. } => b,
+            // the initial value is folded into the gate as a constant
+            BaseOp::CumProdInit { .. } => b,
+            // x * inv = 1 - is_zero, the gate also constrains x * is_zero = 0
+            BaseOp::IsZeroInv => T::one().unwrap() - a * b,
+            // the third input c takes the place of m
+            BaseOp::MulAdd => a * b + m,
+            // the constant is folded into the gate
+            BaseOp::AddConst { .. } => b,
+            BaseOp::MultConst { .. } => b,
+            // the operands are constrained to be boolean by the gate
+            BaseOp::And => a * b,
+            BaseOp::Or => a.clone() + b.clone() - a * b,
+            BaseOp::Xor => a.clone() + b.clone() - (a.clone() * b.clone() + a * b),
+            // the unsaturated result, the saturation is folded into the gate
+            BaseOp::SaturatingAdd { .. } => a + b,
+            BaseOp::SaturatingSub { .. } => a - b,
         }
     }
 
@@ -60,6 +132,44 @@ impl BaseOp {
             BaseOp::Range { .. } => "RANGE",
             BaseOp::IsZero => "ISZERO",
             BaseOp::IsBoolean => "ISBOOLEAN",
+            BaseOp::Div => "DIV",
+            BaseOp::Max => "MAX",
+            BaseOp::Min => "MIN",
+            BaseOp::Abs => "ABS",
+            BaseOp::Square => "SQUARE",
+            BaseOp::Clamp { .. } => "CLAMP",
+            BaseOp::Select => "SELECT",
+            BaseOp::Greater => "GREATER",
+            BaseOp::Less => "LESS",
+            BaseOp::ReduceMax => "REDUCEMAX",
+            BaseOp::ReduceMin => "REDUCEMIN",
+            BaseOp::Mod { .. } => "MOD",
+            BaseOp::CumProdInit { .. } => "CUMPRODINIT",
+            BaseOp::IsZeroInv => "ISZEROINV",
+            BaseOp::MulAdd => "MULADD",
+            BaseOp::AddConst { .. } => "ADDCONST",
+            BaseOp::MultConst { .. } => "MULTCONST",
+            BaseOp::And => "AND",
+            BaseOp::Or => "OR",
+            BaseOp::Xor => "XOR",
+            BaseOp::SaturatingAdd { .. } => "SATADD",
+            BaseOp::SaturatingSub { .. } => "SATSUB",
+        }
+    }
+
+    /// Like [BaseOp::as_str] but includes the parameters of the op, eg. `RANGE(tol=1)`.
+    /// Can be parsed back into the op using [BaseOp::from_str].
+    pub fn as_string(&self) -> String {
+        match self {
+            BaseOp::Range { tol } => format!("RANGE(tol={})", tol),
+            BaseOp::Clamp { min, max } => format!("CLAMP(min={}, max={})", min, max),
+            BaseOp::Mod { modulus } => format!("MOD(modulus={})", modulus),
+            BaseOp::CumProdInit { init } => format!("CUMPRODINIT(init={})", init),
+            BaseOp::AddConst { c } => format!("ADDCONST(c={})", c),
+            BaseOp::MultConst { c } => format!("MULTCONST(c={})", c),
+            BaseOp::SaturatingAdd { min, max } => format!("SATADD(min={}, max={})", min, max),
+            BaseOp::SaturatingSub { min, max } => format!("SATSUB(min={}, max={})", min, max),
+            _ => self.as_str().to_string(),
         }
     }
 
@@ -77,6 +187,28 @@ impl BaseOp {
             BaseOp::Range { .. } => (0, 1),
             BaseOp::IsZero => (0, 1),
             BaseOp::IsBoolean => (0, 1),
+            BaseOp::Div => (-1, 2),
+            BaseOp::Max => (-1, 2),
+            BaseOp::Min => (-1, 2),
+            BaseOp::Abs => (-1, 2),
+            BaseOp::Square => (0, 1),
+            BaseOp::Clamp { .. } => (-1, 2),
+            BaseOp::Select => (-1, 2),
+            BaseOp::Greater => (-1, 2),
+            BaseOp::Less => (-1, 2),
+            BaseOp::ReduceMax => (-1, 2),
+            BaseOp::ReduceMin => (-1, 2),
+            BaseOp::Mod { .. } => (0, 1),
+            BaseOp::CumProdInit { .. } => (0, 1),
+            BaseOp::IsZeroInv => (0, 1),
+            BaseOp::MulAdd => (0, 1),
+            BaseOp::AddConst { .. } => (0, 1),
+            BaseOp::MultConst { .. } => (0, 1),
+            BaseOp::And => (0, 1),
+            BaseOp::Or => (0, 1),
+            BaseOp::Xor => (0, 1),
+            BaseOp::SaturatingAdd { .. } => (-1, 2),
+            BaseOp::SaturatingSub { .. } => (-1, 2),
         }
     }
 
@@ -94,6 +226,28 @@ impl BaseOp {
             BaseOp::Range { .. } => 1,
             BaseOp::IsZero => 1,
             BaseOp::IsBoolean => 1,
+            BaseOp::Div => 2,
+            BaseOp::Max => 2,
+            BaseOp::Min => 2,
+            BaseOp::Abs => 1,
+            BaseOp::Square => 1,
+            BaseOp::Clamp { .. } => 2,
+            BaseOp::Select => 2,
+            BaseOp::Greater => 2,
+            BaseOp::Less => 2,
+            BaseOp::ReduceMax => 2,
+            BaseOp::ReduceMin => 2,
+            BaseOp::Mod { .. } => 2,
+            BaseOp::CumProdInit { .. } => 1,
+            BaseOp::IsZeroInv => 2,
+            BaseOp::MulAdd => 3,
+            BaseOp::AddConst { .. } => 1,
+            BaseOp::MultConst { .. } => 1,
+            BaseOp::And => 2,
+            BaseOp::Or => 2,
+            BaseOp::Xor => 2,
+            BaseOp::SaturatingAdd { .. } => 2,
+            BaseOp::SaturatingSub { .. } => 2,
         }
     }
 
@@ -111,6 +265,185 @@ impl BaseOp {
             BaseOp::CumProd => 1,
             BaseOp::IsZero => 0,
             BaseOp::IsBoolean => 0,
+            BaseOp::Div => 1,
+            BaseOp::Max => 1,
+            BaseOp::Min => 1,
+            BaseOp::Abs => 1,
+            BaseOp::Square => 0,
+            BaseOp::Clamp { .. } => 1,
+            BaseOp::Select => 1,
+            BaseOp::Greater => 1,
+            BaseOp::Less => 1,
+            BaseOp::ReduceMax => 1,
+            BaseOp::ReduceMin => 1,
+            BaseOp::Mod { .. } => 0,
+            BaseOp::CumProdInit { .. } => 0,
+            BaseOp::IsZeroInv => 0,
+            BaseOp::MulAdd => 0,
+            BaseOp::AddConst { .. } => 0,
+            BaseOp::MultConst { .. } => 0,
+            BaseOp::And => 0,
+            BaseOp::Or => 0,
+            BaseOp::Xor => 0,
+            BaseOp::SaturatingAdd { .. } => 1,
+            BaseOp::SaturatingSub { .. } => 1,
+        }
+    }
+}
+
+/// An operation laid out by accumulation over the input and output columns of a [BaseConfig](crate::circuit::BaseConfig),
+/// see [BaseConfig::configure_accumulated_op](crate::circuit::BaseConfig::configure_accumulated_op) to configure ops defined
+/// outside of [BaseOp].
+///
+/// At each enabled row the gate queries the last [AccumulatedOp::num_inputs] of the first `max(2, num_inputs)` input
+/// columns at the current row, and the output column over `len` rows starting at `offset` relative to the current row,
+/// where `(offset, len)` is [AccumulatedOp::query_offset_rng]. Accumulating ops (eg. [BaseOp::Dot]) use `(-1, 2)` so that
+/// the previous output is available as the running value.
+pub trait AccumulatedOp {
+    /// The name of the op's gate.
+    fn name(&self) -> &'static str;
+
+    /// Returns the range of the query offset for this operation.
+    fn query_offset_rng(&self) -> (i32, usize);
+
+    /// Returns the number of inputs for this operation.
+    fn num_inputs(&self) -> usize;
+
+    /// Returns the index of the queried output cell that the op's result is constrained to.
+    fn constraint_idx(&self) -> usize;
+
+    /// Returns the constraints which must vanish at every enabled row. `inputs` holds `max(2, num_inputs)` queries,
+    /// the leading unqueried ones being zero, and `outputs` holds the `len` queried output cells.
+    fn constraints<F: PrimeField>(
+        &self,
+        inputs: &[Expression<F>],
+        outputs: &[Expression<F>],
+    ) -> Vec<Expression<F>>;
+}
+
+impl AccumulatedOp for BaseOp {
+    fn name(&self) -> &'static str {
+        self.as_str()
+    }
+
+    fn query_offset_rng(&self) -> (i32, usize) {
+        BaseOp::query_offset_rng(self)
+    }
+
+    fn num_inputs(&self) -> usize {
+        BaseOp::num_inputs(self)
+    }
+
+    fn constraint_idx(&self) -> usize {
+        BaseOp::constraint_idx(self)
+    }
+
+    fn constraints<F: PrimeField>(
+        &self,
+        inputs: &[Expression<F>],
+        outputs: &[Expression<F>],
+    ) -> Vec<Expression<F>> {
+        match self {
+            BaseOp::IsBoolean => {
+                vec![(inputs[1].clone()) * (inputs[1].clone() - Expression::Constant(F::from(1)))]
+            }
+            BaseOp::IsZero => vec![inputs[1].clone()],
+            BaseOp::MulAdd => {
+                let res = self.f((inputs[0].clone(), inputs[1].clone(), inputs[2].clone()));
+                vec![outputs[self.constraint_idx()].clone() - res]
+            }
+            BaseOp::IsZeroInv => {
+                let res = self.f((inputs[0].clone(), inputs[1].clone(), outputs[0].clone()));
+                let is_zero = outputs[self.constraint_idx()].clone();
+                // a nonzero input forces the indicator to 0, which in turn forces a valid inverse
+                vec![is_zero.clone() - res, inputs[0].clone() * is_zero]
+            }
+            BaseOp::Clamp { min, max } => {
+                // below-min bit in the previous output row, above-max bit in the first input
+                let one = Expression::Constant(F::from(1));
+                let (below, above, x) = (outputs[0].clone(), inputs[0].clone(), inputs[1].clone());
+                let res = below.clone() * Expression::Constant(i128_to_felt(*min))
+                    + above.clone() * Expression::Constant(i128_to_felt(*max))
+                    + (one.clone() - below.clone() - above.clone()) * x;
+
+                vec![
+                    outputs[self.constraint_idx()].clone() - res,
+                    below.clone() * (below.clone() - one.clone()),
+                    above.clone() * (above.clone() - one),
+                    below * above,
+                ]
+            }
+            BaseOp::SaturatingAdd { min, max } | BaseOp::SaturatingSub { min, max } => {
+                // the overflow indicator in the previous output row is -1 below min, 1 above max, and 0 otherwise
+                let (o, res) = (outputs[0].clone(), outputs[self.constraint_idx()].clone());
+                let sum = self.f((inputs[0].clone(), inputs[1].clone(), o.clone()));
+                let one = Expression::Constant(F::from(1));
+                let two = Expression::Constant(F::from(2));
+                let (min, max) = (
+                    Expression::Constant(i128_to_felt(*min)),
+                    Expression::Constant(i128_to_felt(*max)),
+                );
+                // o(o + 1) / 2 and o(o - 1) / 2 select max and min respectively, scaled by 2 to avoid the inverse
+                let saturated = two.clone() * sum.clone()
+                    + o.clone() * (o.clone() + one.clone()) * (max - sum.clone())
+                    + o.clone() * (o.clone() - one.clone()) * (min - sum);
+                vec![
+                    two * res - saturated,
+                    o.clone() * (o.clone() - one.clone()) * (o + one),
+                ]
+            }
+            BaseOp::Mod { modulus } => {
+                // x = quotient * modulus + residue
+                let res = inputs[0].clone() * Expression::Constant(i128_to_felt(*modulus))
+                    + outputs[self.constraint_idx()].clone();
+                vec![inputs[1].clone() - res]
+            }
+            BaseOp::CumProdInit { init } => {
+                // the first running product is the first input scaled by init
+                let res = inputs[1].clone() * Expression::Constant(i128_to_felt(*init));
+                vec![outputs[self.constraint_idx()].clone() - res]
+            }
+            BaseOp::AddConst { c } | BaseOp::MultConst { c } => {
+                let c = Expression::Constant(i128_to_felt(*c));
+                let res = match self {
+                    BaseOp::AddConst { .. } => inputs[1].clone() + c,
+                    _ => inputs[1].clone() * c,
+                };
+                vec![outputs[self.constraint_idx()].clone() - res]
+            }
+            _ => {
+                let res = self.f((inputs[0].clone(), inputs[1].clone(), outputs[0].clone()));
+                let mut constraints = vec![outputs[self.constraint_idx()].clone() - res];
+
+                // the selection, sign, or comparison bit sits in the previous row of the output
+                if matches!(
+                    self,
+                    BaseOp::Max
+                        | BaseOp::Min
+                        | BaseOp::Abs
+                        | BaseOp::Select
+                        | BaseOp::Greater
+                        | BaseOp::Less
+                ) {
+                    let bit = outputs[0].clone();
+                    constraints.push(bit.clone() * (bit - Expression::Constant(F::from(1))));
+                }
+
+                // the accumulator's selection bit sits in the first input
+                if matches!(self, BaseOp::ReduceMax | BaseOp::ReduceMin) {
+                    let bit = inputs[0].clone();
+                    constraints.push(bit.clone() * (bit - Expression::Constant(F::from(1))));
+                }
+
+                // both operands of the logical ops are bits
+                if matches!(self, BaseOp::And | BaseOp::Or | BaseOp::Xor) {
+                    for bit in inputs[0..2].iter().cloned() {
+                        constraints.push(bit.clone() * (bit - Expression::Constant(F::from(1))));
+                    }
+                }
+
+                constraints
+            }
         }
     }
 }
@@ -120,3 +453,85 @@ impl fmt::Display for BaseOp {
         write!(f, "{}", self.as_str())
     }
 }
+
+impl FromStr for BaseOp {
+    type Err = CircuitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || CircuitError::InvalidOpString(s.to_string());
+        let (name, params) = utils::parse_op_string(s).ok_or_else(err)?;
+        let op = match name {
+            "IDENTITY" => BaseOp::Identity,
+            "DOT" => BaseOp::Dot,
+            "CUMPROD" => BaseOp::CumProd,
+            "ADD" => BaseOp::Add,
+            "NEG" => BaseOp::Neg,
+            "SUB" => BaseOp::Sub,
+            "MULT" => BaseOp::Mult,
+            "SUM" => BaseOp::Sum,
+            "RANGE" => BaseOp::Range {
+                tol: utils::parse_op_param(&params, "tol").ok_or_else(err)?,
+            },
+            "ISZERO" => BaseOp::IsZero,
+            "ISBOOLEAN" => BaseOp::IsBoolean,
+            "DIV" => BaseOp::Div,
+            "MAX" => BaseOp::Max,
+            "MIN" => BaseOp::Min,
+            "ABS" => BaseOp::Abs,
+            "SQUARE" => BaseOp::Square,
+            "CLAMP" => BaseOp::Clamp {
+                min: utils::parse_op_param(&params, "min").ok_or_else(err)?,
+                max: utils::parse_op_param(&params, "max").ok_or_else(err)?,
+            },
+            "SELECT" => BaseOp::Select,
+            "GREATER" => BaseOp::Greater,
+            "LESS" => BaseOp::Less,
+            "REDUCEMAX" => BaseOp::ReduceMax,
+            "REDUCEMIN" => BaseOp::ReduceMin,
+            "MOD" => BaseOp::Mod {
+                modulus: utils::parse_op_param(&params, "modulus").ok_or_else(err)?,
+            },
+            "ISZEROINV" => BaseOp::IsZeroInv,
+            "MULADD" => BaseOp::MulAdd,
+            "ADDCONST" => BaseOp::AddConst {
+                c: utils::parse_op_param(&params, "c").ok_or_else(err)?,
+            },
+            "MULTCONST" => BaseOp::MultConst {
+                c: utils::parse_op_param(&params, "c").ok_or_else(err)?,
+            },
+            "CUMPRODINIT" => BaseOp::CumProdInit {
+                init: utils::parse_op_param(&params, "init").ok_or_else(err)?,
+            },
+            "AND" => BaseOp::And,
+            "OR" => BaseOp::Or,
+            "XOR" => BaseOp::Xor,
+            "SATADD" => BaseOp::SaturatingAdd {
+                min: utils::parse_op_param(&params, "min").ok_or_else(err)?,
+                max: utils::parse_op_param(&params, "max").ok_or_else(err)?,
+            },
+            "SATSUB" => BaseOp::SaturatingSub {
+                min: utils::parse_op_param(&params, "min").ok_or_else(err)?,
+                max: utils::parse_op_param(&params, "max").ok_or_else(err)?,
+            },
+            _ => return Err(err()),
+        };
+        Ok(op)
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Converts BaseOp into a PyObject (Required for BaseOp to be compatible with Python)
+impl IntoPy<PyObject> for BaseOp {
+    fn into_py(self, py: Python) -> PyObject {
+        self.as_string().to_object(py)
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Obtains BaseOp from PyObject (Required for BaseOp to be compatible with Python)
+impl<'source> FromPyObject<'source> for BaseOp {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        let trystr = <PyString as PyTryFrom>::try_from(ob)?;
+        BaseOp::from_str(&trystr.to_string()).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+}
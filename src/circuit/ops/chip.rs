@@ -4,7 +4,7 @@ use thiserror::Error;
 
 use halo2_proofs::{
     circuit::Layouter,
-    plonk::{ConstraintSystem, Constraints, Expression, Selector},
+    plonk::{ConstraintSystem, Constraints, Expression, Selector, TableColumn},
     poly::Rotation,
 };
 use log::debug;
@@ -18,14 +18,28 @@ use pyo3::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    circuit::ops::base::BaseOp,
-    circuit::{table::Table, utils},
-    tensor::{Tensor, TensorType, ValTensor, VarTensor},
+    circuit::ops::base::{AccumulatedOp, BaseOp},
+    circuit::{
+        table::{
+            signed_bounds, BinaryTable, Table, TableRange, TableRegistry,
+            RESERVED_BLINDING_ROWS_PAD,
+        },
+        utils,
+    },
+    fieldutils::{felt_to_i128, i128_to_felt, try_i128_to_felt},
+    tensor::{get_broadcasted_shape, Tensor, TensorType, ValTensor, VarTensor, VarTensorLayout},
 };
 use std::{collections::BTreeMap, error::Error, marker::PhantomData};
 
-use super::{lookup::LookupOp, region::RegionCtx, Op};
+use super::{
+    layouts,
+    lookup::{BinaryLookupOp, LookupOp, LookupWithDefault},
+    poly::PolyOp,
+    region::RegionCtx,
+    Op,
+};
 use halo2curves::ff::{Field, PrimeField};
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 
 /// circuit related errors.
 #[derive(Debug, Error)]
@@ -36,12 +50,53 @@ pub enum CircuitError {
     /// Error when instantiating lookup tables
     #[error("failed to instantiate lookup tables")]
     LookupInstantiation,
+    /// The lookup range needs more bits than the table columns available at `logrows` can hold
+    #[error("a {bits}-bit lookup range does not fit in the table columns available at logrows {logrows}")]
+    LookupBitWidthTooLarge {
+        /// bits needed to represent the lookup range
+        bits: u32,
+        /// log2 of the number of rows in the circuit
+        logrows: usize,
+    },
     /// A lookup table was was already assigned
     #[error("attempting to initialize an already instantiated lookup table")]
     TableAlreadyAssigned,
     /// This operation is unsupported
     #[error("unsupported operation in graph")]
     UnsupportedOp,
+    /// A range was requested with an unsupported bit width, eg. 0 or wider than an i128
+    #[error("unsupported bit width for a range: {0}")]
+    InvalidSignedBits(usize),
+    /// An output fell outside of the configured signed range, eg. due to field wraparound
+    #[error("output of {0} exceeds the signed {1}-bit range")]
+    SignedRangeOverflow(String, usize),
+    /// A claimed output differed from the reference output by more than the configured tolerance
+    #[error("output of {0} is not within tolerance of the reference output")]
+    ToleranceExceeded(String),
+    /// A claimed output differed from the reference output in [CheckMode::SAFE]
+    #[error("safe mode check failed for {op}: element {index} is {claimed}, expected {expected}")]
+    SafeModeMismatch {
+        /// the op being checked
+        op: String,
+        /// flat index of the first mismatching element
+        index: usize,
+        /// the claimed value at `index`
+        claimed: i128,
+        /// the reference value at `index`
+        expected: i128,
+    },
+    /// Failed to parse a [CheckMode]
+    #[error("invalid check mode: {0}")]
+    InvalidCheckMode(String),
+    /// Failed to parse an op from its string representation
+    #[error("invalid op string: {0}")]
+    InvalidOpString(String),
+    /// An integer lies outside of the signed range that can be embedded in the field
+    #[error("{0} is outside of the signed range of the field")]
+    FeltOutOfRange(i128),
+    /// The shared lookup input and output columns were set more than once
+    #[error("the lookup input and output columns have already been assigned")]
+    LookupColumnsAlreadyAssigned,
 }
 
 #[allow(missing_docs)]
@@ -53,15 +108,33 @@ pub enum CheckMode {
     #[default]
     SAFE,
     UNSAFE,
+    /// Like [CheckMode::SAFE] but accepts outputs within [BaseConfig::tolerance] of the reference
+    RELAXED,
+    /// Like [CheckMode::SAFE] but logs violations as warnings instead of failing
+    WARN,
+}
+
+impl FromStr for CheckMode {
+    type Err = CircuitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "safe" => Ok(CheckMode::SAFE),
+            "unsafe" => Ok(CheckMode::UNSAFE),
+            "relaxed" => Ok(CheckMode::RELAXED),
+            "warn" => Ok(CheckMode::WARN),
+            _ => Err(CircuitError::InvalidCheckMode(s.to_string())),
+        }
+    }
 }
 
 impl From<String> for CheckMode {
+    /// Falls back to [CheckMode::SAFE] on invalid input, use [CheckMode::from_str] to handle the error.
     fn from(value: String) -> Self {
-        match value.to_lowercase().as_str() {
-            "safe" => CheckMode::SAFE,
-            "unsafe" => CheckMode::UNSAFE,
-            _ => panic!("not a valid checkmode"),
-        }
+        CheckMode::from_str(&value).unwrap_or_else(|e| {
+            log::warn!("{}, defaulting to {:?}", e, CheckMode::default());
+            CheckMode::default()
+        })
     }
 }
 
@@ -69,25 +142,55 @@ impl From<String> for CheckMode {
 /// An enum representing the tolerance we can accept for the accumulated arguments, either absolute or percentage
 #[derive(Clone, Default, Debug, PartialEq, PartialOrd, Serialize, Deserialize, Copy)]
 pub struct Tolerance {
+    /// percentage tolerance
     pub val: f32,
+    /// fixed point scale of the values being compared
     pub scale: utils::F32,
+    /// absolute tolerance, in units of the (unscaled) values being compared
+    #[serde(default)]
+    pub abs: f32,
+}
+
+impl Tolerance {
+    /// Returns true if the (unscaled) `claimed` value is within the larger of the percentage band, [Self::val] percent
+    /// of `expected`, and the absolute band, [Self::abs], around `expected`. Both bands are inclusive.
+    pub fn is_within(&self, claimed: f32, expected: f32) -> bool {
+        let percent_band = (expected as f64).abs() * (self.val as f64) / 100.0;
+        let abs_band = self.abs as f64;
+        (claimed as f64 - expected as f64).abs() <= percent_band.max(abs_band)
+    }
+
+    /// [Self::is_within] for fixed point values, which are first unscaled by [Self::scale] (taken as 1 if unset).
+    pub fn accepts(&self, claimed: i128, reference: i128) -> bool {
+        let scale = match self.scale.0 {
+            s if s > 0.0 => s,
+            _ => 1.0,
+        };
+        self.is_within(claimed as f32 / scale, reference as f32 / scale)
+    }
 }
 
 impl FromStr for Tolerance {
     type Err = String;
 
+    /// Parses either a percentage (`"1.5"` or `"1.5%"`) or an absolute tolerance (`"abs:3"`).
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Ok(val) = s.parse::<f32>() {
-            Ok(Tolerance {
-                val,
-                scale: utils::F32(1.0),
-            })
-        } else {
-            Err(
-                "Invalid tolerance value provided. It should expressed as a percentage (f32)."
-                    .to_string(),
-            )
+        let s = s.trim();
+        if let Some(abs) = s.strip_prefix("abs:") {
+            if let Ok(abs) = abs.trim().parse::<f32>() {
+                return Ok(Tolerance {
+                    val: 0.0,
+                    scale: utils::F32(1.0),
+                    abs,
+                });
+            }
+        } else if let Ok(val) = s.strip_suffix('%').unwrap_or(s).trim().parse::<f32>() {
+            return Ok(Tolerance::from(val));
         }
+        Err(
+            "Invalid tolerance value provided. It should expressed as a percentage (eg. 1.5 or 1.5%) or an absolute value (eg. abs:3)."
+                .to_string(),
+        )
     }
 }
 
@@ -96,6 +199,7 @@ impl From<f32> for Tolerance {
         Tolerance {
             val: value,
             scale: utils::F32(1.0),
+            abs: 0.0,
         }
     }
 }
@@ -107,6 +211,8 @@ impl IntoPy<PyObject> for CheckMode {
         match self {
             CheckMode::SAFE => "safe".to_object(py),
             CheckMode::UNSAFE => "unsafe".to_object(py),
+            CheckMode::RELAXED => "relaxed".to_object(py),
+            CheckMode::WARN => "warn".to_object(py),
         }
     }
 }
@@ -117,11 +223,8 @@ impl<'source> FromPyObject<'source> for CheckMode {
     fn extract(ob: &'source PyAny) -> PyResult<Self> {
         let trystr = <PyString as PyTryFrom>::try_from(ob)?;
         let strval = trystr.to_string();
-        match strval.to_lowercase().as_str() {
-            "safe" => Ok(CheckMode::SAFE),
-            "unsafe" => Ok(CheckMode::UNSAFE),
-            _ => Err(PyValueError::new_err("Invalid value for CheckMode")),
-        }
+        CheckMode::from_str(&strval)
+            .map_err(|_| PyValueError::new_err("Invalid value for CheckMode"))
     }
 }
 
@@ -129,18 +232,26 @@ impl<'source> FromPyObject<'source> for CheckMode {
 /// Converts Tolerance into a PyObject (Required for Tolerance to be compatible with Python)
 impl IntoPy<PyObject> for Tolerance {
     fn into_py(self, py: Python) -> PyObject {
-        (self.val, self.scale.0).to_object(py)
+        (self.val, self.scale.0, self.abs).to_object(py)
     }
 }
 
 #[cfg(feature = "python-bindings")]
 /// Obtains Tolerance from PyObject (Required for Tolerance to be compatible with Python)
+/// Accepts `(val, scale, abs)`, or `(val, scale)` for a purely percentage tolerance.
 impl<'source> FromPyObject<'source> for Tolerance {
     fn extract(ob: &'source PyAny) -> PyResult<Self> {
-        if let Ok((val, scale)) = ob.extract::<(f32, f32)>() {
+        if let Ok((val, scale, abs)) = ob.extract::<(f32, f32, f32)>() {
+            Ok(Tolerance {
+                val,
+                scale: utils::F32(scale),
+                abs,
+            })
+        } else if let Ok((val, scale)) = ob.extract::<(f32, f32)>() {
             Ok(Tolerance {
                 val,
                 scale: utils::F32(scale),
+                abs: 0.0,
             })
         } else {
             Err(PyValueError::new_err("Invalid tolerance value provided. "))
@@ -148,6 +259,123 @@ impl<'source> FromPyObject<'source> for Tolerance {
     }
 }
 
+/// A serializable snapshot of the column layout, selector keys and tables of a [BaseConfig].
+/// Can be persisted and compared against a freshly configured circuit to detect drift.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BaseConfigLayout {
+    ///
+    pub inputs: Vec<VarTensorLayout>,
+    ///
+    pub lookup_input: VarTensorLayout,
+    ///
+    pub output: VarTensorLayout,
+    ///
+    pub lookup_output: VarTensorLayout,
+    ///
+    pub lookup_index: VarTensorLayout,
+    /// keys of [BaseConfig::selectors]
+    pub selectors: Vec<(BaseOp, usize, usize)>,
+    /// keys of [BaseConfig::lookup_selectors]
+    pub lookup_selectors: Vec<(LookupOp, usize, usize)>,
+    /// keys of [BaseConfig::tables]
+    pub tables: Vec<LookupOp>,
+    /// keys of [BaseConfig::binary_tables]
+    #[serde(default)]
+    pub binary_tables: Vec<BinaryLookupOp>,
+    ///
+    pub check_mode: CheckMode,
+}
+
+/// Resources consumed by a configured [BaseConfig], see [BaseConfig::cost_estimate].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaseConfigCost {
+    /// advice columns across [BaseConfig::inputs] and [BaseConfig::output]
+    pub advice_columns: usize,
+    /// number of [BaseConfig::selectors]
+    pub selectors: usize,
+    /// number of [BaseConfig::lookup_selectors] and [BaseConfig::binary_lookup_selectors]
+    pub lookup_selectors: usize,
+    /// rows occupied by [BaseConfig::tables] and [BaseConfig::binary_tables]
+    pub table_rows: usize,
+}
+
+/// Per op entry of a [BaseConfigReport].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpReport {
+    /// the op, as returned by `as_string`
+    pub name: String,
+    /// selectors enabling the op's gate or lookup, one per block and inner column
+    pub selectors: usize,
+    /// advice columns queried by the op's gates or lookups
+    pub advice_columns: usize,
+    /// fixed columns of the op's lookup table, if any
+    pub table_columns: usize,
+    /// rows of the op's lookup table, if any
+    pub table_rows: usize,
+}
+
+/// A serializable breakdown of the resources consumed by each op of a [BaseConfig], see [BaseConfig::report].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaseConfigReport {
+    /// [BaseOp]s, then [LookupOp]s, then [BinaryLookupOp]s
+    pub ops: Vec<OpReport>,
+    /// rows across all lookup tables
+    pub total_table_rows: usize,
+}
+
+/// Estimated resources one way of laying out an op consumes, see [OpCost].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StrategyCost {
+    /// advice rows laid out per element
+    pub rows_per_element: usize,
+    /// lookup table rows that need to be added, 0 if the table is already configured
+    pub table_rows: usize,
+    /// fixed columns that need to be added for lookup tables
+    pub fixed_columns: usize,
+}
+
+impl StrategyCost {
+    /// Rows of the circuit needed to lay out `num_elements`. Advice and table rows share the rows of the circuit,
+    /// so this is the larger of the two. An upper bound, as a table spanning several columns needs fewer rows.
+    pub fn rows(&self, num_elements: usize) -> usize {
+        (self.rows_per_element * num_elements).max(self.table_rows)
+    }
+}
+
+/// Estimated cost of an op as a lookup vs decomposed into base ops, see [BaseConfig::estimate_op_cost].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpCost {
+    /// cost of a [LookupOp] table over the bit width
+    pub lookup: StrategyCost,
+    /// cost of a bit decomposition followed by base ops, `None` if the op has no such decomposition
+    pub decomposed: Option<StrategyCost>,
+}
+
+impl OpCost {
+    /// Whether the lookup needs no more rows than the decomposition to lay out `num_elements`.
+    pub fn prefers_lookup(&self, num_elements: usize) -> bool {
+        match &self.decomposed {
+            Some(decomposed) => self.lookup.rows(num_elements) <= decomposed.rows(num_elements),
+            None => true,
+        }
+    }
+}
+
+/// The [BaseOp]s that [BaseConfig::configure] creates selectors and gates for. Any other op has to be configured via
+/// [BaseConfig::configure_base_op] or [BaseConfig::configure_with_ops] before it is laid out.
+pub const DEFAULT_BASE_OPS: [BaseOp; 10] = [
+    BaseOp::Add,
+    BaseOp::Sub,
+    BaseOp::Dot,
+    BaseOp::CumProd,
+    BaseOp::Sum,
+    BaseOp::Neg,
+    BaseOp::Mult,
+    BaseOp::IsZero,
+    BaseOp::Identity,
+    BaseOp::IsBoolean,
+];
+
 /// Configuration for an accumulated arg.
 #[derive(Clone, Debug, Default)]
 pub struct BaseConfig<F: PrimeField + TensorType + PartialOrd> {
@@ -163,14 +391,29 @@ pub struct BaseConfig<F: PrimeField + TensorType + PartialOrd> {
     pub lookup_output: VarTensor,
     ///
     pub lookup_index: VarTensor,
+    /// When set, each [LookupOp] configured via [Self::configure_lookup] gets fresh input, output, and index columns
+    /// (shaped like the ones passed in) rather than sharing [Self::lookup_input], [Self::lookup_output], and [Self::lookup_index].
+    pub per_op_lookup_columns: bool,
+    /// The (input, output, index) columns of lookups configured with [Self::per_op_lookup_columns], see [Self::lookup_vars].
+    pub lookup_columns: BTreeMap<LookupOp, (VarTensor, VarTensor, VarTensor)>,
     /// [Selector]s generated when configuring the layer. We use a [BTreeMap] as we expect to configure [BaseOp].
     pub selectors: BTreeMap<(BaseOp, usize, usize), Selector>,
     /// [Selector]s generated when configuring the layer. We use a [BTreeMap] as we expect to configure many lookup ops.
     pub lookup_selectors: BTreeMap<(LookupOp, usize, usize), Selector>,
     ///
     pub tables: BTreeMap<LookupOp, Table<F>>,
+    /// [Selector]s generated when configuring lookups over two inputs.
+    pub binary_lookup_selectors: BTreeMap<(BinaryLookupOp, usize, usize), Selector>,
+    ///
+    pub binary_tables: BTreeMap<BinaryLookupOp, BinaryTable<F>>,
     /// Activate sanity checks
     pub check_mode: CheckMode,
+    /// Signed bit width that outputs are expected to fit in, checked in [CheckMode::SAFE]
+    signed_range_bits: Option<usize>,
+    /// Signed bit width that lookup table outputs must fit in, checked when the tables are laid out
+    lookup_output_bits: Option<usize>,
+    /// Tolerance that outputs are checked against in [CheckMode::RELAXED]
+    pub tolerance: Tolerance,
     _marker: PhantomData<F>,
 }
 
@@ -185,101 +428,446 @@ impl<F: PrimeField + TensorType + PartialOrd> BaseConfig<F> {
             output: dummy_var.clone(),
             lookup_output: dummy_var.clone(),
             lookup_index: dummy_var,
+            per_op_lookup_columns: false,
+            lookup_columns: BTreeMap::new(),
             selectors: BTreeMap::new(),
             lookup_selectors: BTreeMap::new(),
             tables: BTreeMap::new(),
+            binary_lookup_selectors: BTreeMap::new(),
+            binary_tables: BTreeMap::new(),
             check_mode: CheckMode::SAFE,
+            signed_range_bits: None,
+            lookup_output_bits: None,
+            tolerance: Tolerance::default(),
             _marker: PhantomData,
         }
     }
 
+    /// Snapshots the column layout of the config, see [BaseConfigLayout].
+    pub fn layout_snapshot(&self) -> BaseConfigLayout {
+        BaseConfigLayout {
+            inputs: self.inputs.iter().map(VarTensorLayout::from).collect(),
+            lookup_input: (&self.lookup_input).into(),
+            output: (&self.output).into(),
+            lookup_output: (&self.lookup_output).into(),
+            lookup_index: (&self.lookup_index).into(),
+            selectors: self.selectors.keys().cloned().collect(),
+            lookup_selectors: self.lookup_selectors.keys().cloned().collect(),
+            tables: self.tables.keys().cloned().collect(),
+            binary_tables: self.binary_tables.keys().cloned().collect(),
+            check_mode: self.check_mode,
+        }
+    }
+
+    /// Estimates the columns, selectors and table rows the config consumes, without touching the circuit.
+    /// Useful to size `logrows` before committing to it.
+    pub fn cost_estimate(&self) -> BaseConfigCost {
+        BaseConfigCost {
+            advice_columns: self.inputs.iter().map(|i| i.num_cols()).sum::<usize>()
+                + self.output.num_cols(),
+            selectors: self.selectors.len(),
+            lookup_selectors: self.lookup_selectors.len() + self.binary_lookup_selectors.len(),
+            table_rows: self.tables.values().map(|t| t.table_rows()).sum::<usize>()
+                + self
+                    .binary_tables
+                    .values()
+                    .map(|t| t.table_rows())
+                    .sum::<usize>(),
+        }
+    }
+
+    /// Reports the selectors, columns and table rows consumed by each configured op, for capacity planning.
+    pub fn report(&self) -> BaseConfigReport {
+        fn count<K: Ord + Clone>(keys: impl Iterator<Item = K>) -> BTreeMap<K, usize> {
+            let mut counts = BTreeMap::new();
+            for k in keys {
+                *counts.entry(k).or_insert(0) += 1;
+            }
+            counts
+        }
+
+        let mut ops = vec![];
+        for (op, selectors) in count(self.selectors.keys().map(|(op, _, _)| op.clone())) {
+            ops.push(OpReport {
+                name: op.as_string(),
+                selectors,
+                // each selector's gate queries the op's inputs and the output in its block
+                advice_columns: selectors * (op.num_inputs() + 1),
+                ..Default::default()
+            });
+        }
+        for (op, selectors) in count(self.lookup_selectors.keys().map(|(op, _, _)| op.clone())) {
+            let table = self.tables.get(&op);
+            ops.push(OpReport {
+                name: Op::<F>::as_string(&op),
+                selectors,
+                // input, output and index
+                advice_columns: selectors * 3,
+                table_columns: table.map_or(0, |t| t.table_inputs.len() + t.table_outputs.len()),
+                table_rows: table.map_or(0, |t| t.table_rows()),
+            });
+        }
+        for (op, selectors) in count(
+            self.binary_lookup_selectors
+                .keys()
+                .map(|(op, _, _)| op.clone()),
+        ) {
+            let table = self.binary_tables.get(&op);
+            ops.push(OpReport {
+                name: Op::<F>::as_string(&op),
+                selectors,
+                // lhs, rhs and output
+                advice_columns: selectors * 3,
+                table_columns: table.map_or(0, |_| 3),
+                table_rows: table.map_or(0, |t| t.table_rows()),
+            });
+        }
+
+        BaseConfigReport {
+            total_table_rows: ops.iter().map(|op| op.table_rows).sum(),
+            ops,
+        }
+    }
+
+    /// Estimates the cost of laying out `op` over `bits` bit signed inputs as a lookup, given the tables already
+    /// configured, and decomposed into [BaseOp]s where it can be. [LookupOp::RangeCheck] is unsigned over its own bits.
+    /// A decomposition witnesses the (offset) input's bits with [BaseOp::IsBoolean] and recomposes them with a
+    /// [BaseOp::Dot], so grows linearly with `bits`, whereas the lookup table grows exponentially.
+    /// Returns [CircuitError::InvalidSignedBits] if the table range can't be represented, see [TableRange::bounds].
+    pub fn estimate_op_cost(&self, op: &LookupOp, bits: usize) -> Result<OpCost, CircuitError> {
+        let range = match op {
+            LookupOp::RangeCheck { bits } => TableRange::Unsigned.bounds(*bits)?,
+            _ => TableRange::Signed.bounds(bits)?,
+        };
+
+        let lookup = if self.tables.contains_key(op) {
+            StrategyCost {
+                rows_per_element: 1,
+                ..Default::default()
+            }
+        } else {
+            let cols = Table::<F>::num_cols_required(range, self.output.col_size().max(1));
+            let input_cols = match self.can_reuse_table_inputs(op) {
+                Some(_) => 0,
+                None => cols,
+            };
+            StrategyCost {
+                rows_per_element: 1,
+                table_rows: (range.1 - range.0 + 1) as usize,
+                fixed_columns: input_cols + cols,
+            }
+        };
+
+        // the booleans, their recomposing dot product and its equality with the input
+        let recomposition = |bits: usize| 2 * bits + 1;
+        // signed inputs are offset by 2^(bits-1) first, the top bit of which is then the sign
+        let decomposed_rows = match op {
+            LookupOp::RangeCheck { bits } => Some(recomposition(*bits)),
+            // sign * x
+            LookupOp::ReLU => Some(1 + recomposition(bits) + 1),
+            // (2 * sign - 1) * x
+            LookupOp::Abs => Some(1 + recomposition(bits) + 3),
+            _ => None,
+        };
+
+        Ok(OpCost {
+            lookup,
+            decomposed: decomposed_rows.map(|rows_per_element| StrategyCost {
+                rows_per_element,
+                ..Default::default()
+            }),
+        })
+    }
+
+    /// Iterates over the configured [BaseOp]s, each keyed by the block and inner column its selector gates.
+    pub fn configured_base_ops(&self) -> impl Iterator<Item = &(BaseOp, usize, usize)> {
+        self.selectors.keys()
+    }
+
+    /// Iterates over the configured [LookupOp]s, each keyed by the block and inner column its selector gates.
+    pub fn configured_lookup_ops(&self) -> impl Iterator<Item = &(LookupOp, usize, usize)> {
+        self.lookup_selectors.keys()
+    }
+
     /// Configures [BaseOp]s for a given [ConstraintSystem].
     /// # Arguments
     /// * `meta` - The [ConstraintSystem] to configure the operations in.
-    /// * `inputs` - The explicit inputs to the operations.
+    /// * `inputs` - The explicit inputs to the operations, at least two. Ops query as many of these as their [BaseOp::num_inputs].
     /// * `output` - The variable representing the (currently singular) output of the operations.
     /// * `check_mode` - The variable representing the (currently singular) output of the operations.
+    ///
+    /// Returns [CircuitError::DimMismatch] if the inputs and output are not advice columns of the same shape.
     pub fn configure(
         meta: &mut ConstraintSystem<F>,
-        inputs: &[VarTensor; 2],
+        inputs: &[VarTensor],
         output: &VarTensor,
         check_mode: CheckMode,
-    ) -> Self {
+    ) -> Result<Self, CircuitError> {
+        Self::configure_with_ops(meta, inputs, output, check_mode, &DEFAULT_BASE_OPS)
+    }
+
+    /// Like [Self::configure] but only creates the selectors and gates of `ops`, rather than those of
+    /// [DEFAULT_BASE_OPS], shrinking the verifying key of circuits that only use a few ops.
+    /// Parameterized ops can be passed directly instead of through [Self::configure_base_op].
+    ///
+    /// Returns [CircuitError::DimMismatch] if the columns don't match as in [Self::configure], or if an op requires
+    /// more input columns than were passed.
+    pub fn configure_with_ops(
+        meta: &mut ConstraintSystem<F>,
+        inputs: &[VarTensor],
+        output: &VarTensor,
+        check_mode: CheckMode,
+        ops: &[BaseOp],
+    ) -> Result<Self, CircuitError> {
+        if inputs.len() < 2 {
+            return Err(CircuitError::DimMismatch(format!(
+                "base ops require at least 2 inputs, got {}",
+                inputs.len()
+            )));
+        }
+
+        if inputs.iter().any(|i| i.num_cols() != output.num_cols()) {
+            return Err(CircuitError::DimMismatch(format!(
+                "base op inputs have {} columns but the output has {}",
+                inputs
+                    .iter()
+                    .map(|i| i.num_cols().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" and "),
+                output.num_cols()
+            )));
+        }
+
+        // validate column shapes up front as the gates index into every input at the output's coordinates
+        for var in inputs.iter().chain([output]) {
+            if !matches!(var, VarTensor::Advice { .. }) {
+                return Err(CircuitError::DimMismatch(
+                    "base ops require advice columns".to_string(),
+                ));
+            }
+            if var.num_blocks() != output.num_blocks()
+                || var.num_inner_cols() != output.num_inner_cols()
+                || var.col_size() != output.col_size()
+            {
+                return Err(CircuitError::DimMismatch(
+                    "base op inputs and output have different column shapes".to_string(),
+                ));
+            }
+        }
+
+        if let Some(op) = ops.iter().find(|op| op.num_inputs() > inputs.len()) {
+            return Err(CircuitError::DimMismatch(format!(
+                "{} requires {} input columns",
+                op.as_str(),
+                op.num_inputs()
+            )));
+        }
+
         // setup a selector per base op
         let mut selectors = BTreeMap::new();
 
-        assert!(inputs[0].num_cols() == inputs[1].num_cols());
-        assert!(inputs[0].num_cols() == output.num_cols());
-
         for i in 0..output.num_blocks() {
             for j in 0..output.num_inner_cols() {
-                selectors.insert((BaseOp::Add, i, j), meta.selector());
-                selectors.insert((BaseOp::Sub, i, j), meta.selector());
-                selectors.insert((BaseOp::Dot, i, j), meta.selector());
-                selectors.insert((BaseOp::CumProd, i, j), meta.selector());
-                selectors.insert((BaseOp::Sum, i, j), meta.selector());
-                selectors.insert((BaseOp::Neg, i, j), meta.selector());
-                selectors.insert((BaseOp::Mult, i, j), meta.selector());
-                selectors.insert((BaseOp::IsZero, i, j), meta.selector());
-                selectors.insert((BaseOp::Identity, i, j), meta.selector());
-                selectors.insert((BaseOp::IsBoolean, i, j), meta.selector());
+                for op in ops {
+                    selectors
+                        .entry((op.clone(), i, j))
+                        .or_insert_with(|| meta.selector());
+                }
             }
         }
 
         for ((base_op, block_idx, inner_col_idx), selector) in selectors.iter() {
-            meta.create_gate(base_op.as_str(), |meta| {
-                let selector = meta.query_selector(*selector);
-                let mut qis = vec![Expression::<F>::zero().unwrap(); 2];
-                for (i, q_i) in qis
-                    .iter_mut()
-                    .enumerate()
-                    .take(2)
-                    .skip(2 - base_op.num_inputs())
-                {
-                    *q_i = inputs[i]
-                        .query_rng(meta, *block_idx, *inner_col_idx, 0, 1)
-                        .expect("accum: input query failed")[0]
-                        .clone()
-                }
-
-                // Get output expressions for each input channel
-                let (rotation_offset, rng) = base_op.query_offset_rng();
-
-                let constraints = match base_op {
-                    BaseOp::IsBoolean => {
-                        vec![(qis[1].clone()) * (qis[1].clone() - Expression::Constant(F::from(1)))]
-                    }
-                    BaseOp::IsZero => vec![qis[1].clone()],
-                    _ => {
-                        let expected_output: Tensor<Expression<F>> = output
-                            .query_rng(meta, *block_idx, *inner_col_idx, rotation_offset, rng)
-                            .expect("poly: output query failed");
-
-                        let res =
-                            base_op.f((qis[0].clone(), qis[1].clone(), expected_output[0].clone()));
-                        vec![expected_output[base_op.constraint_idx()].clone() - res]
-                    }
-                };
-
-                Constraints::with_selector(selector, constraints)
-            });
+            Self::create_gate(
+                meta,
+                inputs,
+                output,
+                base_op,
+                *block_idx,
+                *inner_col_idx,
+                *selector,
+            );
         }
 
-        Self {
+        Ok(Self {
             selectors,
             lookup_selectors: BTreeMap::new(),
             inputs: inputs.to_vec(),
             lookup_input: VarTensor::Empty,
             lookup_output: VarTensor::Empty,
             lookup_index: VarTensor::Empty,
+            per_op_lookup_columns: false,
+            lookup_columns: BTreeMap::new(),
             tables: BTreeMap::new(),
+            binary_lookup_selectors: BTreeMap::new(),
+            binary_tables: BTreeMap::new(),
             output: output.clone(),
             check_mode,
+            signed_range_bits: None,
+            lookup_output_bits: None,
+            tolerance: Tolerance::default(),
             _marker: PhantomData,
+        })
+    }
+
+    /// Like [Self::configure] but panics on mismatched columns.
+    pub fn configure_unchecked(
+        meta: &mut ConstraintSystem<F>,
+        inputs: &[VarTensor],
+        output: &VarTensor,
+        check_mode: CheckMode,
+    ) -> Self {
+        Self::configure(meta, inputs, output, check_mode)
+            .unwrap_or_else(|e| panic!("failed to configure base ops: {}", e))
+    }
+
+    /// Returns the minimal number of advice columns (blocks of [VarTensor::col_size] rows, see [VarTensor::max_rows]) needed
+    /// to lay out `num_constraints` cells at `logrows`. Each column after the first repeats the last cell of the previous one
+    /// so that accumulated ops can carry over, and so holds one fewer new cell.
+    /// Returns [CircuitError::DimMismatch] if `logrows` leaves fewer than 2 usable rows.
+    pub fn suggest_columns(
+        cs: &ConstraintSystem<F>,
+        num_constraints: usize,
+        logrows: usize,
+    ) -> Result<usize, CircuitError> {
+        let max_rows = 2usize
+            .checked_pow(logrows as u32)
+            .and_then(|rows| rows.checked_sub(cs.blinding_factors() + 1))
+            .filter(|rows| *rows >= 2)
+            .ok_or_else(|| {
+                CircuitError::DimMismatch(format!("logrows {} leaves no usable rows", logrows))
+            })?;
+
+        // n columns hold n * max_rows - (n - 1) cells
+        let carried = num_constraints.saturating_sub(1);
+        Ok(((carried + max_rows - 2) / (max_rows - 1)).max(1))
+    }
+
+    /// Creates the gate for an [AccumulatedOp] at a given block and inner column, gated by `selector`.
+    fn create_gate<O: AccumulatedOp>(
+        meta: &mut ConstraintSystem<F>,
+        inputs: &[VarTensor],
+        output: &VarTensor,
+        op: &O,
+        block_idx: usize,
+        inner_col_idx: usize,
+        selector: Selector,
+    ) {
+        meta.create_gate(op.name(), |meta| {
+            let selector = meta.query_selector(selector);
+            // ops with fewer than two inputs query the trailing of the first two input columns
+            let num_queried = op.num_inputs().max(2);
+            let mut qis = vec![Expression::<F>::zero().unwrap(); num_queried];
+            for (i, q_i) in qis
+                .iter_mut()
+                .enumerate()
+                .skip(num_queried - op.num_inputs())
+            {
+                *q_i = inputs[i]
+                    .query_rng(meta, block_idx, inner_col_idx, 0, 1)
+                    .expect("accum: input columns are validated in configure")[0]
+                    .clone()
+            }
+
+            // Get output expressions for each input channel
+            let (rotation_offset, rng) = op.query_offset_rng();
+            let expected_output: Tensor<Expression<F>> = output
+                .query_rng(meta, block_idx, inner_col_idx, rotation_offset, rng)
+                .expect("accum: output column is validated in configure");
+
+            Constraints::with_selector(selector, op.constraints(&qis, &expected_output))
+        });
+    }
+
+    /// Configures a [BaseOp] outside of [DEFAULT_BASE_OPS], eg. [BaseOp::Max] or a parameterized op such as [BaseOp::Clamp]
+    /// or [BaseOp::Mod], whose constants are folded into its gate. A parameterized op is configured for that one
+    /// constant, eg. [BaseOp::MultConst] with `c = 128` only folds multiplications by 128, see [Self::layout].
+    /// Does nothing if the op has already been configured.
+    /// Returns [CircuitError::DimMismatch] if fewer input columns than [BaseOp::num_inputs] were passed to [Self::configure],
+    /// eg. for [BaseOp::MulAdd].
+    pub fn configure_base_op(
+        &mut self,
+        meta: &mut ConstraintSystem<F>,
+        base_op: &BaseOp,
+    ) -> Result<(), CircuitError> {
+        if self.selectors.keys().any(|(op, _, _)| op == base_op) {
+            return Ok(());
+        }
+        if self.inputs.len() < base_op.num_inputs() {
+            return Err(CircuitError::DimMismatch(format!(
+                "{} requires {} input columns",
+                base_op.as_str(),
+                base_op.num_inputs()
+            )));
+        }
+
+        for i in 0..self.output.num_blocks() {
+            for j in 0..self.output.num_inner_cols() {
+                let selector = meta.selector();
+                Self::create_gate(meta, &self.inputs, &self.output, base_op, i, j, selector);
+                self.selectors.insert((base_op.clone(), i, j), selector);
+            }
+        }
+        Ok(())
+    }
+
+    /// Configures the gates of an [AccumulatedOp] defined outside of [BaseOp] over the columns passed to [Self::configure],
+    /// returning its [Selector]s keyed by block and inner column. Layouts enable these at the coordinates of the output
+    /// cells, see [VarTensor::cartesian_coord].
+    /// Returns [CircuitError::DimMismatch] if fewer input columns than [AccumulatedOp::num_inputs] were passed to [Self::configure].
+    pub fn configure_accumulated_op<O: AccumulatedOp>(
+        &self,
+        meta: &mut ConstraintSystem<F>,
+        op: &O,
+    ) -> Result<BTreeMap<(usize, usize), Selector>, CircuitError> {
+        if self.inputs.len() < op.num_inputs() {
+            return Err(CircuitError::DimMismatch(format!(
+                "{} requires {} input columns",
+                op.name(),
+                op.num_inputs()
+            )));
+        }
+
+        let mut selectors = BTreeMap::new();
+        for i in 0..self.output.num_blocks() {
+            for j in 0..self.output.num_inner_cols() {
+                let selector = meta.selector();
+                Self::create_gate(meta, &self.inputs, &self.output, op, i, j, selector);
+                selectors.insert((i, j), selector);
+            }
         }
+        Ok(selectors)
     }
 
-    /// Configures and creates lookup selectors
+    /// Configures a set of lookups which all share the same input, output and index columns, and the same table input columns.
+    /// Errors if a previously configured table covers a different range, as its input column would then hold the wrong defaults.
+    pub fn configure_lookups(
+        &mut self,
+        cs: &mut ConstraintSystem<F>,
+        input: &VarTensor,
+        output: &VarTensor,
+        index: &VarTensor,
+        lookup_range: (i128, i128),
+        logrows: usize,
+        nls: &[LookupOp],
+    ) -> Result<(), CircuitError>
+    where
+        F: Field,
+    {
+        if self
+            .tables
+            .values()
+            .any(|table| table.range != lookup_range)
+        {
+            return Err(CircuitError::LookupInstantiation);
+        }
+        for nl in nls {
+            self.configure_lookup(cs, input, output, index, lookup_range, logrows, nl)?;
+        }
+        Ok(())
+    }
+
+    /// Configures and creates lookup selectors.
+    /// `nl` is a [LookupOp], or a [LookupWithDefault] to override the pair that disabled rows are checked against.
     pub fn configure_lookup(
         &mut self,
         cs: &mut ConstraintSystem<F>,
@@ -288,33 +876,265 @@ impl<F: PrimeField + TensorType + PartialOrd> BaseConfig<F> {
         index: &VarTensor,
         lookup_range: (i128, i128),
         logrows: usize,
-        nl: &LookupOp,
-    ) -> Result<(), Box<dyn Error>>
+        nl: impl Into<LookupWithDefault>,
+    ) -> Result<(), CircuitError>
+    where
+        F: Field,
+    {
+        self.configure_lookup_inner(cs, input, output, index, lookup_range, logrows, nl, None)
+    }
+
+    /// Like [Self::configure_lookup] but shares the table with other configs through `registry`: if an identical
+    /// table has already been configured, its table columns are reused rather than allocating new ones, and it is
+    /// only laid out by the first config to call [Self::layout_tables].
+    /// As all tables of a config share input columns, a registered table is only reused by configs with no other
+    /// tables, or whose tables already share its inputs.
+    #[allow(clippy::too_many_arguments)]
+    pub fn configure_lookup_with_registry(
+        &mut self,
+        cs: &mut ConstraintSystem<F>,
+        input: &VarTensor,
+        output: &VarTensor,
+        index: &VarTensor,
+        lookup_range: (i128, i128),
+        logrows: usize,
+        nl: impl Into<LookupWithDefault>,
+        registry: &mut TableRegistry<F>,
+    ) -> Result<(), CircuitError>
+    where
+        F: Field,
+    {
+        self.configure_lookup_inner(
+            cs,
+            input,
+            output,
+            index,
+            lookup_range,
+            logrows,
+            nl,
+            Some(registry),
+        )
+    }
+
+    /// Configures a [LookupOp::RangeCheck] over exactly `[0, 2^bits)`, the smallest table that range-checks `bits` bit
+    /// values. Once configured, comparisons and other layouts that constrain a difference to be non-negative
+    /// (see [layouts::constrain_non_negative]) check it against this table rather than the ReLU lookup.
+    pub fn configure_range_lookup(
+        &mut self,
+        cs: &mut ConstraintSystem<F>,
+        input: &VarTensor,
+        output: &VarTensor,
+        index: &VarTensor,
+        bits: usize,
+        logrows: usize,
+    ) -> Result<(), CircuitError>
+    where
+        F: Field,
+    {
+        self.configure_lookup(
+            cs,
+            input,
+            output,
+            index,
+            TableRange::Unsigned.bounds(bits)?,
+            logrows,
+            &LookupOp::RangeCheck { bits },
+        )
+    }
+
+    /// Sets the shared lookup input and output columns before any lookup is configured, eg. for composite ops that
+    /// need a dedicated lookup column chosen up front. Lookups subsequently configured with [Self::configure_lookup]
+    /// query these rather than the input and output columns passed to it.
+    /// Errors with [CircuitError::LookupColumnsAlreadyAssigned] if either column has already been set.
+    pub fn with_lookup_columns(
+        mut self,
+        input: &VarTensor,
+        output: &VarTensor,
+    ) -> Result<Self, CircuitError> {
+        if !matches!(self.lookup_input, VarTensor::Empty)
+            || !matches!(self.lookup_output, VarTensor::Empty)
+        {
+            return Err(CircuitError::LookupColumnsAlreadyAssigned);
+        }
+        for var in [input, output] {
+            if !matches!(var, VarTensor::Advice { .. }) {
+                return Err(CircuitError::DimMismatch(
+                    "lookups require advice columns".to_string(),
+                ));
+            }
+        }
+        if input.num_blocks() != output.num_blocks()
+            || input.num_inner_cols() != output.num_inner_cols()
+        {
+            return Err(CircuitError::DimMismatch(
+                "lookup columns must have the same shape".to_string(),
+            ));
+        }
+
+        self.lookup_input = input.clone();
+        self.lookup_output = output.clone();
+        Ok(self)
+    }
+
+    /// Returns the (input, output, index) columns that `nl` is looked up from: its own columns if it was configured
+    /// with [Self::per_op_lookup_columns], the shared lookup columns otherwise.
+    pub fn lookup_vars(&self, nl: &LookupOp) -> (&VarTensor, &VarTensor, &VarTensor) {
+        match self.lookup_columns.get(nl) {
+            Some((input, output, index)) => (input, output, index),
+            None => (&self.lookup_input, &self.lookup_output, &self.lookup_index),
+        }
+    }
+
+    /// Returns the table input columns that configuring a lookup for `nl` would reuse, or `None` if it would
+    /// allocate new ones. As all tables of a config share their input columns, these are the inputs of any
+    /// already configured table. Returns `None` if `nl` is itself already configured, as there is nothing to allocate.
+    pub fn can_reuse_table_inputs(&self, nl: &LookupOp) -> Option<Vec<TableColumn>> {
+        if self.tables.contains_key(nl) {
+            return None;
+        }
+        self.tables
+            .values()
+            .next()
+            .map(|table| table.table_inputs.clone())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn configure_lookup_inner(
+        &mut self,
+        cs: &mut ConstraintSystem<F>,
+        input: &VarTensor,
+        output: &VarTensor,
+        index: &VarTensor,
+        lookup_range: (i128, i128),
+        logrows: usize,
+        nl: impl Into<LookupWithDefault>,
+        registry: Option<&mut TableRegistry<F>>,
+    ) -> Result<(), CircuitError>
     where
         F: Field,
     {
+        let nl: LookupWithDefault = nl.into();
+        let (nl, default_pair) = (&nl.op, nl.default_pair);
         let mut selectors = BTreeMap::new();
 
+        if lookup_range.0 > lookup_range.1 {
+            return Err(CircuitError::LookupInstantiation);
+        }
+        for var in [input, output, index] {
+            if !matches!(var, VarTensor::Advice { .. }) {
+                return Err(CircuitError::DimMismatch(
+                    "lookups require advice columns".to_string(),
+                ));
+            }
+        }
+        // once set, eg. by [Self::with_lookup_columns], the shared lookup columns are queried instead
+        let (preset_input, preset_output) = (self.lookup_input.clone(), self.lookup_output.clone());
+        let input = match &preset_input {
+            preset @ VarTensor::Advice { .. } => preset,
+            _ => input,
+        };
+        let output = match &preset_output {
+            preset @ VarTensor::Advice { .. } => preset,
+            _ => output,
+        };
+        if input.num_blocks() != index.num_blocks()
+            || input.num_inner_cols() != index.num_inner_cols()
+        {
+            return Err(CircuitError::DimMismatch(
+                "lookup columns must have the same shape".to_string(),
+            ));
+        }
+
+        // ranges whose width overflows can't be laid out at any logrows, so report them up front rather than panicking
+        let range_len = lookup_range
+            .1
+            .checked_sub(lookup_range.0)
+            .filter(|len| usize::try_from(*len).is_ok())
+            .ok_or(CircuitError::LookupBitWidthTooLarge {
+                bits: i128::BITS,
+                logrows,
+            })?;
+        let bits = u128::BITS - (range_len as u128).leading_zeros();
+        // the range is contiguous so it can be embedded in the field if its ends can
+        try_i128_to_felt::<F>(lookup_range.0)?;
+        try_i128_to_felt::<F>(lookup_range.1)?;
+        // disabled rows are only satisfied if the default pair is a row of the table
+        if let Some((x, y)) = default_pair {
+            let in_range = lookup_range.0 <= x && x <= lookup_range.1;
+            if !in_range || Self::lookup_output(nl, x)? != y {
+                return Err(CircuitError::LookupInstantiation);
+            }
+        }
+        let reserved_rows = cs.blinding_factors() + RESERVED_BLINDING_ROWS_PAD;
+        if 2usize.pow(logrows as u32) <= reserved_rows {
+            return Err(CircuitError::LookupBitWidthTooLarge { bits, logrows });
+        }
+
+        // a registered table can only be shared if its inputs are those of this config's tables
+        let shared = registry
+            .as_deref()
+            .and_then(|registry| registry.get(nl, lookup_range, logrows))
+            .filter(|shared| {
+                self.can_reuse_table_inputs(nl)
+                    .map_or(true, |inputs| inputs == shared.table_inputs)
+            })
+            .cloned();
+
         // we borrow mutably twice so we need to do this dance
 
-        let table = if !self.tables.contains_key(nl) {
-            // as all tables have the same input we see if there's another table who's input we can reuse
-            let table = if let Some(table) = self.tables.values().next() {
-                Table::<F>::configure(
-                    cs,
-                    lookup_range,
-                    logrows,
-                    nl,
-                    Some(table.table_inputs.clone()),
-                )
+        let mut table = if self.tables.contains_key(nl) {
+            return Ok(());
+        } else if let Some(shared) = shared {
+            shared
+        } else {
+            if let Some(table_inputs) = self.can_reuse_table_inputs(nl) {
+                let inputs_assigned = self
+                    .tables
+                    .values()
+                    .find(|table| table.table_inputs == table_inputs)
+                    .ok_or(CircuitError::LookupInstantiation)?
+                    .inputs_assigned
+                    .clone();
+                // errors if the reused columns can't hold the whole range
+                let mut table =
+                    Table::<F>::configure(cs, lookup_range, logrows, nl, Some(table_inputs))?;
+                table.inputs_assigned = inputs_assigned;
+                table
             } else {
-                Table::<F>::configure(cs, lookup_range, logrows, nl, None)
-            };
-            self.tables.insert(nl.clone(), table.clone());
-            table
+                Table::<F>::configure(cs, lookup_range, logrows, nl, None)?
+            }
+        };
+        table.default_pair = default_pair;
+        let per_op_columns = if self.per_op_lookup_columns {
+            let columns = (
+                input.new_advice_like(cs),
+                output.new_advice_like(cs),
+                index.new_advice_like(cs),
+            );
+            self.lookup_columns.insert(nl.clone(), columns.clone());
+            Some(columns)
         } else {
-            return Ok(());
+            None
+        };
+        let (input, output, index) = match &per_op_columns {
+            Some((input, output, index)) => (input, output, index),
+            None => (input, output, index),
         };
+        if let Some(registry) = registry {
+            // tables sharing input columns, within and across configs, only lay them out once
+            let inputs_assigned = table
+                .inputs_assigned
+                .clone()
+                .or_else(|| self.tables.values().find_map(|t| t.inputs_assigned.clone()))
+                .unwrap_or_default();
+            for t in self.tables.values_mut() {
+                t.inputs_assigned = Some(inputs_assigned.clone());
+            }
+            table.inputs_assigned = Some(inputs_assigned);
+            table.shared_assigned.get_or_insert_with(Default::default);
+            registry.register(logrows, &table);
+        }
+        self.tables.insert(nl.clone(), table.clone());
 
         for x in 0..input.num_blocks() {
             for y in 0..input.num_inner_cols() {
@@ -338,7 +1158,7 @@ impl<F: PrimeField + TensorType + PartialOrd> BaseConfig<F> {
                                 VarTensor::Advice { inner: advices, .. } => {
                                     cs.query_advice(advices[x][y], Rotation(0))
                                 }
-                                _ => panic!("wrong input type"),
+                                _ => unreachable!("lookup columns are checked to be advice"),
                             },
                         };
 
@@ -346,14 +1166,14 @@ impl<F: PrimeField + TensorType + PartialOrd> BaseConfig<F> {
                             VarTensor::Advice { inner: advices, .. } => {
                                 cs.query_advice(advices[x][y], Rotation(0))
                             }
-                            _ => panic!("wrong input type"),
+                            _ => unreachable!("lookup columns are checked to be advice"),
                         };
 
                         let output_query = match &output {
                             VarTensor::Advice { inner: advices, .. } => {
                                 cs.query_advice(advices[x][y], Rotation(0))
                             }
-                            _ => panic!("wrong input type"),
+                            _ => unreachable!("lookup columns are checked to be advice"),
                         };
 
                         // we index from 1 to avoid the zero element creating soundness issues
@@ -369,7 +1189,7 @@ impl<F: PrimeField + TensorType + PartialOrd> BaseConfig<F> {
 
                         let not_expr = Expression::Constant(multiplier) - col_expr.clone();
 
-                        let (default_x, default_y) = table.get_first_element(col_idx);
+                        let (default_x, default_y) = table.get_default_pair(col_idx);
 
                         log::trace!("---------------- col {:?} ------------------", col_idx,);
                         log::trace!("expr: {:?}", col_expr,);
@@ -398,6 +1218,9 @@ impl<F: PrimeField + TensorType + PartialOrd> BaseConfig<F> {
             }
         }
         self.lookup_selectors.extend(selectors);
+        if per_op_columns.is_some() {
+            return Ok(());
+        }
         // if we haven't previously initialized the input/output, do so now
         if let VarTensor::Empty = self.lookup_input {
             debug!("assigning lookup input");
@@ -414,38 +1237,197 @@ impl<F: PrimeField + TensorType + PartialOrd> BaseConfig<F> {
         Ok(())
     }
 
+    /// Configures a lookup of `op` over two inputs, backed by a [BinaryTable] over the cross product of their ranges.
+    /// The second operand is assigned to the lookup index column, which binary tables don't otherwise need.
+    #[allow(clippy::too_many_arguments)]
+    pub fn configure_binary_lookup(
+        &mut self,
+        cs: &mut ConstraintSystem<F>,
+        lhs: &VarTensor,
+        rhs: &VarTensor,
+        output: &VarTensor,
+        lhs_range: (i128, i128),
+        rhs_range: (i128, i128),
+        logrows: usize,
+        op: &BinaryLookupOp,
+    ) -> Result<(), CircuitError>
+    where
+        F: Field,
+    {
+        for var in [lhs, rhs, output] {
+            if !matches!(var, VarTensor::Advice { .. }) {
+                return Err(CircuitError::DimMismatch(
+                    "lookups require advice columns".to_string(),
+                ));
+            }
+        }
+        if self.binary_tables.contains_key(op) {
+            return Ok(());
+        }
+
+        let table = Table::<F>::configure_binary(cs, lhs_range, rhs_range, logrows, op)?;
+        let (default_lhs, default_rhs, default_output) = table.get_first_element();
+
+        let mut selectors = BTreeMap::new();
+        for x in 0..lhs.num_blocks() {
+            for y in 0..lhs.num_inner_cols() {
+                let selector = cs.complex_selector();
+
+                cs.lookup("", |cs| {
+                    let sel = cs.query_selector(selector);
+                    let not_sel = Expression::Constant(F::from(1)) - sel.clone();
+
+                    [
+                        (lhs, default_lhs, table.table_lhs),
+                        (rhs, default_rhs, table.table_rhs),
+                        (output, default_output, table.table_output),
+                    ]
+                    .into_iter()
+                    .map(|(var, default, col)| {
+                        let query = match var {
+                            VarTensor::Advice { inner: advices, .. } => {
+                                cs.query_advice(advices[x][y], Rotation(0))
+                            }
+                            _ => unreachable!("lookup columns are checked to be advice"),
+                        };
+                        // disabled rows are checked against the first row of the table
+                        (
+                            sel.clone() * query + not_sel.clone() * Expression::Constant(default),
+                            col,
+                        )
+                    })
+                    .collect()
+                });
+                selectors.insert((op.clone(), x, y), selector);
+            }
+        }
+        self.binary_lookup_selectors.extend(selectors);
+        self.binary_tables.insert(op.clone(), table);
+
+        if let VarTensor::Empty = self.lookup_input {
+            debug!("assigning lookup input");
+            self.lookup_input = lhs.clone();
+        }
+        if let VarTensor::Empty = self.lookup_output {
+            debug!("assigning lookup output");
+            self.lookup_output = output.clone();
+        }
+        if let VarTensor::Empty = self.lookup_index {
+            debug!("assigning lookup index");
+            self.lookup_index = rhs.clone();
+        }
+        Ok(())
+    }
+
     /// layout_tables must be called before layout.
+    /// Table values are computed in parallel, but assigned sequentially so that only the first table assigns the shared inputs.
     pub fn layout_tables(&mut self, layouter: &mut impl Layouter<F>) -> Result<(), Box<dyn Error>> {
-        for (i, table) in self.tables.values_mut().enumerate() {
-            if !table.is_assigned {
+        let evals = self
+            .tables
+            .values()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|table| match table.is_laid_out() {
+                true => Ok(None),
+                false => table.evaluate().map(Some),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (i, (table, evals)) in self.tables.values_mut().zip(evals).enumerate() {
+            if let Some(evals) = evals {
+                if let Some(bits) = self.lookup_output_bits {
+                    table.check_output_range(&evals.1, bits)?;
+                }
                 debug!(
                     "laying out table for {}",
                     crate::circuit::ops::Op::<F>::as_string(&table.nonlinearity)
                 );
-                if i == 0 {
-                    table.layout(layouter, false)?;
-                } else {
-                    table.layout(layouter, true)?;
-                }
+                let preassigned_input = !table.claim_inputs(i == 0);
+                table.layout_evaluated(layouter, preassigned_input, evals)?;
+            }
+        }
+        for table in self.binary_tables.values_mut() {
+            if !table.is_assigned {
+                table.layout(layouter)?;
             }
         }
         Ok(())
     }
 
+    /// The signed bit width that outputs are expected to fit in, see [Self::set_signed_range_bits].
+    pub fn signed_range_bits(&self) -> Option<usize> {
+        self.signed_range_bits
+    }
+
+    /// Sets the signed bit width that outputs are expected to fit in, checked in [CheckMode::SAFE] and used as the
+    /// bounds of the saturating ops. `None` disables the check.
+    /// Returns [CircuitError::InvalidSignedBits] unless the width is in `1..=128`.
+    pub fn set_signed_range_bits(&mut self, bits: Option<usize>) -> Result<(), CircuitError> {
+        if let Some(bits) = bits {
+            signed_bounds(bits)?;
+        }
+        self.signed_range_bits = bits;
+        Ok(())
+    }
+
+    /// The signed bit width that lookup table outputs must fit in, see [Self::set_lookup_output_bits].
+    pub fn lookup_output_bits(&self) -> Option<usize> {
+        self.lookup_output_bits
+    }
+
+    /// Sets the signed bit width that lookup table outputs must fit in, checked when the tables are laid out.
+    /// `None` disables the check.
+    /// Returns [CircuitError::InvalidSignedBits] unless the width is in `1..=128`.
+    pub fn set_lookup_output_bits(&mut self, bits: Option<usize>) -> Result<(), CircuitError> {
+        if let Some(bits) = bits {
+            signed_bounds(bits)?;
+        }
+        self.lookup_output_bits = bits;
+        Ok(())
+    }
+
+    /// Sets the [CheckMode] subsequent calls to [Self::layout] run with, returning the previous mode.
+    pub fn set_check_mode(&mut self, check_mode: CheckMode) -> CheckMode {
+        std::mem::replace(&mut self.check_mode, check_mode)
+    }
+
+    /// Runs `f` with the config in `check_mode`, restoring the previous mode afterwards, eg. to lay out the
+    /// first layer of a model in [CheckMode::SAFE] and the rest in [CheckMode::UNSAFE].
+    pub fn with_check_mode<T>(
+        &mut self,
+        check_mode: CheckMode,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let previous = self.set_check_mode(check_mode);
+        let res = f(self);
+        self.check_mode = previous;
+        res
+    }
+
     /// Assigns variables to the regions created when calling `configure`.
     /// # Arguments
     /// * `values` - The explicit values to the operations.
     /// * `layouter` - A Halo2 Layouter.
     /// * `op` - The operation being represented.
+    ///
+    /// A [PolyOp::Add] or [PolyOp::Mult] whose operand is a constant `c` is folded into the [BaseOp::AddConst] /
+    /// [BaseOp::MultConst] gate only if that gate was configured for exactly `c` via [Self::configure_base_op], so each
+    /// distinct constant needs its own gate and selector. Other constants are laid out as usual.
     pub fn layout(
         &mut self,
         region: &mut RegionCtx<F>,
         values: &[ValTensor<F>],
         op: Box<dyn Op<F>>,
     ) -> Result<Option<ValTensor<F>>, Box<dyn Error>> {
-        let res = op.layout(self, region, values)?;
+        let res = if let Some(folded) = self.fold_constant_operand(region, values, op.as_ref())? {
+            Some(folded)
+        } else if region.is_dummy() {
+            op.dummy_layout(self, region, values)?
+        } else {
+            op.layout(self, region, values)?
+        };
 
-        if matches!(&self.check_mode, CheckMode::SAFE) && !region.is_dummy() {
+        if !matches!(&self.check_mode, CheckMode::UNSAFE) && !region.is_dummy() {
             if let Some(claimed_output) = &res {
                 // during key generation this will be unknown vals so we use this as a flag to check
                 let mut is_assigned = !claimed_output.any_unknowns();
@@ -453,10 +1435,495 @@ impl<F: PrimeField + TensorType + PartialOrd> BaseConfig<F> {
                     is_assigned = is_assigned && !val.any_unknowns();
                 }
                 if is_assigned {
-                    op.safe_mode_check(claimed_output, values)?;
+                    match &self.check_mode {
+                        CheckMode::RELAXED => {
+                            self.check_tolerance(op.as_ref(), claimed_output, values)?
+                        }
+                        CheckMode::WARN => {
+                            Self::warn_on_mismatch(op.as_ref(), claimed_output, values)?
+                        }
+                        _ => op.safe_mode_check(claimed_output, values)?,
+                    }
+                    match self.check_signed_range(op.as_ref(), claimed_output) {
+                        Err(e) if matches!(&self.check_mode, CheckMode::WARN) => {
+                            log::warn!("{}", e)
+                        }
+                        res => res?,
+                    }
                 }
             }
         };
         Ok(res)
     }
+
+    /// Assigns an op with several outputs, eg. a quotient and a remainder, see [Op::layout_multi].
+    /// Unless in [CheckMode::UNSAFE], each output is checked exactly against [Op::f_multi]; in [CheckMode::WARN]
+    /// mismatches are logged rather than failing.
+    pub fn layout_multi(
+        &mut self,
+        region: &mut RegionCtx<F>,
+        values: &[ValTensor<F>],
+        op: Box<dyn Op<F>>,
+    ) -> Result<Vec<ValTensor<F>>, Box<dyn Error>> {
+        let res = op.layout_multi(self, region, values)?;
+
+        if !matches!(&self.check_mode, CheckMode::UNSAFE) && !region.is_dummy() {
+            // during key generation this will be unknown vals so we use this as a flag to check
+            let is_assigned = !res.iter().chain(values.iter()).any(|v| v.any_unknowns());
+            if is_assigned {
+                let references = Self::reference_outputs(op.as_ref(), values)?;
+                if references.len() != res.len() {
+                    return Err(Box::new(CircuitError::DimMismatch(op.as_string())));
+                }
+                for (i, (claimed, reference)) in res.iter().zip(references.iter()).enumerate() {
+                    if claimed.dims() != reference.dims() {
+                        return Err(Box::new(CircuitError::DimMismatch(format!(
+                            "{} (output {})",
+                            op.as_string(),
+                            i
+                        ))));
+                    }
+                    let claimed = claimed.get_int_evals()?;
+                    if let Some((index, (c, r))) = claimed
+                        .iter()
+                        .zip(reference.iter())
+                        .enumerate()
+                        .find(|(_, (c, r))| c != r)
+                    {
+                        let err = CircuitError::SafeModeMismatch {
+                            op: format!("{} (output {})", op.as_string(), i),
+                            index,
+                            claimed: *c,
+                            expected: *r,
+                        };
+                        match &self.check_mode {
+                            CheckMode::WARN => log::warn!("{}", err),
+                            _ => return Err(Box::new(err)),
+                        }
+                    }
+                }
+            }
+        }
+        Ok(res)
+    }
+
+    /// Lays out a [PolyOp::Add] or [PolyOp::Mult] with a single constant operand (eg. a scale multiplier)
+    /// through the cheaper [BaseOp::AddConst] / [BaseOp::MultConst] gate, which leaves the constant out of the advice columns.
+    /// Returns `None`, to lay out the op as usual, for any other op or operands, or if the folded gate for
+    /// this constant has not been configured via [Self::configure_base_op].
+    fn fold_constant_operand(
+        &self,
+        region: &mut RegionCtx<F>,
+        values: &[ValTensor<F>],
+        op: &dyn Op<F>,
+    ) -> Result<Option<ValTensor<F>>, Box<dyn Error>> {
+        let fold: fn(i128) -> BaseOp = match op.as_any().downcast_ref::<PolyOp<F>>() {
+            Some(PolyOp::Add) => |c| BaseOp::AddConst { c },
+            Some(PolyOp::Mult) => |c| BaseOp::MultConst { c },
+            _ => return Ok(None),
+        };
+        // skip scanning the operands if no folded gate of this kind was configured
+        let any_folded = self
+            .selectors
+            .keys()
+            .any(|(o, _, _)| std::mem::discriminant(o) == std::mem::discriminant(&fold(0)));
+        if values.len() != 2 || !any_folded {
+            return Ok(None);
+        }
+
+        // a fully constant operand whose elements all share one value
+        let constant = |v: &ValTensor<F>| -> Result<Option<i128>, Box<dyn Error>> {
+            if v.any_unknowns() || v.is_empty() || v.get_const_indices()?.len() != v.len() {
+                return Ok(None);
+            }
+            let evals = v.get_felt_evals()?;
+            let first = evals[0];
+            Ok(evals
+                .iter()
+                .all(|x| *x == first)
+                .then(|| felt_to_i128(first)))
+        };
+
+        for (i, j) in [(1, 0), (0, 1)] {
+            let c = match constant(&values[i])? {
+                Some(c) => c,
+                None => continue,
+            };
+            let (input, base_op) = (&values[j], fold(c));
+            let broadcasted_shape = get_broadcasted_shape(input.dims(), values[i].dims())?;
+            if broadcasted_shape != input.dims()
+                || !self.selectors.keys().any(|(o, _, _)| o == &base_op)
+            {
+                continue;
+            }
+            return Ok(Some(layouts::pairwise_const(
+                self,
+                region,
+                &[input.clone()],
+                base_op,
+            )?));
+        }
+        Ok(None)
+    }
+
+    /// Lays out the op named `op_name`, resolving it to an element-wise [PolyOp] (`add`, `sub`, `mult`, `neg`, `identity`)
+    /// or otherwise to a [LookupOp] (eg. `relu` or `sigmoid(scale=128)`), see [LookupOp::from_str].
+    /// Names are case insensitive. Returns [CircuitError::UnsupportedOp] for unknown names.
+    pub fn apply(
+        &mut self,
+        region: &mut RegionCtx<F>,
+        op_name: &str,
+        inputs: &[ValTensor<F>],
+    ) -> Result<Option<ValTensor<F>>, Box<dyn Error>>
+    where
+        F: Serialize + for<'de> Deserialize<'de>,
+    {
+        let op = Self::resolve_op(op_name)?;
+        self.layout(region, inputs, op)
+    }
+
+    /// Resolves an op name for [Self::apply].
+    fn resolve_op(op_name: &str) -> Result<Box<dyn Op<F>>, CircuitError>
+    where
+        F: Serialize + for<'de> Deserialize<'de>,
+    {
+        // only the name is case insensitive, parameter keys are kept as is
+        let split = op_name.find('(').unwrap_or(op_name.len());
+        let name = format!(
+            "{}{}",
+            op_name[..split].trim().to_uppercase(),
+            &op_name[split..]
+        );
+
+        let op: Box<dyn Op<F>> = match name.as_str() {
+            "ADD" => Box::new(PolyOp::<F>::Add),
+            "SUB" => Box::new(PolyOp::<F>::Sub),
+            "MULT" => Box::new(PolyOp::<F>::Mult),
+            "NEG" => Box::new(PolyOp::<F>::Neg),
+            "IDENTITY" => Box::new(PolyOp::<F>::Identity),
+            _ => Box::new(LookupOp::from_str(&name).map_err(|_| CircuitError::UnsupportedOp)?),
+        };
+        Ok(op)
+    }
+
+    /// Lays out the `n_bits` little-endian bit decomposition of a single element `value`.
+    /// Each bit is constrained to be boolean and `value == sum(bit_i * 2^i)`.
+    pub fn layout_bit_decomposition(
+        &mut self,
+        region: &mut RegionCtx<F>,
+        value: &ValTensor<F>,
+        n_bits: usize,
+    ) -> Result<ValTensor<F>, Box<dyn Error>> {
+        layouts::bit_decomposition(self, region, value, n_bits)
+    }
+
+    /// Constrains every element of `values` to be 0 or 1, firing the [BaseOp::IsBoolean] selector once per assigned cell.
+    /// Returns the assigned tensor with the shape of `values`.
+    pub fn layout_is_boolean(
+        &mut self,
+        region: &mut RegionCtx<F>,
+        values: &ValTensor<F>,
+    ) -> Result<ValTensor<F>, Box<dyn Error>> {
+        layouts::boolean_identity(self, region, &[values.clone()])
+    }
+
+    /// Lays out a parametric ReLU of `input`, with the (broadcastable, eg. per-channel) `slope` applied where the
+    /// input is not positive, see [layouts::prelu].
+    pub fn layout_prelu(
+        &mut self,
+        region: &mut RegionCtx<F>,
+        input: &ValTensor<F>,
+        slope: &ValTensor<F>,
+    ) -> Result<ValTensor<F>, Box<dyn Error>> {
+        layouts::prelu(self, region, &[input.clone(), slope.clone()])
+    }
+
+    /// Lays out a max pool over the last two dims of `input`, see [layouts::accumulated_max_pool] for how dims
+    /// that aren't a whole number of windows are handled.
+    pub fn layout_maxpool(
+        &mut self,
+        region: &mut RegionCtx<F>,
+        input: &ValTensor<F>,
+        window: (usize, usize),
+        stride: (usize, usize),
+    ) -> Result<ValTensor<F>, Box<dyn Error>> {
+        layouts::accumulated_max_pool(self, region, &[input.clone()], window, stride)
+    }
+
+    /// Lays out the argmax of the flattened `input`, with ties resolved to the lowest index, see [layouts::accumulated_argmax].
+    pub fn layout_argmax(
+        &mut self,
+        region: &mut RegionCtx<F>,
+        input: &ValTensor<F>,
+    ) -> Result<ValTensor<F>, Box<dyn Error>> {
+        layouts::accumulated_argmax(self, region, &[input.clone()])
+    }
+
+    /// Lays out the softmax of `input` at scale `scale`, shifted by its max for stability. The output is at
+    /// scale `scale^2`, see [layouts::stable_softmax] for the scale flow and the lookup ranges it requires.
+    pub fn layout_softmax(
+        &mut self,
+        region: &mut RegionCtx<F>,
+        input: &ValTensor<F>,
+        scale: utils::F32,
+    ) -> Result<ValTensor<F>, Box<dyn Error>> {
+        layouts::stable_softmax(self, region, &[input.clone()], scale)
+    }
+
+    /// Lays out `input` rescaled from `from_scale` to `to_scale`, saturating to [Self::signed_range_bits] if set,
+    /// see [layouts::saturating_rescale].
+    pub fn layout_rescale(
+        &mut self,
+        region: &mut RegionCtx<F>,
+        input: &ValTensor<F>,
+        from_scale: utils::F32,
+        to_scale: utils::F32,
+    ) -> Result<ValTensor<F>, Box<dyn Error>> {
+        let bits = self.signed_range_bits;
+        layouts::saturating_rescale(self, region, &[input.clone()], from_scale, to_scale, bits)
+    }
+
+    /// Lays out the product of the `[m, n]` `matrix` with the length `n` `vector` as one dot product per row,
+    /// see [layouts::matvec].
+    pub fn layout_matvec(
+        &mut self,
+        region: &mut RegionCtx<F>,
+        matrix: &ValTensor<F>,
+        vector: &ValTensor<F>,
+    ) -> Result<ValTensor<F>, Box<dyn Error>> {
+        layouts::matvec(self, region, &[matrix.clone(), vector.clone()])
+    }
+
+    /// Constrains `computed` to equal `instance` element-wise through the [BaseOp::Sub] gate,
+    /// see [layouts::enforce_zero_difference].
+    pub fn layout_enforce_equal(
+        &mut self,
+        region: &mut RegionCtx<F>,
+        computed: &ValTensor<F>,
+        instance: &ValTensor<F>,
+    ) -> Result<ValTensor<F>, Box<dyn Error>> {
+        layouts::enforce_zero_difference(self, region, &[computed.clone(), instance.clone()])
+    }
+
+    /// Lays out `a + b` saturating to the signed [Self::signed_range_bits] range, see [layouts::saturating_pairwise].
+    /// Requires the matching [BaseOp::SaturatingAdd] to have been configured via [Self::configure_base_op].
+    pub fn layout_saturating_add(
+        &mut self,
+        region: &mut RegionCtx<F>,
+        a: &ValTensor<F>,
+        b: &ValTensor<F>,
+    ) -> Result<ValTensor<F>, Box<dyn Error>> {
+        let (min, max) = self.saturation_bounds()?;
+        layouts::saturating_pairwise(
+            self,
+            region,
+            &[a.clone(), b.clone()],
+            BaseOp::SaturatingAdd { min, max },
+        )
+    }
+
+    /// Lays out `a - b` saturating to the signed [Self::signed_range_bits] range, see [layouts::saturating_pairwise].
+    /// Requires the matching [BaseOp::SaturatingSub] to have been configured via [Self::configure_base_op].
+    pub fn layout_saturating_sub(
+        &mut self,
+        region: &mut RegionCtx<F>,
+        a: &ValTensor<F>,
+        b: &ValTensor<F>,
+    ) -> Result<ValTensor<F>, Box<dyn Error>> {
+        let (min, max) = self.saturation_bounds()?;
+        layouts::saturating_pairwise(
+            self,
+            region,
+            &[a.clone(), b.clone()],
+            BaseOp::SaturatingSub { min, max },
+        )
+    }
+
+    /// The bounds of the saturating ops, errors if [Self::signed_range_bits] isn't set.
+    fn saturation_bounds(&self) -> Result<(i128, i128), CircuitError> {
+        self.signed_range_bits
+            .ok_or(CircuitError::UnsupportedOp)
+            .and_then(signed_bounds)
+    }
+
+    /// Lays out the convolution of the `[C, H, W]` `input` by the `[O, C, kh, kw]` `kernels`, as a matrix-vector
+    /// product per kernel over the windows of the padded input, see [layouts::im2col_conv].
+    pub fn layout_conv2d(
+        &mut self,
+        region: &mut RegionCtx<F>,
+        input: &ValTensor<F>,
+        kernels: &ValTensor<F>,
+        stride: (usize, usize),
+        padding: [(usize, usize); 2],
+    ) -> Result<ValTensor<F>, Box<dyn Error>> {
+        layouts::im2col_conv(
+            self,
+            region,
+            &[input.clone(), kernels.clone()],
+            padding,
+            stride,
+        )
+    }
+
+    /// Lays out `base^exp` element-wise as a running product over `exp` copies of `base`, see [layouts::accumulated_pow]
+    /// for its overflow behavior.
+    pub fn layout_pow(
+        &mut self,
+        region: &mut RegionCtx<F>,
+        base: &ValTensor<F>,
+        exp: usize,
+    ) -> Result<ValTensor<F>, Box<dyn Error>> {
+        layouts::accumulated_pow(self, region, &[base.clone()], exp)
+    }
+
+    /// Lays out the dot product of `values` continuing from the accumulator `acc`, see [layouts::dot_continue].
+    pub fn layout_dot_continue(
+        &mut self,
+        region: &mut RegionCtx<F>,
+        values: &[ValTensor<F>; 2],
+        acc: &ValTensor<F>,
+    ) -> Result<ValTensor<F>, Box<dyn Error>> {
+        layouts::dot_continue(self, region, values, acc)
+    }
+
+    /// Evaluates the lookup `nl` on the single input `x`.
+    fn lookup_output(nl: &LookupOp, x: i128) -> Result<i128, CircuitError> {
+        let output = Op::<F>::f(nl, &[Tensor::from(vec![i128_to_felt::<F>(x)].into_iter())])
+            .map_err(|_| CircuitError::LookupInstantiation)?;
+        Ok(felt_to_i128(output.output[0]))
+    }
+
+    /// Evaluates the op on the (known) input values, as a reference for the claimed output.
+    fn reference_output(
+        op: &dyn Op<F>,
+        values: &[ValTensor<F>],
+    ) -> Result<Tensor<i128>, Box<dyn Error>> {
+        let felt_evals = values
+            .iter()
+            .map(|v| {
+                let mut evals = v.get_felt_evals()?;
+                evals.reshape(v.dims());
+                Ok(evals)
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+        Ok(op.f(&felt_evals)?.output.map(felt_to_i128))
+    }
+
+    /// Evaluates every output of a multi-output op on the (known) input values, see [Op::f_multi].
+    fn reference_outputs(
+        op: &dyn Op<F>,
+        values: &[ValTensor<F>],
+    ) -> Result<Vec<Tensor<i128>>, Box<dyn Error>> {
+        let felt_evals = values
+            .iter()
+            .map(|v| {
+                let mut evals = v.get_felt_evals()?;
+                evals.reshape(v.dims());
+                Ok(evals)
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+        Ok(op
+            .f_multi(&felt_evals)?
+            .into_iter()
+            .map(|output| output.map(felt_to_i128))
+            .collect())
+    }
+
+    /// Logs a warning listing the indices at which the claimed output differs from the reference output, or their
+    /// shapes if these differ.
+    fn warn_on_mismatch(
+        op: &dyn Op<F>,
+        claimed_output: &ValTensor<F>,
+        values: &[ValTensor<F>],
+    ) -> Result<(), Box<dyn Error>> {
+        let reference = Self::reference_output(op, values)?;
+        if claimed_output.dims() != reference.dims() {
+            log::warn!(
+                "output of {} has shape {:?} but the reference output has shape {:?}",
+                op.as_string(),
+                claimed_output.dims(),
+                reference.dims()
+            );
+            return Ok(());
+        }
+        let claimed = claimed_output.get_int_evals()?;
+
+        let mismatches = claimed
+            .iter()
+            .zip(reference.iter())
+            .enumerate()
+            .filter(|(_, (c, r))| c != r)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+
+        if !mismatches.is_empty() {
+            log::warn!(
+                "output of {} does not match the reference output at indices {:?}",
+                op.as_string(),
+                mismatches
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks that the claimed output has the shape of the reference output, and is within [Self::tolerance] of it.
+    fn check_tolerance(
+        &self,
+        op: &dyn Op<F>,
+        claimed_output: &ValTensor<F>,
+        values: &[ValTensor<F>],
+    ) -> Result<(), Box<dyn Error>> {
+        let reference = Self::reference_output(op, values)?;
+        if claimed_output.dims() != reference.dims() {
+            return Err(Box::new(CircuitError::DimMismatch(op.as_string())));
+        }
+        let claimed = claimed_output.get_int_evals()?;
+
+        let within_tolerance = claimed
+            .iter()
+            .zip(reference.iter())
+            .all(|(c, r)| self.tolerance.accepts(*c, *r));
+
+        if within_tolerance {
+            Ok(())
+        } else {
+            Err(Box::new(CircuitError::ToleranceExceeded(op.as_string())))
+        }
+    }
+
+    /// Checks that the output of ops which can silently wrap around the field ([PolyOp::Sub], and
+    /// [PolyOp::Neg] of the most negative value) fits within [Self::signed_range_bits], if set.
+    fn check_signed_range(
+        &self,
+        op: &dyn Op<F>,
+        claimed_output: &ValTensor<F>,
+    ) -> Result<(), CircuitError> {
+        let bits = match self.signed_range_bits {
+            Some(bits) => bits,
+            None => return Ok(()),
+        };
+
+        let wraps = matches!(
+            op.as_any().downcast_ref::<PolyOp<F>>(),
+            Some(PolyOp::Sub | PolyOp::Neg)
+        );
+        if !wraps {
+            return Ok(());
+        }
+
+        let (min, max) = signed_bounds(bits)?;
+        let in_range = claimed_output
+            .get_int_evals()
+            .map_err(|_| CircuitError::SignedRangeOverflow(op.as_string(), bits))?
+            .iter()
+            .all(|x| (min..=max).contains(x));
+
+        if in_range {
+            Ok(())
+        } else {
+            Err(CircuitError::SignedRangeOverflow(op.as_string(), bits))
+        }
+    }
 }
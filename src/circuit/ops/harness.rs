@@ -0,0 +1,132 @@
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use halo2curves::bn256::Fr as Fp;
+
+use crate::{
+    circuit::{BaseConfig, CheckMode},
+    tensor::{Tensor, ValTensor, VarTensor},
+};
+
+use super::{lookup::LookupOp, region::RegionCtx, Op};
+
+/// log2 of the number of rows of the harness circuit.
+pub const HARNESS_LOGROWS: usize = 10;
+/// The range over which the lookups required by the op under test are configured.
+pub const HARNESS_LOOKUP_RANGE: (i128, i128) = (-256, 256);
+
+/// The shape of the harness circuit, see [BaseConfig::prove_op].
+#[derive(Clone, Debug, Default)]
+pub struct OpHarnessParams {
+    /// number of input columns, at least 2
+    pub num_inputs: usize,
+    /// lookups required by the op under test
+    pub lookups: Vec<LookupOp>,
+}
+
+/// A minimal circuit laying out a single op, see [BaseConfig::prove_op].
+#[derive(Clone)]
+struct OpHarness {
+    op: Box<dyn Op<Fp>>,
+    inputs: Vec<ValTensor<Fp>>,
+    expected: Tensor<i128>,
+    params: OpHarnessParams,
+}
+
+impl Circuit<Fp> for OpHarness {
+    type Config = BaseConfig<Fp>;
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = OpHarnessParams;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn params(&self) -> Self::Params {
+        self.params.clone()
+    }
+
+    fn configure_with_params(cs: &mut ConstraintSystem<Fp>, params: Self::Params) -> Self::Config {
+        let capacity = VarTensor::max_rows(cs, HARNESS_LOGROWS);
+        let inputs = (0..params.num_inputs.max(2))
+            .map(|_| VarTensor::new_advice(cs, HARNESS_LOGROWS, 1, capacity))
+            .collect::<Vec<_>>();
+        let output = VarTensor::new_advice(cs, HARNESS_LOGROWS, 1, capacity);
+        VarTensor::constant_cols(cs, HARNESS_LOGROWS, 1, false);
+
+        let mut config = BaseConfig::configure(cs, &inputs, &output, CheckMode::SAFE)
+            .expect("harness: columns have the same shape");
+        for nl in params.lookups.iter() {
+            config
+                .configure_lookup(
+                    cs,
+                    &inputs[1],
+                    &output,
+                    &inputs[0],
+                    HARNESS_LOOKUP_RANGE,
+                    HARNESS_LOGROWS,
+                    nl,
+                )
+                .expect("harness: lookup range fits at HARNESS_LOGROWS");
+        }
+        config
+    }
+
+    fn configure(_: &mut ConstraintSystem<Fp>) -> Self::Config {
+        unimplemented!("you should call configure_with_params instead")
+    }
+
+    fn synthesize(
+        &self,
+        mut config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        config
+            .layout_tables(&mut layouter)
+            .map_err(|_| Error::Synthesis)?;
+        layouter.assign_region(
+            || "op harness",
+            |region| {
+                let mut region = RegionCtx::new(region, 0, 1);
+                let output = config
+                    .layout(&mut region, &self.inputs, self.op.clone())
+                    .map_err(|_| Error::Synthesis)?
+                    .ok_or(Error::Synthesis)?;
+
+                if !output.any_unknowns() {
+                    let output = output.get_int_evals().map_err(|_| Error::Synthesis)?;
+                    assert_eq!(
+                        output.iter().collect::<Vec<_>>(),
+                        self.expected.iter().collect::<Vec<_>>(),
+                        "{} produced an unexpected output",
+                        self.op.as_string()
+                    );
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+impl BaseConfig<Fp> {
+    /// Lays out `op` over `inputs` in a minimal circuit, asserting that it outputs `expected` (compared element-wise)
+    /// and that the circuit is satisfied under [MockProver]. The lookups returned by [Op::required_lookups] are
+    /// configured over [HARNESS_LOOKUP_RANGE]. Panics on failure, as it's intended for tests.
+    pub fn prove_op(op: Box<dyn Op<Fp>>, inputs: &[ValTensor<Fp>], expected: &Tensor<i128>) {
+        let circuit = OpHarness {
+            params: OpHarnessParams {
+                num_inputs: inputs.len(),
+                lookups: op.required_lookups(),
+            },
+            op,
+            inputs: inputs.to_vec(),
+            expected: expected.clone(),
+        };
+
+        let prover = MockProver::run(HARNESS_LOGROWS as u32, &circuit, vec![])
+            .expect("harness: failed to synthesize");
+        prover.assert_satisfied();
+    }
+}
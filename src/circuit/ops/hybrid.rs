@@ -60,6 +60,32 @@ pub enum HybridOp {
         dim: usize,
         constant_idx: Option<Tensor<usize>>,
     },
+    /// Euclidean division by a positive divisor, outputting the quotient and then the remainder, see [Op::layout_multi].
+    /// Requires [crate::circuit::ops::base::BaseOp::Div] to have been configured via [crate::circuit::BaseConfig::configure_base_op].
+    DivMod,
+}
+
+/// Euclidean quotient and remainder of `a` by `b`, broadcast against each other.
+/// A zero divisor yields a zero quotient and `a` as the remainder, as in [layouts::div_rem].
+fn div_mod(a: &Tensor<i128>, b: &Tensor<i128>) -> Result<[Tensor<i128>; 2], TensorError> {
+    let shape = tensor::get_broadcasted_shape(a.dims(), b.dims())
+        .map_err(|_| TensorError::DimMismatch("divmod".to_string()))?;
+    let (a, b) = (a.expand(&shape)?, b.expand(&shape)?);
+
+    let (quotient, remainder): (Vec<i128>, Vec<i128>) = a
+        .iter()
+        .zip(b.iter())
+        .map(|(a, b)| match b {
+            0 => (0, *a),
+            _ => (a.div_euclid(*b), a.rem_euclid(*b)),
+        })
+        .unzip();
+    let mut quotient = Tensor::from(quotient.into_iter());
+    let mut remainder = Tensor::from(remainder.into_iter());
+    quotient.reshape(&shape);
+    remainder.reshape(&shape);
+
+    Ok([quotient, remainder])
 }
 
 impl<F: PrimeField + TensorType + PartialOrd> Op<F> for HybridOp {
@@ -245,6 +271,11 @@ impl<F: PrimeField + TensorType + PartialOrd> Op<F> for HybridOp {
                 let y = inputs[1].clone().map(|x| felt_to_i128(x));
                 tensor::ops::equals(&x, &y)?
             }
+            HybridOp::DivMod => {
+                let y = inputs[1].clone().map(|x| felt_to_i128(x));
+                let [quotient, _] = div_mod(&x, &y)?;
+                (quotient, vec![])
+            }
         };
 
         // convert back to felt
@@ -256,6 +287,20 @@ impl<F: PrimeField + TensorType + PartialOrd> Op<F> for HybridOp {
         })
     }
 
+    fn f_multi(&self, inputs: &[Tensor<F>]) -> Result<Vec<Tensor<F>>, TensorError> {
+        match self {
+            HybridOp::DivMod => {
+                let x = inputs[0].clone().map(|x| felt_to_i128(x));
+                let y = inputs[1].clone().map(|x| felt_to_i128(x));
+                Ok(div_mod(&x, &y)?
+                    .into_iter()
+                    .map(|t| t.map(|x| i128_to_felt(x)))
+                    .collect())
+            }
+            _ => Ok(vec![self.f(inputs)?.output]),
+        }
+    }
+
     fn as_string(&self) -> String {
         match self {
             HybridOp::ReduceMax { axes } => format!("REDUCEMAX (axes={:?})", axes),
@@ -286,6 +331,7 @@ impl<F: PrimeField + TensorType + PartialOrd> Op<F> for HybridOp {
             HybridOp::OneHot { dim, num_classes } => {
                 format!("ONEHOT (dim={}, num_classes={})", dim, num_classes)
             }
+            HybridOp::DivMod => "DIVMOD".into(),
         }
     }
 
@@ -370,9 +416,27 @@ impl<F: PrimeField + TensorType + PartialOrd> Op<F> for HybridOp {
             HybridOp::OneHot { dim, num_classes } => {
                 layouts::one_hot_axis(config, region, values[..].try_into()?, *num_classes, *dim)?
             }
+            HybridOp::DivMod => {
+                let [quotient, _] = layouts::div_rem(config, region, values[..].try_into()?)?;
+                quotient
+            }
         }))
     }
 
+    fn layout_multi(
+        &self,
+        config: &mut crate::circuit::BaseConfig<F>,
+        region: &mut RegionCtx<F>,
+        values: &[ValTensor<F>],
+    ) -> Result<Vec<ValTensor<F>>, Box<dyn std::error::Error>> {
+        match self {
+            HybridOp::DivMod => {
+                Ok(layouts::div_rem(config, region, values[..].try_into()?)?.to_vec())
+            }
+            _ => Ok(self.layout(config, region, values)?.into_iter().collect()),
+        }
+    }
+
     fn out_scale(&self, in_scales: Vec<crate::Scale>) -> crate::Scale {
         match self {
             HybridOp::Greater { .. }
@@ -391,7 +455,8 @@ impl<F: PrimeField + TensorType + PartialOrd> Op<F> for HybridOp {
         match self {
             HybridOp::ReduceMax { .. }
             | HybridOp::ReduceMin { .. }
-            | HybridOp::MaxPool2d { .. } => Op::<F>::required_lookups(&LookupOp::ReLU),
+            | HybridOp::MaxPool2d { .. }
+            | HybridOp::DivMod => Op::<F>::required_lookups(&LookupOp::ReLU),
             HybridOp::Softmax { scale, .. } => {
                 vec![
                     LookupOp::Exp { scale: *scale },
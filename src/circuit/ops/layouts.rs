@@ -20,8 +20,12 @@ use super::{
     region::RegionCtx,
 };
 use crate::{
-    circuit::{ops::base::BaseOp, utils},
-    fieldutils::i128_to_felt,
+    circuit::{
+        ops::base::BaseOp,
+        table::{signed_bounds, TableRange},
+        utils,
+    },
+    fieldutils::{felt_to_i128, i128_to_felt},
     tensor::{
         get_broadcasted_shape,
         ops::{accumulated, add, mult, sub},
@@ -30,7 +34,7 @@ use crate::{
 };
 
 use super::*;
-use crate::circuit::ops::lookup::LookupOp;
+use crate::circuit::ops::lookup::{BinaryLookupOp, LookupOp};
 
 ///
 pub fn overflowed_len(starting_idx: usize, mut total_len: usize, column_len: usize) -> usize {
@@ -151,6 +155,104 @@ pub fn dot<F: PrimeField + TensorType + PartialOrd>(
     Ok(last_elem)
 }
 
+/// Matrix-vector product accumulated layout, laying out a [dot] of each row of the `[m, n]` matrix `values[0]` with the
+/// length `n` vector `values[1]`. Every row reuses the config's [BaseOp::Dot] selectors, and the result has length `m`.
+pub fn matvec<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 2],
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let (matrix, vector) = (&values[0], &values[1]);
+    let (m, n) = match matrix.dims() {
+        [m, n] if vector.dims() == [*n] => (*m, *n),
+        _ => {
+            return Err(Box::new(TensorError::DimMismatch(format!(
+                "matvec of {:?} by {:?}",
+                matrix.dims(),
+                vector.dims()
+            ))))
+        }
+    };
+
+    // assign the vector once so that every row's copy is constrained to the same cells
+    let vector = identity(config, region, &[vector.clone()])?;
+
+    let mut res = vec![];
+    for i in 0..m {
+        let mut row = matrix.get_slice(&[i..i + 1, 0..n])?;
+        row.reshape(&[n])?;
+        let dot = dot(config, region, &[row, vector.clone()])?;
+        res.push(dot.get_inner_tensor()?[0].clone());
+    }
+
+    Ok(Tensor::from(res.into_iter()).into())
+}
+
+/// 2D convolution laid out by expanding the `[C, H, W]` image `values[0]` into a matrix of windows (im2col), which each
+/// `[C, kh, kw]` kernel of the `[O, C, kh, kw]` kernels `values[1]` is then applied to via [matvec]. The output is
+/// `[O, oh, ow]`. The padding zeros, laid out as in [conv], are constants and so drop out of the dot products.
+pub fn im2col_conv<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 2],
+    padding: [(usize, usize); 2],
+    stride: (usize, usize),
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let dim_mismatch = || -> Box<dyn Error> {
+        Box::new(TensorError::DimMismatch(format!(
+            "conv of {:?} by {:?} with stride {:?}",
+            values[0].dims(),
+            values[1].dims(),
+            stride
+        )))
+    };
+    let (c, h, w, num_kernels, kh, kw) = match (values[0].dims(), values[1].dims()) {
+        ([c, h, w], [o, kc, kh, kw]) if c == kc => (*c, *h, *w, *o, *kh, *kw),
+        _ => return Err(dim_mismatch()),
+    };
+    let (padded_h, padded_w) = (
+        h + padding[0].0 + padding[1].0,
+        w + padding[0].1 + padding[1].1,
+    );
+    if stride.0 == 0 || stride.1 == 0 || padded_h < kh || padded_w < kw {
+        return Err(dim_mismatch());
+    }
+    let (oh, ow) = (
+        (padded_h - kh) / stride.0 + 1,
+        (padded_w - kw) / stride.1 + 1,
+    );
+    let window_len = c * kh * kw;
+
+    // assign the image once so that every overlapping window is constrained to the same cells
+    let mut image = identity(config, region, &[values[0].clone()])?;
+    image.reshape(&[1, c, h, w])?;
+    image.pad(padding)?;
+
+    // one row per output pixel, holding its window across all channels
+    let mut windows = vec![];
+    for i in 0..oh {
+        for j in 0..ow {
+            let (row, col) = (i * stride.0, j * stride.1);
+            let mut window = image.get_slice(&[0..1, 0..c, row..row + kh, col..col + kw])?;
+            window.reshape(&[1, window_len])?;
+            windows.push(window);
+        }
+    }
+    let windows = concat(&windows, &0)?;
+
+    let mut res = vec![];
+    for o in 0..num_kernels {
+        let mut kernel = values[1].get_slice(&[o..o + 1, 0..c, 0..kh, 0..kw])?;
+        kernel.reshape(&[window_len])?;
+        let output = matvec(config, region, &[windows.clone(), kernel])?;
+        res.extend(output.get_inner_tensor()?.iter().cloned());
+    }
+
+    let mut output: ValTensor<F> = Tensor::from(res.into_iter()).into();
+    output.reshape(&[num_kernels, oh, ow])?;
+    Ok(output)
+}
+
 /// Einsum
 pub fn einsum<F: PrimeField + TensorType + PartialOrd>(
     config: &BaseConfig<F>,
@@ -966,6 +1068,32 @@ pub fn scatter_elements<F: PrimeField + TensorType + PartialOrd>(
     Ok(input)
 }
 
+/// Dot product layout that continues from a carried in single element accumulator `acc`, eg. the
+/// partial sum of a previous chunk, so that large dot products can be split across regions.
+/// The accumulator enters the first row as `acc * 1`, where the 1 is a constrained constant, so this requires constant columns.
+pub fn dot_continue<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 2],
+    acc: &ValTensor<F>,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    if acc.len() != 1 {
+        return Err(Box::new(TensorError::DimMismatch(
+            "dot accumulator".to_string(),
+        )));
+    }
+
+    let mut acc = acc.clone();
+    acc.flatten();
+    let one: ValTensor<F> = Tensor::from([ValType::Constant(F::ONE)].into_iter()).into();
+
+    let [mut a, mut b] = values.clone();
+    a.flatten();
+    b.flatten();
+
+    dot(config, region, &[acc.concat(a)?, one.concat(b)?])
+}
+
 /// sum accumulated layout
 pub fn sum<F: PrimeField + TensorType + PartialOrd>(
     config: &BaseConfig<F>,
@@ -1040,12 +1168,53 @@ pub fn sum<F: PrimeField + TensorType + PartialOrd>(
     Ok(last_elem)
 }
 
+/// sum accumulated layout with a single element `bias` added into the final accumulator cell.
+/// The bias takes the input slot of an extra [BaseOp::Sum] row, which is cheaper than a separate [BaseOp::Add] region.
+pub fn sum_with_bias<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 2],
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let (input, bias) = (&values[0], &values[1]);
+    if bias.len() != 1 {
+        return Err(Box::new(TensorError::DimMismatch("sum bias".to_string())));
+    }
+
+    let mut input = input.clone();
+    input.flatten();
+    let mut bias = bias.clone();
+    bias.flatten();
+
+    sum(config, region, &[input.concat(bias)?])
+}
+
 /// product accumulated layout
 pub fn prod<F: PrimeField + TensorType + PartialOrd>(
     config: &BaseConfig<F>,
     region: &mut RegionCtx<F>,
     values: &[ValTensor<F>; 1],
 ) -> Result<ValTensor<F>, Box<dyn Error>> {
+    prod_with_init(config, region, values, 1)
+}
+
+/// product accumulated layout where the running product starts at `init` rather than 1, eg. the
+/// fixed point scale so that the product stays in range.
+/// Requires [BaseOp::CumProdInit] to have been configured (see [BaseConfig::configure_base_op]) when `init != 1`.
+pub fn prod_with_init<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+    init: i128,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let first_op = if init == 1 {
+        BaseOp::Identity
+    } else {
+        BaseOp::CumProdInit { init }
+    };
+    if !region.is_dummy() && !config.selectors.keys().any(|(op, _, _)| op == &first_op) {
+        return Err(Box::new(CircuitError::UnsupportedOp));
+    }
+
     // time this entire function run
     let global_start = instant::Instant::now();
 
@@ -1068,7 +1237,11 @@ pub fn prod<F: PrimeField + TensorType + PartialOrd>(
     };
 
     // Now we can assign the dot product
-    let accumulated_prod = accumulated::prod(&input).expect("accum poly: prod op failed");
+    let mut accumulated_prod = accumulated::prod(&input).expect("accum poly: prod op failed");
+    if init != 1 {
+        let init = Value::known(i128_to_felt::<F>(init));
+        accumulated_prod = accumulated_prod.map(|x| x * init);
+    }
 
     let (output, output_assigned_len) = region.assign_with_duplication(
         &config.output,
@@ -1087,7 +1260,7 @@ pub fn prod<F: PrimeField + TensorType + PartialOrd>(
                 return;
             }
             let selector = if i == 0 {
-                config.selectors.get(&(BaseOp::Identity, x, y))
+                config.selectors.get(&(first_op.clone(), x, y))
             } else {
                 config.selectors.get(&(BaseOp::CumProd, x, y))
             };
@@ -1410,6 +1583,44 @@ pub fn pairwise<F: PrimeField + TensorType + PartialOrd>(
     Ok(output)
 }
 
+/// Element-wise `x + c` ([BaseOp::AddConst]) or `x * c` ([BaseOp::MultConst]) with the constant `c` folded
+/// into the gate, so that only `x` and the output are assigned.
+/// Requires `op` to have been configured via [BaseConfig::configure_base_op].
+pub fn pairwise_const<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+    op: BaseOp,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let c = match op {
+        BaseOp::AddConst { c } | BaseOp::MultConst { c } => Value::known(i128_to_felt::<F>(c)),
+        _ => return Err(Box::new(CircuitError::UnsupportedOp)),
+    };
+    if !region.is_dummy() && !config.selectors.keys().any(|(o, _, _)| o == &op) {
+        return Err(Box::new(CircuitError::UnsupportedOp));
+    }
+
+    let input = region.assign(&config.inputs[1], &values[0])?;
+    let op_result = input.get_inner()?.map(|x| match op {
+        BaseOp::AddConst { .. } => x + c,
+        _ => x * c,
+    });
+    let output = region.assign(&config.output, &op_result.into())?;
+
+    // Enable the selectors
+    if !region.is_dummy() {
+        (0..output.len()).for_each(|i| {
+            let (x, y, z) = config.inputs[1].cartesian_coord(region.linear_coord() + i);
+            let selector = config.selectors.get(&(op.clone(), x, y));
+
+            region.enable(selector, z).unwrap();
+        });
+    }
+    region.increment(output.len());
+
+    Ok(output)
+}
+
 ///
 pub fn greater<F: PrimeField + TensorType + PartialOrd>(
     config: &BaseConfig<F>,
@@ -1632,161 +1843,1434 @@ pub fn neg<F: PrimeField + TensorType + PartialOrd>(
     Ok(output)
 }
 
-/// Sumpool accumulated layout
-pub fn sumpool<F: PrimeField + TensorType + PartialOrd>(
+/// Lays out `op` element-wise, with the witness assigned to the output column one row above the
+/// inputs and output (as expected by ops with a query offset of -1). Like the gate, the inputs
+/// fill the last `op.num_inputs()` input columns.
+/// Returns the assigned inputs, output, and witness.
+#[allow(clippy::type_complexity)]
+fn rotated_elementwise<F: PrimeField + TensorType + PartialOrd>(
     config: &BaseConfig<F>,
     region: &mut RegionCtx<F>,
-    values: &[ValTensor<F>],
-    padding: [(usize, usize); 2],
-    stride: (usize, usize),
-    kernel_shape: (usize, usize),
-) -> Result<ValTensor<F>, Box<dyn Error>> {
-    let batch_size = values[0].dims()[0];
-    let image_channels = values[0].dims()[1];
-
-    let unit = region.assign_constant(&config.inputs[1], F::from(1))?;
-    region.next();
+    inputs: &[ValTensor<F>],
+    output: &ValTensor<F>,
+    witness: &ValTensor<F>,
+    op: BaseOp,
+) -> Result<(Vec<ValTensor<F>>, ValTensor<F>, ValTensor<F>), Box<dyn Error>> {
+    let len = output.len();
+    if inputs.len() != op.num_inputs() || inputs.iter().chain([witness]).any(|v| v.len() != len) {
+        return Err(Box::new(CircuitError::DimMismatch(format!(
+            "rotated {} layout",
+            op.as_str()
+        ))));
+    }
 
-    let mut kernel = Tensor::from(0..kernel_shape.0 * kernel_shape.1).map(|_| unit.clone());
-    kernel.reshape(&[1, 1, kernel_shape.0, kernel_shape.1]);
+    let offset = config.inputs.len() - inputs.len();
+    let mut assigned_inputs = vec![vec![]; inputs.len()];
+    let mut assigned_output = vec![];
+    let mut assigned_witness = vec![];
+    for i in 0..len {
+        // hop over the last row of a column so the witness and the gate share a column
+        let (_, _, z) = config.output.cartesian_coord(region.linear_coord() + 1);
+        if z == 0 {
+            region.next();
+        }
 
-    let cartesian_coord = [(0..batch_size), (0..image_channels)]
-        .iter()
-        .cloned()
-        .multi_cartesian_product()
-        .collect::<Vec<_>>();
+        let w = region.assign(&config.output, &witness.get_single_elem(i)?)?;
+        assigned_witness.push(w.get_inner_tensor()?.get_flat_index(0));
+        region.next();
 
-    let mut res = vec![];
+        for (j, input) in inputs.iter().enumerate() {
+            let inp = region.assign(&config.inputs[offset + j], &input.get_single_elem(i)?)?;
+            assigned_inputs[j].push(inp.get_inner_tensor()?.get_flat_index(0));
+        }
+        let out = region.assign(&config.output, &output.get_single_elem(i)?)?;
+        assigned_output.push(out.get_inner_tensor()?.get_flat_index(0));
 
-    cartesian_coord.iter().for_each(|coord| {
-        let (b, i) = (coord[0], coord[1]);
-        let input = values[0].get_slice(&[b..b + 1, i..i + 1]).unwrap();
-        let output = conv(
-            config,
-            region,
-            &[input, kernel.clone().into()],
-            padding,
-            stride,
-        )
-        .unwrap();
-        res.push(output);
-    });
+        if !region.is_dummy() {
+            let (x, y, z) = config.output.cartesian_coord(region.linear_coord());
+            let selector = config.selectors.get(&(op.clone(), x, y));
+            region.enable(selector, z)?;
+        }
+        region.next();
+    }
 
-    let shape = &res[0].dims()[2..];
-    let mut last_elem = res[1..].iter().fold(res[0].clone(), |acc, elem| {
-        acc.concat(elem.clone()).unwrap()
-    });
-    last_elem.reshape(&[&[batch_size, image_channels], shape].concat())?;
+    let to_valtensor = |v: Vec<ValType<F>>| -> Result<ValTensor<F>, Box<dyn Error>> {
+        let mut v: ValTensor<F> = Tensor::from(v.into_iter()).into();
+        v.reshape(output.dims())?;
+        Ok(v)
+    };
 
-    Ok(last_elem)
+    Ok((
+        assigned_inputs
+            .into_iter()
+            .map(to_valtensor)
+            .collect::<Result<Vec<_>, _>>()?,
+        to_valtensor(assigned_output)?,
+        to_valtensor(assigned_witness)?,
+    ))
 }
 
-/// Convolution accumulated layout
-pub fn max_pool2d<F: PrimeField + TensorType + PartialOrd>(
+/// Constrains every element of the input to be non-negative, by looking it up in the range check table if one
+/// is configured (see [BaseConfig::configure_range_lookup]), or by checking that it is left unchanged by the
+/// ReLU lookup otherwise. Only sound for values within the range of the table used.
+pub fn constrain_non_negative<F: PrimeField + TensorType + PartialOrd>(
     config: &BaseConfig<F>,
     region: &mut RegionCtx<F>,
     values: &[ValTensor<F>; 1],
-    padding: [(usize, usize); 2],
-    stride: (usize, usize),
-    pool_dims: (usize, usize),
-) -> Result<ValTensor<F>, Box<dyn Error>> {
-    let image = values[0].clone();
-
-    if image.dims().len() != 4 {
-        return Err(Box::new(TensorError::DimMismatch("max_pool2d".to_string())));
+) -> Result<(), Box<dyn Error>> {
+    // a range check is a single lookup, whereas the ReLU needs an extra equality constraint
+    let range_check = config
+        .tables
+        .keys()
+        .find(|nl| matches!(nl, LookupOp::RangeCheck { .. }))
+        .cloned();
+    if let Some(range_check) = range_check {
+        nonlinearity(config, region, values, &range_check)?;
+        return Ok(());
     }
-    let image_dims = image.dims();
-
-    let (batch, input_channels, image_height, image_width) =
-        (image_dims[0], image_dims[1], image_dims[2], image_dims[3]);
-
-    let mut padded_image = image.clone();
-    padded_image.pad(padding)?;
+    let relu = nonlinearity(config, region, values, &LookupOp::ReLU)?;
+    enforce_equality(config, region, &[values[0].clone(), relu])?;
+    Ok(())
+}
 
-    let vert_slides = (image_height + padding[0].0 + padding[1].0 - pool_dims.0) / stride.0 + 1;
-    let horz_slides = (image_width + padding[0].1 + padding[1].1 - pool_dims.1) / stride.1 + 1;
+/// Constrains every element of the (possibly negative) input to lie within a table domain, so that it can't wrap
+/// around the field. Offsets the input by `2^(bits-1)` and looks it up in the range check table if one is configured,
+/// bounding it to `[-2^(bits-1), 2^(bits-1))`, or looks it up in the ReLU table otherwise, bounding it to that
+/// table's domain.
+pub fn constrain_bounded<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+) -> Result<(), Box<dyn Error>> {
+    let range_check = config
+        .tables
+        .keys()
+        .find(|nl| matches!(nl, LookupOp::RangeCheck { .. }))
+        .cloned();
+    if let Some(LookupOp::RangeCheck { bits }) = range_check {
+        let (min, _) = signed_bounds(bits)?;
+        let offset: ValTensor<F> =
+            Tensor::from([ValType::Constant(i128_to_felt::<F>(-min))].into_iter()).into();
+        let shifted = pairwise(config, region, &[values[0].clone(), offset], BaseOp::Add)?;
+        nonlinearity(config, region, &[shifted], &LookupOp::RangeCheck { bits })?;
+        return Ok(());
+    }
+    // the output is unconstrained, the lookup itself bounds the input
+    nonlinearity(config, region, values, &LookupOp::ReLU)?;
+    Ok(())
+}
 
-    let mut output: Tensor<ValType<F>> =
-        Tensor::new(None, &[batch, input_channels, horz_slides, vert_slides])?;
+/// Integer division of `values[0]` by a positive `values[1]`, returning `[quotient, remainder]`.
+/// The quotient and remainder are witnessed such that `numerator = quotient * divisor + remainder`
+/// and `0 <= remainder < divisor`, with the quotient bounded by [constrain_bounded] so that the
+/// product can't wrap around the field.
+///
+/// Requires [BaseOp::Div] to have been configured via [BaseConfig::configure_base_op].
+pub fn div_rem<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 2],
+) -> Result<[ValTensor<F>; 2], Box<dyn Error>> {
+    let (mut numerator, mut divisor) = (values[0].clone(), values[1].clone());
 
-    let cartesian_coord = [
-        (0..batch),
-        (0..input_channels),
-        (0..vert_slides),
-        (0..horz_slides),
-    ]
-    .iter()
-    .cloned()
-    .multi_cartesian_product()
-    .collect::<Vec<_>>();
+    let broadcasted_shape = get_broadcasted_shape(numerator.dims(), divisor.dims())?;
+    numerator.expand(&broadcasted_shape)?;
+    divisor.expand(&broadcasted_shape)?;
 
-    output.iter_mut().enumerate().for_each(|(flat_index, o)| {
-        let coord = &cartesian_coord[flat_index];
-        let (b, i, j, k) = (coord[0], coord[1], coord[2], coord[3]);
-        let rs = j * stride.0;
-        let cs = k * stride.1;
-        let slice = padded_image
-            .get_slice(&[
-                b..(b + 1),
-                i..(i + 1),
-                rs..(rs + pool_dims.0),
-                cs..(cs + pool_dims.1),
-            ])
-            .unwrap();
-        let max_w = max(config, region, &[slice]).unwrap();
-        *o = max_w.get_inner_tensor().unwrap()[0].clone();
-    });
+    // this is safe because we later constrain it
+    let (quotient, remainder): (Vec<ValType<F>>, Vec<ValType<F>>) = numerator
+        .get_inner_tensor()?
+        .iter()
+        .zip(divisor.get_inner_tensor()?.iter())
+        .map(|(n, d)| match (n.get_felt_eval(), d.get_felt_eval()) {
+            (Some(n), Some(d)) => {
+                let (n, d) = (felt_to_i128(n), felt_to_i128(d));
+                // a zero divisor can't satisfy the remainder bound, so any witness will do
+                let (q, r) = if d == 0 {
+                    (0, n)
+                } else {
+                    (n.div_euclid(d), n.rem_euclid(d))
+                };
+                (
+                    Value::known(i128_to_felt::<F>(q)).into(),
+                    Value::known(i128_to_felt::<F>(r)).into(),
+                )
+            }
+            _ => (Value::<F>::unknown().into(), Value::<F>::unknown().into()),
+        })
+        .unzip();
 
-    let res: ValTensor<F> = output.into();
+    let mut quotient: ValTensor<F> = Tensor::from(quotient.into_iter()).into();
+    let mut remainder: ValTensor<F> = Tensor::from(remainder.into_iter()).into();
+    quotient.reshape(&broadcasted_shape)?;
+    remainder.reshape(&broadcasted_shape)?;
 
-    Ok(res)
+    div_rem_witnessed(config, region, &[numerator, divisor, quotient, remainder])
 }
 
-/// DeConvolution accumulated layout
-pub fn deconv<F: PrimeField + TensorType + PartialOrd + std::marker::Send + std::marker::Sync>(
+/// Integer division with a caller supplied quotient and remainder, passed as
+/// `[numerator, divisor, quotient, remainder]`. Returns the assigned `[quotient, remainder]`.
+pub(crate) fn div_rem_witnessed<F: PrimeField + TensorType + PartialOrd>(
     config: &BaseConfig<F>,
     region: &mut RegionCtx<F>,
-    inputs: &[ValTensor<F>],
-    padding: [(usize, usize); 2],
-    output_padding: (usize, usize),
-    stride: (usize, usize),
-) -> Result<ValTensor<F>, Box<dyn Error>> {
-    let has_bias = inputs.len() == 3;
-    let (image, kernel) = (&inputs[0], &inputs[1]);
+    values: &[ValTensor<F>; 4],
+) -> Result<[ValTensor<F>; 2], Box<dyn Error>> {
+    let (numerator, divisor, quotient, remainder) =
+        (&values[0], &values[1], &values[2], &values[3]);
 
-    if (image.dims().len() != 4) || (kernel.dims().len() != 4) {
-        return Err(Box::new(TensorError::DimMismatch("deconv".to_string())));
-    }
+    // numerator = quotient * divisor + remainder
+    let (inputs, _, remainder) = rotated_elementwise(
+        config,
+        region,
+        &[quotient.clone(), divisor.clone()],
+        numerator,
+        remainder,
+        BaseOp::Div,
+    )?;
+    let (quotient, divisor) = (inputs[0].clone(), inputs[1].clone());
 
-    if stride.0 == 0 || stride.1 == 0 {
-        return Err(Box::new(TensorError::DimMismatch(
-            "non-positive stride is not supported for deconv".to_string(),
-        )));
-    }
+    // otherwise any remainder in range passes along with the field quotient (numerator - remainder) / divisor
+    constrain_bounded(config, region, &[quotient.clone()])?;
 
-    if has_bias {
-        let bias = &inputs[2];
-        if (bias.dims().len() != 1) || (bias.dims()[0] != kernel.dims()[0]) {
-            return Err(Box::new(TensorError::DimMismatch(
-                "deconv bias".to_string(),
-            )));
-        }
-    }
+    // 0 <= remainder
+    constrain_non_negative(config, region, &[remainder.clone()])?;
 
-    let (kernel_height, kernel_width) = (kernel.dims()[2], kernel.dims()[3]);
+    let unit: ValTensor<F> =
+        Tensor::from(vec![region.assign_constant(&config.inputs[1], F::from(1))?].into_iter())
+            .into();
+    region.next();
 
-    let null_val = ValType::Constant(F::ZERO);
-    // region.assign_constant(&config.inputs[1], F::from(0))?;
-    // region.next();
+    // remainder < divisor <=> 0 <= divisor - remainder - 1
+    let slack = pairwise(config, region, &[divisor, remainder.clone()], BaseOp::Sub)?;
+    let slack = pairwise(config, region, &[slack, unit], BaseOp::Sub)?;
+    constrain_non_negative(config, region, &[slack])?;
 
-    let mut expanded_image = image.clone();
-    expanded_image.intercalate_values(null_val.clone(), stride.0, 2)?;
-    expanded_image.intercalate_values(null_val, stride.1, 3)?;
-    expanded_image.pad([(kernel_height - 1, kernel_width - 1); 2])?;
+    Ok([quotient, remainder])
+}
 
-    // flip order
-    let channel_coord = (0..kernel.dims()[0])
-        .cartesian_product(0..kernel.dims()[1])
+/// Element-wise zero indicator, returned as `[is_zero, inverse]` where `inverse` is the witnessed
+/// inverse of each nonzero input (and 0 otherwise), so that later ops can reuse it.
+/// Constrains `x * inverse = 1 - is_zero` and `x * is_zero = 0`.
+///
+/// Requires [BaseOp::IsZeroInv] to have been configured via [BaseConfig::configure_base_op].
+pub fn is_zero<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+) -> Result<[ValTensor<F>; 2], Box<dyn Error>> {
+    // this is safe because we later constrain it
+    let (inverse, indicator): (Vec<ValType<F>>, Vec<ValType<F>>) = values[0]
+        .get_inner_tensor()?
+        .iter()
+        .map(|x| match x.get_felt_eval() {
+            Some(x) => (
+                Value::known(x.invert().unwrap_or(F::ZERO)).into(),
+                Value::known(if x == F::ZERO { F::ONE } else { F::ZERO }).into(),
+            ),
+            None => (Value::<F>::unknown().into(), Value::<F>::unknown().into()),
+        })
+        .unzip();
+
+    let mut inverse: ValTensor<F> = Tensor::from(inverse.into_iter()).into();
+    let mut indicator: ValTensor<F> = Tensor::from(indicator.into_iter()).into();
+    inverse.reshape(values[0].dims())?;
+    indicator.reshape(values[0].dims())?;
+
+    is_zero_witnessed(config, region, &[values[0].clone(), inverse, indicator])
+}
+
+/// Element-wise zero indicator with a caller supplied inverse and indicator, passed as
+/// `[x, inverse, is_zero]`. See [is_zero].
+pub(crate) fn is_zero_witnessed<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 3],
+) -> Result<[ValTensor<F>; 2], Box<dyn Error>> {
+    let (x, inverse, indicator) = (&values[0], &values[1], &values[2]);
+    let op = BaseOp::IsZeroInv;
+
+    if inverse.len() != x.len() || indicator.len() != x.len() {
+        return Err(Box::new(CircuitError::DimMismatch(format!(
+            "{} layout",
+            op.as_str()
+        ))));
+    }
+
+    region.assign(&config.inputs[0], x)?;
+    let inverse = region.assign(&config.inputs[1], inverse)?;
+    let indicator = region.assign(&config.output, indicator)?;
+
+    // Enable the selectors
+    if !region.is_dummy() {
+        (0..x.len()).for_each(|i| {
+            let (x, y, z) = config.inputs[1].cartesian_coord(region.linear_coord() + i);
+            let selector = config.selectors.get(&(op.clone(), x, y));
+
+            region.enable(selector, z).unwrap();
+        });
+    }
+
+    region.increment(x.len());
+
+    Ok([indicator, inverse])
+}
+
+/// Element-wise `x mod modulus` for a positive constant modulus, with the residue in `[0, modulus)`.
+///
+/// Requires [BaseOp::Mod] to have been configured via [BaseConfig::configure_base_op]. The residue
+/// bound is checked with the ReLU lookup and the quotient is bounded by [constrain_bounded], so a
+/// [LookupOp::ReLU] table covering `[-modulus, modulus]` and every quotient `x.div_euclid(modulus)`
+/// must also have been set up with [BaseConfig::configure_lookup].
+pub fn modulo<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+    modulus: i128,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    // this is safe because we later constrain it
+    let (quotient, residue): (Vec<ValType<F>>, Vec<ValType<F>>) = values[0]
+        .get_inner_tensor()?
+        .iter()
+        .map(|x| match x.get_felt_eval() {
+            Some(x) => {
+                let x = felt_to_i128(x);
+                (
+                    Value::known(i128_to_felt::<F>(x.div_euclid(modulus))).into(),
+                    Value::known(i128_to_felt::<F>(x.rem_euclid(modulus))).into(),
+                )
+            }
+            None => (Value::<F>::unknown().into(), Value::<F>::unknown().into()),
+        })
+        .unzip();
+
+    let mut quotient: ValTensor<F> = Tensor::from(quotient.into_iter()).into();
+    let mut residue: ValTensor<F> = Tensor::from(residue.into_iter()).into();
+    quotient.reshape(values[0].dims())?;
+    residue.reshape(values[0].dims())?;
+
+    modulo_witnessed(
+        config,
+        region,
+        &[values[0].clone(), quotient, residue],
+        modulus,
+    )
+}
+
+/// Element-wise `x mod modulus` with a caller supplied quotient and residue, passed as
+/// `[x, quotient, residue]`. See [modulo] for the required configuration.
+pub(crate) fn modulo_witnessed<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 3],
+    modulus: i128,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let (x, quotient, residue) = (&values[0], &values[1], &values[2]);
+    let op = BaseOp::Mod { modulus };
+
+    if quotient.len() != x.len() || residue.len() != x.len() {
+        return Err(Box::new(CircuitError::DimMismatch(format!(
+            "{} layout",
+            op.as_str()
+        ))));
+    }
+
+    let quotient = region.assign(&config.inputs[0], quotient)?;
+    region.assign(&config.inputs[1], x)?;
+    let residue = region.assign(&config.output, residue)?;
+
+    // Enable the selectors
+    if !region.is_dummy() {
+        (0..x.len()).for_each(|i| {
+            let (x, y, z) = config.inputs[1].cartesian_coord(region.linear_coord() + i);
+            let selector = config.selectors.get(&(op.clone(), x, y));
+
+            region.enable(selector, z).unwrap();
+        });
+    }
+
+    region.increment(x.len());
+
+    // otherwise any residue in range passes along with the field quotient (x - residue) / modulus
+    constrain_bounded(config, region, &[quotient])?;
+
+    // 0 <= residue < modulus
+    constrain_non_negative(config, region, &[residue.clone()])?;
+    let bound: ValTensor<F> =
+        Tensor::from([ValType::Constant(i128_to_felt::<F>(modulus - 1))].into_iter()).into();
+    let slack = pairwise(config, region, &[bound, residue.clone()], BaseOp::Sub)?;
+    constrain_non_negative(config, region, &[slack])?;
+
+    Ok(residue)
+}
+
+/// Element-wise fused multiply-add `a * b + c` of three (broadcastable) tensors, in a single gate.
+/// Requires three input columns and [BaseOp::MulAdd] to have been configured via [BaseConfig::configure_base_op].
+pub fn mul_add<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 3],
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let op = BaseOp::MulAdd;
+    let [mut a, mut b, mut c] = values.clone();
+
+    let broadcasted_shape = get_broadcasted_shape(a.dims(), b.dims())?;
+    let broadcasted_shape = get_broadcasted_shape(&broadcasted_shape, c.dims())?;
+    a.expand(&broadcasted_shape)?;
+    b.expand(&broadcasted_shape)?;
+    c.expand(&broadcasted_shape)?;
+
+    let mut inputs = vec![];
+    for (i, input) in [a, b, c].iter().enumerate() {
+        inputs.push(region.assign(&config.inputs[i], input)?.get_inner()?);
+    }
+
+    let op_result = add(&[mult(&inputs[0..2])?, inputs[2].clone()])?;
+    let mut output = region.assign(&config.output, &op_result.into())?;
+
+    // Enable the selectors
+    if !region.is_dummy() {
+        (0..output.len()).for_each(|i| {
+            let (x, y, z) = config.inputs[0].cartesian_coord(region.linear_coord() + i);
+            let selector = config.selectors.get(&(op.clone(), x, y));
+
+            region.enable(selector, z).unwrap();
+        });
+    }
+    region.increment(output.len());
+
+    output.reshape(&broadcasted_shape)?;
+
+    Ok(output)
+}
+
+/// Element-wise [BaseOp::And], [BaseOp::Or], or [BaseOp::Xor] of two (broadcastable) boolean tensors, in a single gate
+/// which also constrains both operands to be boolean.
+///
+/// Requires the op to have been configured via [BaseConfig::configure_base_op].
+pub fn logical<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 2],
+    op: BaseOp,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    if !matches!(op, BaseOp::And | BaseOp::Or | BaseOp::Xor) {
+        return Err(Box::new(CircuitError::UnsupportedOp));
+    }
+    let [mut a, mut b] = values.clone();
+
+    let broadcasted_shape = get_broadcasted_shape(a.dims(), b.dims())?;
+    a.expand(&broadcasted_shape)?;
+    b.expand(&broadcasted_shape)?;
+
+    let a = region.assign(&config.inputs[0], &a)?.get_inner()?;
+    let b = region.assign(&config.inputs[1], &b)?.get_inner()?;
+
+    let op_result: Tensor<Value<F>> = a
+        .iter()
+        .zip(b.iter())
+        .map(|(a, b)| op.f((*a, *b, Value::known(F::ZERO))))
+        .collect();
+    let mut output = region.assign(&config.output, &op_result.into())?;
+
+    // Enable the selectors
+    if !region.is_dummy() {
+        (0..output.len()).for_each(|i| {
+            let (x, y, z) = config.inputs[0].cartesian_coord(region.linear_coord() + i);
+            let selector = config.selectors.get(&(op.clone(), x, y));
+
+            region.enable(selector, z).unwrap();
+        });
+    }
+    region.increment(output.len());
+
+    output.reshape(&broadcasted_shape)?;
+
+    Ok(output)
+}
+
+/// Element-wise [BaseOp::SaturatingAdd] or [BaseOp::SaturatingSub] of two (broadcastable) tensors, clamping the result
+/// to the op's `[min, max]` rather than wrapping around the field.
+/// The gate witnesses an overflow indicator per element (-1 below `min`, 1 above `max`, 0 otherwise), which is then
+/// checked against the unsaturated result with [constrain_non_negative], so the unsaturated result must lie within the
+/// range of the configured range check or ReLU lookup.
+pub fn saturating_pairwise<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 2],
+    op: BaseOp,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let (min, max) = match op {
+        BaseOp::SaturatingAdd { min, max } | BaseOp::SaturatingSub { min, max } if min <= max => {
+            (min, max)
+        }
+        _ => return Err(Box::new(CircuitError::UnsupportedOp)),
+    };
+    let [mut a, mut b] = values.clone();
+
+    let broadcasted_shape = get_broadcasted_shape(a.dims(), b.dims())?;
+    a.expand(&broadcasted_shape)?;
+    b.expand(&broadcasted_shape)?;
+
+    let (mut indicator, mut output): (Tensor<ValType<F>>, Tensor<ValType<F>>) = a
+        .get_inner_tensor()?
+        .iter()
+        .zip(b.get_inner_tensor()?.iter())
+        .map(|(a, b)| match (a.get_felt_eval(), b.get_felt_eval()) {
+            (Some(a), Some(b)) => {
+                let unsaturated = felt_to_i128(op.f((a, b, F::ZERO)));
+                let o = (unsaturated > max) as i128 - (unsaturated < min) as i128;
+                (
+                    Value::known(i128_to_felt::<F>(o)).into(),
+                    Value::known(i128_to_felt::<F>(unsaturated.clamp(min, max))).into(),
+                )
+            }
+            _ => (Value::<F>::unknown().into(), Value::<F>::unknown().into()),
+        })
+        .unzip();
+    indicator.reshape(&broadcasted_shape);
+    output.reshape(&broadcasted_shape);
+
+    let (inputs, output, indicator) = rotated_elementwise(
+        config,
+        region,
+        &[a, b],
+        &output.into(),
+        &indicator.into(),
+        op.clone(),
+    )?;
+
+    let constant = |c: i128| -> ValTensor<F> {
+        Tensor::from([ValType::Constant(i128_to_felt::<F>(c))].into_iter()).into()
+    };
+    let unsaturated = pairwise(
+        config,
+        region,
+        &[inputs[0].clone(), inputs[1].clone()],
+        match op {
+            BaseOp::SaturatingAdd { .. } => BaseOp::Add,
+            _ => BaseOp::Sub,
+        },
+    )?;
+
+    // min <= output <= max
+    let diff = pairwise(
+        config,
+        region,
+        &[output.clone(), constant(min)],
+        BaseOp::Sub,
+    )?;
+    constrain_non_negative(config, region, &[diff])?;
+    let diff = pairwise(
+        config,
+        region,
+        &[constant(max), output.clone()],
+        BaseOp::Sub,
+    )?;
+    constrain_non_negative(config, region, &[diff])?;
+
+    // o = 1 => unsaturated > max, which also holds for o = -1 as then unsaturated < min <= max
+    let diff = pairwise(
+        config,
+        region,
+        &[unsaturated.clone(), constant(max + 1)],
+        BaseOp::Sub,
+    )?;
+    let diff = pairwise(config, region, &[indicator.clone(), diff], BaseOp::Mult)?;
+    constrain_non_negative(config, region, &[diff])?;
+
+    // o = -1 => unsaturated < min, which also holds for o = 1 as then unsaturated > max >= min
+    let diff = pairwise(
+        config,
+        region,
+        &[unsaturated, constant(min - 1)],
+        BaseOp::Sub,
+    )?;
+    let diff = pairwise(config, region, &[indicator, diff], BaseOp::Mult)?;
+    constrain_non_negative(config, region, &[diff])?;
+
+    Ok(output)
+}
+
+/// Element-wise max of two tensors
+///
+/// Requires [BaseOp::Max] to have been configured via [BaseConfig::configure_base_op].
+pub fn pairwise_max<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 2],
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    pairwise_extremum(config, region, values, BaseOp::Max)
+}
+
+/// Element-wise min of two tensors
+///
+/// Requires [BaseOp::Min] to have been configured via [BaseConfig::configure_base_op].
+pub fn pairwise_min<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 2],
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    pairwise_extremum(config, region, values, BaseOp::Min)
+}
+
+fn pairwise_extremum<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 2],
+    op: BaseOp,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let (mut lhs, mut rhs) = (values[0].clone(), values[1].clone());
+
+    let broadcasted_shape = get_broadcasted_shape(lhs.dims(), rhs.dims())?;
+    lhs.expand(&broadcasted_shape)?;
+    rhs.expand(&broadcasted_shape)?;
+
+    // this is safe because we later constrain it
+    let selection: Tensor<ValType<F>> = lhs
+        .get_inner_tensor()?
+        .iter()
+        .zip(rhs.get_inner_tensor()?.iter())
+        .map(|(a, b)| match (a.get_felt_eval(), b.get_felt_eval()) {
+            (Some(a), Some(b)) => {
+                let (a, b) = (felt_to_i128(a), felt_to_i128(b));
+                let bit = match op {
+                    BaseOp::Max => a >= b,
+                    _ => a <= b,
+                };
+                Value::known(F::from(bit as u64)).into()
+            }
+            _ => Value::<F>::unknown().into(),
+        })
+        .collect();
+    let mut selection: ValTensor<F> = selection.into();
+    selection.reshape(&broadcasted_shape)?;
+
+    pairwise_extremum_witnessed(config, region, &[lhs, rhs, selection], op)
+}
+
+/// Element-wise max ([BaseOp::Max]) or min ([BaseOp::Min]) with a caller supplied selection
+/// bit, passed as `[a, b, selection]`. The output is `selection * a + (1 - selection) * b`,
+/// and is checked to be no smaller (resp. no larger) than both `a` and `b`.
+pub(crate) fn pairwise_extremum_witnessed<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 3],
+    op: BaseOp,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let (a, b, selection) = (&values[0], &values[1], &values[2]);
+
+    let output: Tensor<ValType<F>> = a
+        .get_inner_tensor()?
+        .iter()
+        .zip(b.get_inner_tensor()?.iter())
+        .zip(selection.get_inner_tensor()?.iter())
+        .map(
+            |((a, b), s)| match (a.get_felt_eval(), b.get_felt_eval(), s.get_felt_eval()) {
+                (Some(a), Some(b), Some(s)) => Value::known(s * a + (F::ONE - s) * b).into(),
+                _ => Value::<F>::unknown().into(),
+            },
+        )
+        .collect();
+    let mut output: ValTensor<F> = output.into();
+    output.reshape(a.dims())?;
+
+    let (inputs, output, _) = rotated_elementwise(
+        config,
+        region,
+        &[a.clone(), b.clone()],
+        &output,
+        selection,
+        op.clone(),
+    )?;
+
+    // the output is one of a and b, so it's the extremum if it bounds both
+    for x in inputs {
+        let diff = match op {
+            BaseOp::Max => pairwise(config, region, &[output.clone(), x], BaseOp::Sub)?,
+            _ => pairwise(config, region, &[x, output.clone()], BaseOp::Sub)?,
+        };
+        constrain_non_negative(config, region, &[diff])?;
+    }
+
+    Ok(output)
+}
+
+/// Element-wise ternary select over `[c, a, b]`, returning `c * a + (1 - c) * b` with `c`
+/// constrained to be boolean. Unlike [iff], this is a single gate per element laid out as:
+///
+/// | row | inputs\[0\] | inputs\[1\] | output |
+/// |-----|-------------|-------------|--------|
+/// | i   |             |             | c      |
+/// | i+1 | a           | b           | out    |
+///
+/// Requires [BaseOp::Select] to have been configured via [BaseConfig::configure_base_op].
+pub fn ternary<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 3],
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let (mut c, mut a, mut b) = (values[0].clone(), values[1].clone(), values[2].clone());
+
+    let broadcasted_shape = get_broadcasted_shape(a.dims(), b.dims())?;
+    let broadcasted_shape = get_broadcasted_shape(c.dims(), &broadcasted_shape)?;
+    c.expand(&broadcasted_shape)?;
+    a.expand(&broadcasted_shape)?;
+    b.expand(&broadcasted_shape)?;
+
+    let output: Tensor<ValType<F>> = a
+        .get_inner_tensor()?
+        .iter()
+        .zip(b.get_inner_tensor()?.iter())
+        .zip(c.get_inner_tensor()?.iter())
+        .map(
+            |((a, b), c)| match (a.get_felt_eval(), b.get_felt_eval(), c.get_felt_eval()) {
+                (Some(a), Some(b), Some(c)) => Value::known(c * a + (F::ONE - c) * b).into(),
+                _ => Value::<F>::unknown().into(),
+            },
+        )
+        .collect();
+    let mut output: ValTensor<F> = output.into();
+    output.reshape(&broadcasted_shape)?;
+
+    let (_, output, _) = rotated_elementwise(config, region, &[a, b], &output, &c, BaseOp::Select)?;
+
+    Ok(output)
+}
+
+/// Parametric ReLU over `[x, slope]`, returning `x` where `x > 0` and `x * slope` elsewhere. `slope` is typically a
+/// per-channel tensor broadcast against `x`, and is applied as an integer multiplier, so any rescaling of the
+/// negative branch is left to the caller. The sign is detected by [compare], which range-checks against the
+/// configured lookups, and the branches are chosen with [ternary].
+///
+/// Requires [BaseOp::Greater] and [BaseOp::Select] to have been configured via [BaseConfig::configure_base_op].
+pub fn prelu<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 2],
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let (mut x, mut slope) = (values[0].clone(), values[1].clone());
+
+    let broadcasted_shape = get_broadcasted_shape(x.dims(), slope.dims())?;
+    x.expand(&broadcasted_shape)?;
+    slope.expand(&broadcasted_shape)?;
+
+    let zero: ValTensor<F> = Tensor::from(vec![ValType::Constant(F::ZERO)].into_iter()).into();
+    let is_positive = compare(config, region, &[x.clone(), zero], BaseOp::Greater)?;
+    let negative = pairwise(config, region, &[x.clone(), slope], BaseOp::Mult)?;
+
+    ternary(config, region, &[is_positive, x, negative])
+}
+
+/// Element-wise strict comparison, returning a boolean tensor for `a > b` ([BaseOp::Greater])
+/// or `a < b` ([BaseOp::Less]).
+///
+/// Requires the op to have been configured via [BaseConfig::configure_base_op].
+pub fn compare<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 2],
+    op: BaseOp,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let (mut lhs, mut rhs) = (values[0].clone(), values[1].clone());
+
+    let broadcasted_shape = get_broadcasted_shape(lhs.dims(), rhs.dims())?;
+    lhs.expand(&broadcasted_shape)?;
+    rhs.expand(&broadcasted_shape)?;
+
+    // this is safe because we later constrain it
+    let bits: Tensor<ValType<F>> = lhs
+        .get_inner_tensor()?
+        .iter()
+        .zip(rhs.get_inner_tensor()?.iter())
+        .map(|(a, b)| match (a.get_felt_eval(), b.get_felt_eval()) {
+            (Some(a), Some(b)) => {
+                let (a, b) = (felt_to_i128(a), felt_to_i128(b));
+                let bit = match op {
+                    BaseOp::Greater => a > b,
+                    _ => a < b,
+                };
+                Value::known(F::from(bit as u64)).into()
+            }
+            _ => Value::<F>::unknown().into(),
+        })
+        .collect();
+    let mut bits: ValTensor<F> = bits.into();
+    bits.reshape(&broadcasted_shape)?;
+
+    compare_witnessed(config, region, &[lhs, rhs, bits], op)
+}
+
+/// Element-wise strict comparison with a caller supplied comparison bit, passed as `[a, b, bit]`.
+/// For [BaseOp::Greater] the gate witnesses `diff = (2 * bit - 1) * (a - b) - bit`, which is
+/// non-negative exactly when the bit is correct. `diff` is range-checked by [constrain_non_negative],
+/// so the inputs should be within the configured lookup range.
+pub(crate) fn compare_witnessed<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 3],
+    op: BaseOp,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let (a, b, bits) = (&values[0], &values[1], &values[2]);
+
+    let diff: Tensor<ValType<F>> = a
+        .get_inner_tensor()?
+        .iter()
+        .zip(b.get_inner_tensor()?.iter())
+        .zip(bits.get_inner_tensor()?.iter())
+        .map(
+            |((a, b), c)| match (a.get_felt_eval(), b.get_felt_eval(), c.get_felt_eval()) {
+                (Some(a), Some(b), Some(c)) => Value::known(op.f((a, b, c))).into(),
+                _ => Value::<F>::unknown().into(),
+            },
+        )
+        .collect();
+    let mut diff: ValTensor<F> = diff.into();
+    diff.reshape(a.dims())?;
+
+    let (_, diff, bits) =
+        rotated_elementwise(config, region, &[a.clone(), b.clone()], &diff, bits, op)?;
+
+    constrain_non_negative(config, region, &[diff])?;
+
+    Ok(bits)
+}
+
+/// Accumulated max layout, returning the final accumulator cell
+///
+/// Requires [BaseOp::ReduceMax] to have been configured via [BaseConfig::configure_base_op].
+pub fn accumulated_max<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    accumulated_extremum(config, region, values, BaseOp::ReduceMax)
+}
+
+/// Accumulated min layout, returning the final accumulator cell
+///
+/// Requires [BaseOp::ReduceMin] to have been configured via [BaseConfig::configure_base_op].
+pub fn accumulated_min<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    accumulated_extremum(config, region, values, BaseOp::ReduceMin)
+}
+
+fn accumulated_extremum<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+    op: BaseOp,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let mut input = values[0].clone();
+    input.flatten();
+
+    // this is safe because we later constrain it
+    let mut running: Option<i128> = None;
+    let bits: Tensor<ValType<F>> = input
+        .get_inner_tensor()?
+        .iter()
+        .map(|x| match x.get_felt_eval() {
+            Some(x) => {
+                let x = felt_to_i128(x);
+                let bit = match (running, &op) {
+                    (None, _) => true,
+                    (Some(r), BaseOp::ReduceMax) => x > r,
+                    (Some(r), _) => x < r,
+                };
+                if bit {
+                    running = Some(x);
+                }
+                Value::known(F::from(bit as u64)).into()
+            }
+            None => Value::<F>::unknown().into(),
+        })
+        .collect();
+
+    accumulated_extremum_witnessed(config, region, &[input, bits.into()], op)
+}
+
+/// Accumulated max ([BaseOp::ReduceMax]) or min ([BaseOp::ReduceMin]) with caller supplied
+/// selection bits, passed as `[x, bits]`. Each accumulator cell is `bit * x + (1 - bit) * prev`,
+/// and is checked to bound both the new element and the previous accumulator cell.
+/// Returns the final accumulator cell.
+pub(crate) fn accumulated_extremum_witnessed<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 2],
+    op: BaseOp,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let (mut input, mut bits) = (values[0].clone(), values[1].clone());
+    input.flatten();
+    bits.flatten();
+
+    if input.is_empty() || input.len() != bits.len() {
+        return Err(Box::new(CircuitError::DimMismatch(format!(
+            "accumulated {} layout",
+            op.as_str()
+        ))));
+    }
+
+    let mut running: Option<F> = None;
+    let accumulated: Tensor<ValType<F>> = input
+        .get_inner_tensor()?
+        .iter()
+        .zip(bits.get_inner_tensor()?.iter())
+        .enumerate()
+        .map(|(i, (x, b))| match (x.get_felt_eval(), b.get_felt_eval()) {
+            (Some(x), Some(b)) if i == 0 || running.is_some() => {
+                let acc = match running {
+                    Some(prev) => b * x + (F::ONE - b) * prev,
+                    None => x,
+                };
+                running = Some(acc);
+                Value::known(acc).into()
+            }
+            _ => {
+                running = None;
+                Value::<F>::unknown().into()
+            }
+        })
+        .collect();
+
+    let (_, assigned_len) =
+        region.assign_with_duplication(&config.inputs[0], &bits, &config.check_mode)?;
+    let (input, _) =
+        region.assign_with_duplication(&config.inputs[1], &input, &config.check_mode)?;
+    let (output, output_assigned_len) =
+        region.assign_with_duplication(&config.output, &accumulated.into(), &config.check_mode)?;
+
+    assert_eq!(assigned_len, output_assigned_len);
+
+    // enable the selectors
+    if !region.is_dummy() {
+        for i in 0..assigned_len {
+            let (x, y, z) = config.output.cartesian_coord(region.linear_coord() + i);
+            // skip over duplicates at start of column
+            if z == 0 && y == 0 && i > 0 {
+                continue;
+            }
+            let selector = if i == 0 {
+                config.selectors.get(&(BaseOp::Identity, x, y))
+            } else {
+                config.selectors.get(&(op.clone(), x, y))
+            };
+
+            region.enable(selector, z)?;
+        }
+    }
+
+    region.increment(assigned_len);
+
+    // each accumulator cell is either the new element or the previous accumulator cell,
+    // so it's the running extremum if it bounds both
+    let bound = |lhs: ValTensor<F>, rhs: ValTensor<F>| match op {
+        BaseOp::ReduceMax => [lhs, rhs],
+        _ => [rhs, lhs],
+    };
+
+    let diff = pairwise(config, region, &bound(output.clone(), input), BaseOp::Sub)?;
+    constrain_non_negative(config, region, &[diff])?;
+
+    let len = output.len();
+    if len > 1 {
+        let (curr, prev) = (
+            output.get_slice(&[1..len])?,
+            output.get_slice(&[0..len - 1])?,
+        );
+        let diff = pairwise(config, region, &bound(curr, prev), BaseOp::Sub)?;
+        constrain_non_negative(config, region, &[diff])?;
+    }
+
+    // last element is the result
+    output.get_slice(&[len - 1..len])
+}
+
+/// Max pool over the last two dims of the input, taking the [accumulated_max] of each `window` sized slice,
+/// stepping by `stride`. Leading dims (eg. batch and channels) are pooled independently.
+/// No padding is applied: trailing rows or columns that don't fill a whole window are dropped, ie. the output
+/// has `(len - window) / stride + 1` elements along each pooled dim. Callers that need padding should pad the input.
+///
+/// Requires [BaseOp::ReduceMax] to have been configured via [BaseConfig::configure_base_op].
+pub fn accumulated_max_pool<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+    window: (usize, usize),
+    stride: (usize, usize),
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let input = &values[0];
+    let dims = input.dims().to_vec();
+    let rank = dims.len();
+
+    if rank < 2
+        || window.0 == 0
+        || window.1 == 0
+        || stride.0 == 0
+        || stride.1 == 0
+        || window.0 > dims[rank - 2]
+        || window.1 > dims[rank - 1]
+    {
+        return Err(Box::new(TensorError::DimMismatch(
+            "accumulated max pool".to_string(),
+        )));
+    }
+
+    let vert_slides = (dims[rank - 2] - window.0) / stride.0 + 1;
+    let horz_slides = (dims[rank - 1] - window.1) / stride.1 + 1;
+    let output_dims = [&dims[..rank - 2], &[vert_slides, horz_slides]].concat();
+
+    let mut output = vec![];
+    for coord in output_dims.iter().map(|d| 0..*d).multi_cartesian_product() {
+        let (j, k) = (coord[rank - 2], coord[rank - 1]);
+        let slice = coord[..rank - 2]
+            .iter()
+            .map(|c| *c..(*c + 1))
+            .chain([
+                (j * stride.0)..(j * stride.0 + window.0),
+                (k * stride.1)..(k * stride.1 + window.1),
+            ])
+            .collect::<Vec<_>>();
+        let max = accumulated_max(config, region, &[input.get_slice(&slice)?])?;
+        output.push(max.get_inner_tensor()?[0].clone());
+    }
+
+    let mut output: ValTensor<F> = Tensor::from(output.into_iter()).into();
+    output.reshape(&output_dims)?;
+
+    Ok(output)
+}
+
+/// Argmax of the flattened input, resolving ties to the lowest index. A one-hot tensor `h` is witnessed and
+/// constrained to be boolean and to sum to 1, and the element it selects, `h · x`, is constrained to equal the
+/// [accumulated_max] of the input. The lowest index is enforced through the witnessed `before_j = 1 - sum_{i <= j} h_i`,
+/// constrained by `before_j + h_j = before_{j-1}` (with `before_{-1} = 1`), which is 1 exactly for the elements
+/// preceding the selected one: each of these must be strictly less than the max.
+/// Returns the index as a single element tensor. Requires a constants column.
+///
+/// Requires [BaseOp::ReduceMax] to have been configured via [BaseConfig::configure_base_op].
+pub fn accumulated_argmax<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let mut input = values[0].clone();
+    input.flatten();
+    let len = input.len();
+    if len == 0 {
+        return Err(Box::new(CircuitError::DimMismatch(
+            "accumulated argmax layout".to_string(),
+        )));
+    }
+
+    // the input is assigned once so that every op below constrains the same cells
+    let input = identity(config, region, &[input])?;
+    let max = accumulated_max(config, region, &[input.clone()])?;
+
+    // this is safe because we later constrain it
+    let argmax = if input.any_unknowns() {
+        None
+    } else {
+        input
+            .get_int_evals()?
+            .iter()
+            .enumerate()
+            // we value the first index in the case of a tie
+            .max_by_key(|(idx, value)| (**value, -(*idx as i64)))
+            .map(|(idx, _)| idx)
+    };
+    let (one_hot, before): (Vec<ValType<F>>, Vec<ValType<F>>) = (0..len)
+        .map(|i| match argmax {
+            Some(idx) => (
+                Value::known(F::from((i == idx) as u64)).into(),
+                Value::known(F::from((i < idx) as u64)).into(),
+            ),
+            None => (Value::<F>::unknown().into(), Value::<F>::unknown().into()),
+        })
+        .unzip();
+
+    let unit: ValTensor<F> = Tensor::from(vec![ValType::Constant(F::ONE)].into_iter()).into();
+
+    // exactly one element is selected
+    let one_hot = boolean_identity(config, region, &[Tensor::from(one_hot.into_iter()).into()])?;
+    let count = sum(config, region, &[one_hot.clone()])?;
+    enforce_equality(config, region, &[count, unit.clone()])?;
+
+    // and it is the max
+    let selected = dot(config, region, &[one_hot.clone(), input.clone()])?;
+    enforce_equality(config, region, &[selected, max.clone()])?;
+
+    // before_j + h_j = before_{j-1}
+    let before = identity(config, region, &[Tensor::from(before.into_iter()).into()])?;
+    let running = pairwise(
+        config,
+        region,
+        &[before.clone(), one_hot.clone()],
+        BaseOp::Add,
+    )?;
+    let shifted = if len > 1 {
+        unit.concat(before.get_slice(&[0..len - 1])?)?
+    } else {
+        unit.clone()
+    };
+    enforce_equality(config, region, &[running, shifted])?;
+
+    // every element before the selected one is strictly less than the max
+    let gap = pairwise(config, region, &[max, input], BaseOp::Sub)?;
+    let gap = pairwise(config, region, &[gap, unit], BaseOp::Sub)?;
+    let gap = pairwise(config, region, &[before, gap], BaseOp::Mult)?;
+    constrain_non_negative(config, region, &[gap])?;
+
+    // these will be assigned as constants
+    let mut indices = Tensor::from((0..len as u64).map(F::from));
+    indices.set_visibility(&crate::graph::Visibility::Fixed);
+    dot(config, region, &[one_hot, indices.into()])
+}
+
+/// Element-wise absolute value
+///
+/// Requires [BaseOp::Abs] to have been configured via [BaseConfig::configure_base_op].
+pub fn abs<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    // this is safe because we later constrain it
+    let sign: Tensor<ValType<F>> = values[0]
+        .get_inner_tensor()?
+        .iter()
+        .map(|x| match x.get_felt_eval() {
+            Some(x) => Value::known(F::from((felt_to_i128(x) < 0) as u64)).into(),
+            None => Value::<F>::unknown().into(),
+        })
+        .collect();
+    let mut sign: ValTensor<F> = sign.into();
+    sign.reshape(values[0].dims())?;
+
+    abs_witnessed(config, region, &[values[0].clone(), sign])
+}
+
+/// Element-wise absolute value with a caller supplied sign bit, passed as `[x, sign]`.
+/// The output is the magnitude `m` such that `x = m - 2 * sign * m`, and is checked to be
+/// non-negative.
+pub(crate) fn abs_witnessed<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 2],
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let (x, sign) = (&values[0], &values[1]);
+
+    let magnitude: Tensor<ValType<F>> = x
+        .get_inner_tensor()?
+        .iter()
+        .zip(sign.get_inner_tensor()?.iter())
+        .map(|(x, s)| match (x.get_felt_eval(), s.get_felt_eval()) {
+            (Some(x), Some(s)) => Value::known(x - (s + s) * x).into(),
+            _ => Value::<F>::unknown().into(),
+        })
+        .collect();
+    let mut magnitude: ValTensor<F> = magnitude.into();
+    magnitude.reshape(x.dims())?;
+
+    let (_, magnitude, _) =
+        rotated_elementwise(config, region, &[x.clone()], &magnitude, sign, BaseOp::Abs)?;
+
+    // pins down the sign bit (up to x = 0)
+    constrain_non_negative(config, region, &[magnitude.clone()])?;
+
+    Ok(magnitude)
+}
+
+/// Element-wise clamp to `[min, max]`. Requires [BaseOp::Clamp] to have been configured via
+/// [BaseConfig::configure_base_op].
+pub fn clamp<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+    min: i128,
+    max: i128,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    // this is safe because we later constrain it
+    let indicator = |f: &dyn Fn(i128) -> bool| -> Result<ValTensor<F>, Box<dyn Error>> {
+        let bits: Tensor<ValType<F>> = values[0]
+            .get_inner_tensor()?
+            .iter()
+            .map(|x| match x.get_felt_eval() {
+                Some(x) => Value::known(F::from(f(felt_to_i128(x)) as u64)).into(),
+                None => Value::<F>::unknown().into(),
+            })
+            .collect();
+        let mut bits: ValTensor<F> = bits.into();
+        bits.reshape(values[0].dims())?;
+        Ok(bits)
+    };
+    let below = indicator(&|x| x < min)?;
+    let above = indicator(&|x| x > max)?;
+
+    clamp_witnessed(config, region, &[values[0].clone(), below, above], min, max)
+}
+
+/// Element-wise clamp to `[min, max]` with caller supplied indicator bits, passed as
+/// `[x, below, above]`.
+pub(crate) fn clamp_witnessed<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 3],
+    min: i128,
+    max: i128,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let (x, below, above) = (&values[0], &values[1], &values[2]);
+    let (min_felt, max_felt) = (i128_to_felt::<F>(min), i128_to_felt::<F>(max));
+
+    let output: Tensor<ValType<F>> = x
+        .get_inner_tensor()?
+        .iter()
+        .zip(below.get_inner_tensor()?.iter())
+        .zip(above.get_inner_tensor()?.iter())
+        .map(
+            |((x, lo), hi)| match (x.get_felt_eval(), lo.get_felt_eval(), hi.get_felt_eval()) {
+                (Some(x), Some(lo), Some(hi)) => {
+                    Value::known(lo * min_felt + hi * max_felt + (F::ONE - lo - hi) * x).into()
+                }
+                _ => Value::<F>::unknown().into(),
+            },
+        )
+        .collect();
+    let mut output: ValTensor<F> = output.into();
+    output.reshape(x.dims())?;
+
+    let (inputs, output, below) = rotated_elementwise(
+        config,
+        region,
+        &[above.clone(), x.clone()],
+        &output,
+        below,
+        BaseOp::Clamp { min, max },
+    )?;
+    let (above, x) = (inputs[0].clone(), inputs[1].clone());
+
+    let constant = |c: i128| -> ValTensor<F> {
+        Tensor::from([ValType::Constant(i128_to_felt::<F>(c))].into_iter()).into()
+    };
+
+    // min <= output <= max
+    let diff = pairwise(
+        config,
+        region,
+        &[output.clone(), constant(min)],
+        BaseOp::Sub,
+    )?;
+    constrain_non_negative(config, region, &[diff])?;
+    let diff = pairwise(
+        config,
+        region,
+        &[constant(max), output.clone()],
+        BaseOp::Sub,
+    )?;
+    constrain_non_negative(config, region, &[diff])?;
+
+    // below => x < min
+    let diff = pairwise(config, region, &[constant(min - 1), x.clone()], BaseOp::Sub)?;
+    let diff = pairwise(config, region, &[below, diff], BaseOp::Mult)?;
+    constrain_non_negative(config, region, &[diff])?;
+
+    // above => x > max
+    let diff = pairwise(config, region, &[x, constant(max + 1)], BaseOp::Sub)?;
+    let diff = pairwise(config, region, &[above, diff], BaseOp::Mult)?;
+    constrain_non_negative(config, region, &[diff])?;
+
+    Ok(output)
+}
+
+/// Element-wise square accumulated layout. Like any product, the result wraps around the field modulus.
+///
+/// Requires [BaseOp::Square] to have been configured via [BaseConfig::configure_base_op].
+pub fn square<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let input = {
+        let res = region.assign(&config.inputs[1], &values[0])?;
+
+        res.get_inner()?
+    };
+
+    let square = input.map(|e| e * e);
+
+    let output = region.assign(&config.output, &square.into())?;
+
+    // Enable the selectors
+    if !region.is_dummy() {
+        (0..values[0].len()).for_each(|i| {
+            let (x, y, z) = config.inputs[1].cartesian_coord(region.linear_coord() + i);
+            let selector = config.selectors.get(&(BaseOp::Square, x, y));
+
+            region.enable(selector, z).unwrap();
+        });
+    }
+
+    region.increment(output.len());
+
+    Ok(output)
+}
+
+/// Sumpool accumulated layout
+pub fn sumpool<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>],
+    padding: [(usize, usize); 2],
+    stride: (usize, usize),
+    kernel_shape: (usize, usize),
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let batch_size = values[0].dims()[0];
+    let image_channels = values[0].dims()[1];
+
+    let unit = region.assign_constant(&config.inputs[1], F::from(1))?;
+    region.next();
+
+    let mut kernel = Tensor::from(0..kernel_shape.0 * kernel_shape.1).map(|_| unit.clone());
+    kernel.reshape(&[1, 1, kernel_shape.0, kernel_shape.1]);
+
+    let cartesian_coord = [(0..batch_size), (0..image_channels)]
+        .iter()
+        .cloned()
+        .multi_cartesian_product()
+        .collect::<Vec<_>>();
+
+    let mut res = vec![];
+
+    cartesian_coord.iter().for_each(|coord| {
+        let (b, i) = (coord[0], coord[1]);
+        let input = values[0].get_slice(&[b..b + 1, i..i + 1]).unwrap();
+        let output = conv(
+            config,
+            region,
+            &[input, kernel.clone().into()],
+            padding,
+            stride,
+        )
+        .unwrap();
+        res.push(output);
+    });
+
+    let shape = &res[0].dims()[2..];
+    let mut last_elem = res[1..].iter().fold(res[0].clone(), |acc, elem| {
+        acc.concat(elem.clone()).unwrap()
+    });
+    last_elem.reshape(&[&[batch_size, image_channels], shape].concat())?;
+
+    Ok(last_elem)
+}
+
+/// Convolution accumulated layout
+pub fn max_pool2d<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+    padding: [(usize, usize); 2],
+    stride: (usize, usize),
+    pool_dims: (usize, usize),
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let image = values[0].clone();
+
+    if image.dims().len() != 4 {
+        return Err(Box::new(TensorError::DimMismatch("max_pool2d".to_string())));
+    }
+    let image_dims = image.dims();
+
+    let (batch, input_channels, image_height, image_width) =
+        (image_dims[0], image_dims[1], image_dims[2], image_dims[3]);
+
+    let mut padded_image = image.clone();
+    padded_image.pad(padding)?;
+
+    let vert_slides = (image_height + padding[0].0 + padding[1].0 - pool_dims.0) / stride.0 + 1;
+    let horz_slides = (image_width + padding[0].1 + padding[1].1 - pool_dims.1) / stride.1 + 1;
+
+    let mut output: Tensor<ValType<F>> =
+        Tensor::new(None, &[batch, input_channels, horz_slides, vert_slides])?;
+
+    let cartesian_coord = [
+        (0..batch),
+        (0..input_channels),
+        (0..vert_slides),
+        (0..horz_slides),
+    ]
+    .iter()
+    .cloned()
+    .multi_cartesian_product()
+    .collect::<Vec<_>>();
+
+    output.iter_mut().enumerate().for_each(|(flat_index, o)| {
+        let coord = &cartesian_coord[flat_index];
+        let (b, i, j, k) = (coord[0], coord[1], coord[2], coord[3]);
+        let rs = j * stride.0;
+        let cs = k * stride.1;
+        let slice = padded_image
+            .get_slice(&[
+                b..(b + 1),
+                i..(i + 1),
+                rs..(rs + pool_dims.0),
+                cs..(cs + pool_dims.1),
+            ])
+            .unwrap();
+        let max_w = max(config, region, &[slice]).unwrap();
+        *o = max_w.get_inner_tensor().unwrap()[0].clone();
+    });
+
+    let res: ValTensor<F> = output.into();
+
+    Ok(res)
+}
+
+/// DeConvolution accumulated layout
+pub fn deconv<F: PrimeField + TensorType + PartialOrd + std::marker::Send + std::marker::Sync>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    inputs: &[ValTensor<F>],
+    padding: [(usize, usize); 2],
+    output_padding: (usize, usize),
+    stride: (usize, usize),
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let has_bias = inputs.len() == 3;
+    let (image, kernel) = (&inputs[0], &inputs[1]);
+
+    if (image.dims().len() != 4) || (kernel.dims().len() != 4) {
+        return Err(Box::new(TensorError::DimMismatch("deconv".to_string())));
+    }
+
+    if stride.0 == 0 || stride.1 == 0 {
+        return Err(Box::new(TensorError::DimMismatch(
+            "non-positive stride is not supported for deconv".to_string(),
+        )));
+    }
+
+    if has_bias {
+        let bias = &inputs[2];
+        if (bias.dims().len() != 1) || (bias.dims()[0] != kernel.dims()[0]) {
+            return Err(Box::new(TensorError::DimMismatch(
+                "deconv bias".to_string(),
+            )));
+        }
+    }
+
+    let (kernel_height, kernel_width) = (kernel.dims()[2], kernel.dims()[3]);
+
+    let null_val = ValType::Constant(F::ZERO);
+    // region.assign_constant(&config.inputs[1], F::from(0))?;
+    // region.next();
+
+    let mut expanded_image = image.clone();
+    expanded_image.intercalate_values(null_val.clone(), stride.0, 2)?;
+    expanded_image.intercalate_values(null_val, stride.1, 3)?;
+    expanded_image.pad([(kernel_height - 1, kernel_width - 1); 2])?;
+
+    // flip order
+    let channel_coord = (0..kernel.dims()[0])
+        .cartesian_product(0..kernel.dims()[1])
         .collect::<Vec<_>>();
 
     let slice_coord = expanded_image
@@ -2040,6 +3524,43 @@ pub fn pow<F: PrimeField + TensorType + PartialOrd>(
     Ok(t)
 }
 
+/// Element-wise `values[0]^exponent` laid out as a [BaseOp::CumProd] over `exponent` copies of each element, returning
+/// the final accumulators. An exponent of 0 gives constant ones and 1 returns the input. Like [pow] this costs a row per
+/// copy, but lays them out as a single accumulated product rather than a chain of [BaseOp::Mult]s.
+/// The result wraps around the field modulus like any other product, so it silently overflows once `|x|^exponent` no
+/// longer fits in the field, and is only meaningful as a signed integer while it fits in [BaseConfig::signed_range_bits].
+pub fn accumulated_pow<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+    exponent: usize,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let dims = values[0].dims().to_vec();
+    match exponent {
+        0 => {
+            let mut ones: ValTensor<F> =
+                Tensor::from((0..values[0].len()).map(|_| ValType::Constant(F::ONE))).into();
+            ones.reshape(&dims)?;
+            return Ok(ones);
+        }
+        1 => return Ok(values[0].clone()),
+        _ => {}
+    }
+
+    // assign the base once so that every copy is constrained to the same cells
+    let mut base = identity(config, region, values)?;
+    let len = base.len();
+    base.reshape(&[1, len])?;
+    let mut copies = base.clone();
+    for _ in 1..exponent {
+        copies = copies.concat_axis(base.clone(), &0)?;
+    }
+
+    let mut output = prod_axes(config, region, &[copies], &[0])?;
+    output.reshape(&dims)?;
+    Ok(output)
+}
+
 /// Rescaled op accumulated layout
 pub fn rescale<F: PrimeField + TensorType + PartialOrd>(
     config: &BaseConfig<F>,
@@ -2063,6 +3584,34 @@ pub fn rescale<F: PrimeField + TensorType + PartialOrd>(
     Ok(rescaled_inputs)
 }
 
+/// Rescales the input from `from_scale` to `to_scale` with the rounding [LookupOp::Div] lookup, then saturates the
+/// result to the signed `bits` bit range, if any, with [clamp]. Values that would overflow the range are clamped
+/// to its ends rather than wrapping. The input must lie within the range of the Div table, and [BaseOp::Clamp]
+/// must have been configured for the bounds of the range.
+pub fn saturating_rescale<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+    from_scale: utils::F32,
+    to_scale: utils::F32,
+    bits: Option<usize>,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let rescaled = if from_scale == to_scale {
+        values[0].clone()
+    } else {
+        let denom = utils::F32(from_scale.0 / to_scale.0);
+        nonlinearity(config, region, values, &LookupOp::Div { denom })?
+    };
+
+    match bits {
+        Some(bits) => {
+            let (min, max) = TableRange::Signed.bounds(bits)?;
+            clamp(config, region, &[rescaled], min, max)
+        }
+        None => Ok(rescaled),
+    }
+}
+
 /// Pack accumulated layout
 pub fn pack<F: PrimeField + TensorType + PartialOrd>(
     config: &BaseConfig<F>,
@@ -2199,6 +3748,58 @@ pub fn boolean_identity<F: PrimeField + TensorType + PartialOrd>(
     Ok(output)
 }
 
+/// Decomposes a single element `value` into `n_bits` little-endian bits and constrains them with [bit_recomposition].
+pub fn bit_decomposition<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    value: &ValTensor<F>,
+    n_bits: usize,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    if value.len() != 1 {
+        return Err(Box::new(TensorError::DimMismatch(
+            "bit decomposition".to_string(),
+        )));
+    }
+
+    let felt = value.get_inner_tensor()?[0].get_felt_eval();
+    // the repr of the field element is little-endian
+    let bits: Tensor<ValType<F>> = (0..n_bits)
+        .map(|i| match felt {
+            Some(f) => {
+                let bit = (f.to_repr().as_ref()[i / 8] >> (i % 8)) & 1;
+                Value::known(F::from(bit as u64)).into()
+            }
+            None => Value::<F>::unknown().into(),
+        })
+        .into();
+
+    bit_recomposition(config, region, value, &bits.into())
+}
+
+/// Constrains every element of `bits` to be boolean and `value == sum(bits_i * 2^i)`, returning the assigned bits.
+pub fn bit_recomposition<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    value: &ValTensor<F>,
+    bits: &ValTensor<F>,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let bits = boolean_identity(config, region, &[bits.clone()])?;
+
+    let mut power = F::ONE;
+    let powers: Tensor<ValType<F>> = (0..bits.len())
+        .map(|_| {
+            let p = power;
+            power = power.double();
+            ValType::Constant(p)
+        })
+        .into();
+
+    let recomposed = dot(config, region, &[bits.clone(), powers.into()])?;
+    enforce_equality(config, region, &[value.clone(), recomposed])?;
+
+    Ok(bits)
+}
+
 /// Downsample layout
 pub fn downsample<F: PrimeField + TensorType + PartialOrd>(
     config: &BaseConfig<F>,
@@ -2238,6 +3839,42 @@ pub fn enforce_equality<F: PrimeField + TensorType + PartialOrd>(
     Ok(output)
 }
 
+/// Constrains `values[0]` to equal `values[1]` element-wise, eg. a computed output and an instance, by laying out a
+/// [BaseOp::Sub] of the two whose output is fixed to the constant zero. Unlike [enforce_equality] this goes through the
+/// gate rather than copy constraints, so requires constant columns (see [crate::tensor::VarTensor::constant_cols]).
+/// Returns the assigned `values[0]`.
+pub fn enforce_zero_difference<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 2],
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    if values[0].len() != values[1].len() {
+        return Err(Box::new(CircuitError::DimMismatch(format!(
+            "equality of tensors of length {} and {}",
+            values[0].len(),
+            values[1].len()
+        ))));
+    }
+
+    let computed = region.assign(&config.inputs[0], &values[0])?;
+    region.assign(&config.inputs[1], &values[1])?;
+    let zeros: Tensor<ValType<F>> = (0..values[0].len())
+        .map(|_| ValType::Constant(F::ZERO))
+        .collect();
+    region.assign(&config.output, &zeros.into())?;
+
+    if !region.is_dummy() {
+        (0..computed.len()).for_each(|i| {
+            let (x, y, z) = config.output.cartesian_coord(region.linear_coord() + i);
+            let selector = config.selectors.get(&(BaseOp::Sub, x, y));
+            region.enable(selector, z).unwrap();
+        });
+    }
+    region.increment(computed.len());
+
+    Ok(computed)
+}
+
 /// layout for nonlinearity check.
 pub fn nonlinearity<F: PrimeField + TensorType + PartialOrd>(
     config: &BaseConfig<F>,
@@ -2249,12 +3886,13 @@ pub fn nonlinearity<F: PrimeField + TensorType + PartialOrd>(
     let timer = instant::Instant::now();
 
     let x = values[0].clone();
+    let (lookup_input, lookup_output, lookup_index) = config.lookup_vars(nl);
 
     let removal_indices = values[0].get_const_indices()?;
     let removal_indices: HashSet<&usize> = HashSet::from_iter(removal_indices.iter());
     let removal_indices_ptr = &removal_indices;
 
-    let w = region.assign_with_omissions(&config.lookup_input, &x, removal_indices_ptr)?;
+    let w = region.assign_with_omissions(lookup_input, &x, removal_indices_ptr)?;
 
     let output = w.get_inner_tensor()?.par_enum_map(|i, e| {
         Ok::<_, TensorError>(if let Some(f) = e.get_felt_eval() {
@@ -2270,7 +3908,12 @@ pub fn nonlinearity<F: PrimeField + TensorType + PartialOrd>(
 
     let assigned_len = x.len() - removal_indices.len();
     let mut output =
-        region.assign_with_omissions(&config.lookup_output, &output.into(), removal_indices_ptr)?;
+        region.assign_with_omissions(lookup_output, &output.into(), removal_indices_ptr)?;
+
+    // out of range values map to 0 so a range check only holds if the output is the input itself
+    if let LookupOp::RangeCheck { .. } = nl {
+        region.constrain_equal(&w, &output)?;
+    }
 
     let is_dummy = region.is_dummy();
 
@@ -2295,13 +3938,11 @@ pub fn nonlinearity<F: PrimeField + TensorType + PartialOrd>(
         })?
         .into();
 
-    region.assign_with_omissions(&config.lookup_index, &table_index, removal_indices_ptr)?;
+    region.assign_with_omissions(lookup_index, &table_index, removal_indices_ptr)?;
 
     if !is_dummy {
         (0..assigned_len).for_each(|i| {
-            let (x, y, z) = config
-                .lookup_input
-                .cartesian_coord(region.linear_coord() + i);
+            let (x, y, z) = lookup_input.cartesian_coord(region.linear_coord() + i);
             let selector = config.lookup_selectors.get(&(nl.clone(), x, y));
             region.enable(selector, z).unwrap();
         });
@@ -2323,6 +3964,88 @@ pub fn nonlinearity<F: PrimeField + TensorType + PartialOrd>(
     Ok(output)
 }
 
+/// Shortcut for [nonlinearity] in a dummy region: consumes the same rows and computes the same
+/// output, but skips assigning the input, output and table index.
+pub fn dummy_nonlinearity<F: PrimeField + TensorType + PartialOrd>(
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+    nl: &LookupOp,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let x = &values[0];
+    let removal_indices = x.get_const_indices()?;
+
+    let mut output: ValTensor<F> = x
+        .get_inner_tensor()?
+        .par_enum_map(|_, e| {
+            Ok::<_, TensorError>(match e.get_felt_eval() {
+                Some(f) => {
+                    let res = Op::<F>::f(nl, &[Tensor::from(vec![f].into_iter())])?.output[0];
+                    if e.is_constant() {
+                        ValType::Constant(res)
+                    } else {
+                        Value::known(res).into()
+                    }
+                }
+                None => Value::<F>::unknown().into(),
+            })
+        })?
+        .into();
+
+    region.increment(x.len() - removal_indices.len());
+    output.reshape(x.dims())?;
+
+    Ok(output)
+}
+
+/// Layout for a lookup over two inputs, see [BaseConfig::configure_binary_lookup].
+pub fn binary_nonlinearity<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 2],
+    op: &BinaryLookupOp,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    if values[0].dims() != values[1].dims() {
+        return Err(Box::new(TensorError::DimMismatch(Op::<F>::as_string(op))));
+    }
+
+    let lhs = region.assign(&config.lookup_input, &values[0])?;
+    let rhs = region.assign(&config.lookup_index, &values[1])?;
+
+    let rhs_inner = rhs.get_inner_tensor()?;
+    let output = lhs.get_inner_tensor()?.par_enum_map(|i, a| {
+        Ok::<_, TensorError>(
+            match (
+                a.get_felt_eval(),
+                rhs_inner.get_flat_index(i).get_felt_eval(),
+            ) {
+                (Some(a), Some(b)) => Value::known(i128_to_felt::<F>(
+                    op.apply(felt_to_i128(a), felt_to_i128(b)),
+                ))
+                .into(),
+                _ => Value::<F>::unknown().into(),
+            },
+        )
+    })?;
+
+    let mut output = region.assign(&config.lookup_output, &output.into())?;
+
+    if !region.is_dummy() {
+        (0..lhs.len()).for_each(|i| {
+            let (x, y, z) = config
+                .lookup_input
+                .cartesian_coord(region.linear_coord() + i);
+            let selector = config.binary_lookup_selectors.get(&(op.clone(), x, y));
+            region.enable(selector, z).unwrap();
+        });
+    }
+
+    region.increment(lhs.len());
+
+    output.reshape(values[0].dims())?;
+
+    Ok(output)
+}
+
 /// mean function layout
 pub fn mean<F: PrimeField + TensorType + PartialOrd>(
     config: &BaseConfig<F>,
@@ -2725,6 +4448,37 @@ pub fn softmax<F: PrimeField + TensorType + PartialOrd>(
     Ok(softmax)
 }
 
+/// Softmax over the flattened input, shifted by its [accumulated_max] before exponentiating for stability.
+/// Scale flow, for an input at scale `s`:
+/// - the shifted input `x - max(x)` is at scale `s` and non-positive, so the [LookupOp::Exp] outputs lie in `[0, s]`;
+/// - their sum is at scale `s` and bounded by `n * s` for `n` elements;
+/// - the [LookupOp::Recip] table, at scale `s^2`, maps the sum back to its reciprocal at scale `s`;
+/// - the product of the two is the softmax at scale `s^2`, bounded by `s^2`.
+///
+/// The Exp table must therefore cover `[min(x) - max(x), 0]` and the Recip table `[0, n * s]`, and the
+/// differences range-checked by the max must fit in the configured lookups.
+///
+/// Requires [BaseOp::ReduceMax] to have been configured via [BaseConfig::configure_base_op].
+pub fn stable_softmax<F: PrimeField + TensorType + PartialOrd>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+    scale: utils::F32,
+) -> Result<ValTensor<F>, Box<dyn Error>> {
+    let mut input = values[0].clone();
+    input.flatten();
+
+    // the input is assigned once so that the max and the shift constrain the same cells
+    let input = identity(config, region, &[input])?;
+    let max = accumulated_max(config, region, &[input.clone()])?;
+    let shifted = pairwise(config, region, &[input, max], BaseOp::Sub)?;
+
+    let mut output = softmax(config, region, &[shifted], scale)?;
+    output.reshape(values[0].dims())?;
+
+    Ok(output)
+}
+
 /// Checks that the percent error between the expected public output and the actual output value
 /// is within the percent error expressed by the `tol` input, where `tol == 1.0` means the percent
 /// error tolerance is 1 percent.
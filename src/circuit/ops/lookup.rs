@@ -1,10 +1,17 @@
 use super::*;
+#[cfg(feature = "python-bindings")]
+use pyo3::{
+    conversion::{FromPyObject, PyTryFrom},
+    exceptions::PyValueError,
+    prelude::*,
+    types::PyString,
+};
 use serde::{Deserialize, Serialize};
-use std::error::Error;
+use std::{error::Error, fmt, str::FromStr};
 
 use crate::{
-    circuit::{layouts, utils},
-    fieldutils::{felt_to_i128, i128_to_felt},
+    circuit::{layouts, utils, CircuitError},
+    fieldutils::{felt_to_i128, i128_to_felt, try_i128_to_felt},
     graph::{multiplier_to_scale, scale_to_multiplier},
     tensor::{self, Tensor, TensorError, TensorType},
 };
@@ -56,6 +63,12 @@ pub enum LookupOp {
     Sigmoid {
         scale: utils::F32,
     },
+    Silu {
+        scale: utils::F32,
+    },
+    HardSigmoid {
+        scale: utils::F32,
+    },
     Ln {
         scale: utils::F32,
     },
@@ -95,12 +108,36 @@ pub enum LookupOp {
     Tanh {
         scale: utils::F32,
     },
+    HardTanh {
+        scale: utils::F32,
+    },
     ATanh {
         scale: utils::F32,
     },
     Erf {
         scale: utils::F32,
     },
+    Gelu {
+        scale: utils::F32,
+    },
+    ExactGelu {
+        scale: utils::F32,
+    },
+    Softplus {
+        scale: utils::F32,
+    },
+    Mish {
+        scale: utils::F32,
+    },
+    Elu {
+        scale: utils::F32,
+        alpha: utils::F32,
+    },
+    Selu {
+        scale: utils::F32,
+        alpha: utils::F32,
+        gamma: utils::F32,
+    },
     GreaterThan {
         a: utils::F32,
     },
@@ -115,6 +152,15 @@ pub enum LookupOp {
     },
     Sign,
     KroneckerDelta,
+    /// Constrains values to `[0, 2^bits)`, in range values map to themselves and the rest to 0
+    RangeCheck {
+        bits: usize,
+    },
+    /// Divides by `2^bits`, rounding to the nearest integer with ties away from zero (as [LookupOp::Div] does),
+    /// eg. `5 >> 1 = 3` and `-5 >> 1 = -3`
+    RightShift {
+        bits: usize,
+    },
     Pow {
         scale: utils::F32,
         a: utils::F32,
@@ -128,6 +174,276 @@ impl LookupOp {
         let range = range as i128;
         (-range, range)
     }
+
+    /// Computes the `(input, output)` rows of the op's table over the inclusive `range`, as laid out by
+    /// [Table::layout](crate::circuit::table::Table::layout), without needing a [ConstraintSystem](halo2_proofs::plonk::ConstraintSystem)
+    /// or a layouter, eg. to cache tables on the host.
+    pub fn tabulate<F: PrimeField + TensorType + PartialOrd>(
+        &self,
+        range: (i128, i128),
+    ) -> Result<Vec<(i128, F)>, TensorError> {
+        let inputs = Tensor::from(range.0..=range.1)
+            .enum_map(|_, x| try_i128_to_felt::<F>(x).map_err(|_| TensorError::FeltError))?;
+        let evals = Op::<F>::f(self, &[inputs])?;
+        Ok((range.0..=range.1)
+            .zip(evals.output.iter().cloned())
+            .collect())
+    }
+
+    /// Checks disabled lookup rows against the (input, output) pair `(x, y)` rather than the first row of the table,
+    /// eg. when the first input collides with a real domain value. `(x, y)` must itself be a row of the table.
+    pub fn with_default_pair(self, x: i128, y: i128) -> LookupWithDefault {
+        LookupWithDefault {
+            op: self,
+            default_pair: Some((x, y)),
+        }
+    }
+}
+
+/// A [LookupOp] with an optional override of the default pair that disabled lookup rows are checked against,
+/// see [LookupOp::with_default_pair].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LookupWithDefault {
+    /// The op the lookup is for
+    pub op: LookupOp,
+    /// The (input, output) pair to use for disabled rows, the first row of the table if `None`
+    pub default_pair: Option<(i128, i128)>,
+}
+
+impl From<&LookupOp> for LookupWithDefault {
+    fn from(op: &LookupOp) -> Self {
+        LookupWithDefault {
+            op: op.clone(),
+            default_pair: None,
+        }
+    }
+}
+
+impl From<&LookupWithDefault> for LookupWithDefault {
+    fn from(nl: &LookupWithDefault) -> Self {
+        nl.clone()
+    }
+}
+
+impl fmt::Display for LookupOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LookupOp::Abs => write!(f, "ABS"),
+            LookupOp::Ceil { scale } => write!(f, "CEIL(scale={})", scale),
+            LookupOp::Floor { scale } => write!(f, "FLOOR(scale={})", scale),
+            LookupOp::Round { scale } => write!(f, "ROUND(scale={})", scale),
+            LookupOp::RoundHalfToEven { scale } => write!(f, "ROUND_HALF_TO_EVEN(scale={})", scale),
+            LookupOp::Pow { a, scale } => write!(f, "POW(scale={}, exponent={})", scale, a),
+            LookupOp::KroneckerDelta => write!(f, "K_DELTA"),
+            LookupOp::RangeCheck { bits } => write!(f, "RANGE_CHECK(bits={})", bits),
+            LookupOp::RightShift { bits } => write!(f, "RIGHT_SHIFT(bits={})", bits),
+            LookupOp::Max { scales, a } => write!(f, "MAX(scales={:?}, a={})", scales, a),
+            LookupOp::Min { scales, a } => write!(f, "MIN(scales={:?}, a={})", scales, a),
+            LookupOp::Sign => write!(f, "SIGN"),
+            LookupOp::GreaterThan { a } => write!(f, "GREATER_THAN(a={})", a),
+            LookupOp::GreaterThanEqual { a } => write!(f, "GREATER_THAN_EQUAL(a={})", a),
+            LookupOp::LessThan { a } => write!(f, "LESS_THAN(a={})", a),
+            LookupOp::LessThanEqual { a } => write!(f, "LESS_THAN_EQUAL(a={})", a),
+            LookupOp::Recip { scale } => write!(f, "RECIP(scale={})", scale),
+            LookupOp::Div { denom } => write!(f, "DIV(denom={})", denom),
+            LookupOp::Ln { scale } => write!(f, "LN(scale={})", scale),
+            LookupOp::ReLU => write!(f, "RELU"),
+            LookupOp::LeakyReLU { slope: a } => write!(f, "L_RELU(slope={})", a),
+            LookupOp::Sigmoid { scale } => write!(f, "SIGMOID(scale={})", scale),
+            LookupOp::Silu { scale } => write!(f, "SILU(scale={})", scale),
+            LookupOp::HardSigmoid { scale } => write!(f, "HARD_SIGMOID(scale={})", scale),
+            LookupOp::Sqrt { scale } => write!(f, "SQRT(scale={})", scale),
+            LookupOp::Erf { scale } => write!(f, "ERF(scale={})", scale),
+            LookupOp::Gelu { scale } => write!(f, "GELU(scale={})", scale),
+            LookupOp::ExactGelu { scale } => write!(f, "EXACT_GELU(scale={})", scale),
+            LookupOp::Softplus { scale } => write!(f, "SOFTPLUS(scale={})", scale),
+            LookupOp::Mish { scale } => write!(f, "MISH(scale={})", scale),
+            LookupOp::Elu { scale, alpha } => write!(f, "ELU(scale={}, alpha={})", scale, alpha),
+            LookupOp::Selu {
+                scale,
+                alpha,
+                gamma,
+            } => write!(f, "SELU(scale={}, alpha={}, gamma={})", scale, alpha, gamma),
+            LookupOp::Rsqrt { scale } => write!(f, "RSQRT(scale={})", scale),
+            LookupOp::Exp { scale } => write!(f, "EXP(scale={})", scale),
+            LookupOp::Tan { scale } => write!(f, "TAN(scale={})", scale),
+            LookupOp::ATan { scale } => write!(f, "ATAN(scale={})", scale),
+            LookupOp::Tanh { scale } => write!(f, "TANH(scale={})", scale),
+            LookupOp::HardTanh { scale } => write!(f, "HARD_TANH(scale={})", scale),
+            LookupOp::ATanh { scale } => write!(f, "ATANH(scale={})", scale),
+            LookupOp::Cos { scale } => write!(f, "COS(scale={})", scale),
+            LookupOp::ACos { scale } => write!(f, "ACOS(scale={})", scale),
+            LookupOp::Cosh { scale } => write!(f, "COSH(scale={})", scale),
+            LookupOp::ACosh { scale } => write!(f, "ACOSH(scale={})", scale),
+            LookupOp::Sin { scale } => write!(f, "SIN(scale={})", scale),
+            LookupOp::ASin { scale } => write!(f, "ASIN(scale={})", scale),
+            LookupOp::Sinh { scale } => write!(f, "SINH(scale={})", scale),
+            LookupOp::ASinh { scale } => write!(f, "ASINH(scale={})", scale),
+        }
+    }
+}
+
+impl FromStr for LookupOp {
+    type Err = CircuitError;
+
+    /// Parses the string representation of the op, eg. `SIGMOID(scale=16)`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || CircuitError::InvalidOpString(s.to_string());
+        let (name, params) = utils::parse_op_string(s).ok_or_else(err)?;
+        let param = |key: &str| utils::parse_op_param::<utils::F32>(&params, key).ok_or_else(err);
+        let op = match name {
+            "ABS" => LookupOp::Abs,
+            "CEIL" => LookupOp::Ceil {
+                scale: param("scale")?,
+            },
+            "FLOOR" => LookupOp::Floor {
+                scale: param("scale")?,
+            },
+            "ROUND" => LookupOp::Round {
+                scale: param("scale")?,
+            },
+            "ROUND_HALF_TO_EVEN" => LookupOp::RoundHalfToEven {
+                scale: param("scale")?,
+            },
+            "POW" => LookupOp::Pow {
+                scale: param("scale")?,
+                a: param("exponent")?,
+            },
+            "K_DELTA" => LookupOp::KroneckerDelta,
+            "RANGE_CHECK" => LookupOp::RangeCheck {
+                bits: utils::parse_op_param(&params, "bits").ok_or_else(err)?,
+            },
+            "RIGHT_SHIFT" => LookupOp::RightShift {
+                bits: utils::parse_op_param(&params, "bits").ok_or_else(err)?,
+            },
+            "MAX" => LookupOp::Max {
+                scales: utils::parse_op_pair_param(&params, "scales").ok_or_else(err)?,
+                a: param("a")?,
+            },
+            "MIN" => LookupOp::Min {
+                scales: utils::parse_op_pair_param(&params, "scales").ok_or_else(err)?,
+                a: param("a")?,
+            },
+            "SIGN" => LookupOp::Sign,
+            "GREATER_THAN" => LookupOp::GreaterThan { a: param("a")? },
+            "GREATER_THAN_EQUAL" => LookupOp::GreaterThanEqual { a: param("a")? },
+            "LESS_THAN" => LookupOp::LessThan { a: param("a")? },
+            "LESS_THAN_EQUAL" => LookupOp::LessThanEqual { a: param("a")? },
+            "RECIP" => LookupOp::Recip {
+                scale: param("scale")?,
+            },
+            "DIV" => LookupOp::Div {
+                denom: param("denom")?,
+            },
+            "LN" => LookupOp::Ln {
+                scale: param("scale")?,
+            },
+            "RELU" => LookupOp::ReLU,
+            "L_RELU" => LookupOp::LeakyReLU {
+                slope: param("slope")?,
+            },
+            "SIGMOID" => LookupOp::Sigmoid {
+                scale: param("scale")?,
+            },
+            "SILU" => LookupOp::Silu {
+                scale: param("scale")?,
+            },
+            "HARD_SIGMOID" => LookupOp::HardSigmoid {
+                scale: param("scale")?,
+            },
+            "SQRT" => LookupOp::Sqrt {
+                scale: param("scale")?,
+            },
+            "ERF" => LookupOp::Erf {
+                scale: param("scale")?,
+            },
+            "GELU" => LookupOp::Gelu {
+                scale: param("scale")?,
+            },
+            "EXACT_GELU" => LookupOp::ExactGelu {
+                scale: param("scale")?,
+            },
+            "SOFTPLUS" => LookupOp::Softplus {
+                scale: param("scale")?,
+            },
+            "MISH" => LookupOp::Mish {
+                scale: param("scale")?,
+            },
+            "ELU" => LookupOp::Elu {
+                scale: param("scale")?,
+                alpha: param("alpha")?,
+            },
+            "SELU" => LookupOp::Selu {
+                scale: param("scale")?,
+                alpha: param("alpha")?,
+                gamma: param("gamma")?,
+            },
+            "RSQRT" => LookupOp::Rsqrt {
+                scale: param("scale")?,
+            },
+            "EXP" => LookupOp::Exp {
+                scale: param("scale")?,
+            },
+            "TAN" => LookupOp::Tan {
+                scale: param("scale")?,
+            },
+            "ATAN" => LookupOp::ATan {
+                scale: param("scale")?,
+            },
+            "TANH" => LookupOp::Tanh {
+                scale: param("scale")?,
+            },
+            "HARD_TANH" => LookupOp::HardTanh {
+                scale: param("scale")?,
+            },
+            "ATANH" => LookupOp::ATanh {
+                scale: param("scale")?,
+            },
+            "COS" => LookupOp::Cos {
+                scale: param("scale")?,
+            },
+            "ACOS" => LookupOp::ACos {
+                scale: param("scale")?,
+            },
+            "COSH" => LookupOp::Cosh {
+                scale: param("scale")?,
+            },
+            "ACOSH" => LookupOp::ACosh {
+                scale: param("scale")?,
+            },
+            "SIN" => LookupOp::Sin {
+                scale: param("scale")?,
+            },
+            "ASIN" => LookupOp::ASin {
+                scale: param("scale")?,
+            },
+            "SINH" => LookupOp::Sinh {
+                scale: param("scale")?,
+            },
+            "ASINH" => LookupOp::ASinh {
+                scale: param("scale")?,
+            },
+            _ => return Err(err()),
+        };
+        Ok(op)
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Converts LookupOp into a PyObject (Required for LookupOp to be compatible with Python)
+impl IntoPy<PyObject> for LookupOp {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_string().to_object(py)
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Obtains LookupOp from PyObject (Required for LookupOp to be compatible with Python)
+impl<'source> FromPyObject<'source> for LookupOp {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        let trystr = <PyString as PyTryFrom>::try_from(ob)?;
+        LookupOp::from_str(&trystr.to_string()).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
 }
 
 impl<F: PrimeField + TensorType + PartialOrd> Op<F> for LookupOp {
@@ -152,6 +468,12 @@ impl<F: PrimeField + TensorType + PartialOrd> Op<F> for LookupOp {
                 a.0.into(),
             )),
             LookupOp::KroneckerDelta => Ok(tensor::ops::nonlinearities::kronecker_delta(&x)),
+            LookupOp::RangeCheck { bits } => {
+                Ok(tensor::ops::nonlinearities::range_check(&x, *bits))
+            }
+            LookupOp::RightShift { bits } => {
+                Ok(tensor::ops::nonlinearities::right_shift(&x, *bits))
+            }
             LookupOp::Max { scales, a } => Ok(tensor::ops::nonlinearities::max(
                 &x,
                 scales.0,
@@ -193,9 +515,36 @@ impl<F: PrimeField + TensorType + PartialOrd> Op<F> for LookupOp {
             LookupOp::Sigmoid { scale } => {
                 Ok(tensor::ops::nonlinearities::sigmoid(&x, scale.into()))
             }
+            LookupOp::Silu { scale } => Ok(tensor::ops::nonlinearities::silu(&x, scale.into())),
+            LookupOp::HardSigmoid { scale } => {
+                Ok(tensor::ops::nonlinearities::hard_sigmoid(&x, scale.into()))
+            }
             LookupOp::Sqrt { scale } => Ok(tensor::ops::nonlinearities::sqrt(&x, scale.into())),
             LookupOp::Rsqrt { scale } => Ok(tensor::ops::nonlinearities::rsqrt(&x, scale.into())),
             LookupOp::Erf { scale } => Ok(tensor::ops::nonlinearities::erffunc(&x, scale.into())),
+            LookupOp::Gelu { scale } => Ok(tensor::ops::nonlinearities::gelu(&x, scale.into())),
+            LookupOp::ExactGelu { scale } => {
+                Ok(tensor::ops::nonlinearities::exact_gelu(&x, scale.into()))
+            }
+            LookupOp::Softplus { scale } => {
+                Ok(tensor::ops::nonlinearities::softplus(&x, scale.into()))
+            }
+            LookupOp::Mish { scale } => Ok(tensor::ops::nonlinearities::mish(&x, scale.into())),
+            LookupOp::Elu { scale, alpha } => Ok(tensor::ops::nonlinearities::elu(
+                &x,
+                scale.into(),
+                alpha.into(),
+            )),
+            LookupOp::Selu {
+                scale,
+                alpha,
+                gamma,
+            } => Ok(tensor::ops::nonlinearities::selu(
+                &x,
+                scale.into(),
+                alpha.into(),
+                gamma.into(),
+            )),
             LookupOp::Exp { scale } => Ok(tensor::ops::nonlinearities::exp(&x, scale.into())),
             LookupOp::Ln { scale } => Ok(tensor::ops::nonlinearities::ln(&x, scale.into())),
             LookupOp::Cos { scale } => Ok(tensor::ops::nonlinearities::cos(&x, scale.into())),
@@ -210,6 +559,9 @@ impl<F: PrimeField + TensorType + PartialOrd> Op<F> for LookupOp {
             LookupOp::ATan { scale } => Ok(tensor::ops::nonlinearities::atan(&x, scale.into())),
             LookupOp::ATanh { scale } => Ok(tensor::ops::nonlinearities::atanh(&x, scale.into())),
             LookupOp::Tanh { scale } => Ok(tensor::ops::nonlinearities::tanh(&x, scale.into())),
+            LookupOp::HardTanh { scale } => {
+                Ok(tensor::ops::nonlinearities::hard_tanh(&x, scale.into()))
+            }
         }?;
 
         let output = res.map(|x| i128_to_felt(x));
@@ -223,42 +575,12 @@ impl<F: PrimeField + TensorType + PartialOrd> Op<F> for LookupOp {
     /// Returns the name of the operation
     fn as_string(&self) -> String {
         match self {
-            LookupOp::Abs => "ABS".into(),
-            LookupOp::Ceil { scale } => format!("CEIL(scale={})", scale),
-            LookupOp::Floor { scale } => format!("FLOOR(scale={})", scale),
-            LookupOp::Round { scale } => format!("ROUND(scale={})", scale),
-            LookupOp::RoundHalfToEven { scale } => format!("ROUND_HALF_TO_EVEN(scale={})", scale),
-            LookupOp::Pow { a, scale } => format!("POW(scale={}, exponent={})", scale, a),
-            LookupOp::KroneckerDelta => "K_DELTA".into(),
-            LookupOp::Max { scales, a } => format!("MAX(scales={:?}, a={})", scales, a),
-            LookupOp::Min { scales, a } => format!("MIN(scales={:?}, a={})", scales, a),
-            LookupOp::Sign => "SIGN".into(),
+            // the comparison thresholds are only part of the parseable representation, see [fmt::Display]
             LookupOp::GreaterThan { .. } => "GREATER_THAN".into(),
             LookupOp::GreaterThanEqual { .. } => "GREATER_THAN_EQUAL".into(),
             LookupOp::LessThan { .. } => "LESS_THAN".into(),
             LookupOp::LessThanEqual { .. } => "LESS_THAN_EQUAL".into(),
-            LookupOp::Recip { scale, .. } => format!("RECIP(scale={})", scale),
-            LookupOp::Div { denom, .. } => format!("DIV(denom={})", denom),
-            LookupOp::Ln { scale } => format!("LN(scale={})", scale),
-            LookupOp::ReLU => "RELU".to_string(),
-            LookupOp::LeakyReLU { slope: a } => format!("L_RELU(slope={})", a),
-            LookupOp::Sigmoid { scale } => format!("SIGMOID(scale={})", scale),
-            LookupOp::Sqrt { scale } => format!("SQRT(scale={})", scale),
-            LookupOp::Erf { scale } => format!("ERF(scale={})", scale),
-            LookupOp::Rsqrt { scale } => format!("RSQRT(scale={})", scale),
-            LookupOp::Exp { scale } => format!("EXP(scale={})", scale),
-            LookupOp::Tan { scale } => format!("TAN(scale={})", scale),
-            LookupOp::ATan { scale } => format!("ATAN(scale={})", scale),
-            LookupOp::Tanh { scale } => format!("TANH(scale={})", scale),
-            LookupOp::ATanh { scale } => format!("ATANH(scale={})", scale),
-            LookupOp::Cos { scale } => format!("COS(scale={})", scale),
-            LookupOp::ACos { scale } => format!("ACOS(scale={})", scale),
-            LookupOp::Cosh { scale } => format!("COSH(scale={})", scale),
-            LookupOp::ACosh { scale } => format!("ACOSH(scale={})", scale),
-            LookupOp::Sin { scale } => format!("SIN(scale={})", scale),
-            LookupOp::ASin { scale } => format!("ASIN(scale={})", scale),
-            LookupOp::Sinh { scale } => format!("SINH(scale={})", scale),
-            LookupOp::ASinh { scale } => format!("ASINH(scale={})", scale),
+            _ => self.to_string(),
         }
     }
 
@@ -276,6 +598,19 @@ impl<F: PrimeField + TensorType + PartialOrd> Op<F> for LookupOp {
         )?))
     }
 
+    fn dummy_layout(
+        &self,
+        _config: &mut crate::circuit::BaseConfig<F>,
+        region: &mut RegionCtx<F>,
+        values: &[ValTensor<F>],
+    ) -> Result<Option<ValTensor<F>>, Box<dyn Error>> {
+        Ok(Some(layouts::dummy_nonlinearity(
+            region,
+            values[..].try_into()?,
+            self,
+        )?))
+    }
+
     /// Returns the scale of the output of the operation.
     fn out_scale(&self, inputs_scale: Vec<crate::Scale>) -> crate::Scale {
         match self {
@@ -286,6 +621,7 @@ impl<F: PrimeField + TensorType + PartialOrd> Op<F> for LookupOp {
                 }
                 scale
             }
+            LookupOp::RightShift { bits } => inputs_scale[0] - *bits as crate::Scale,
             LookupOp::Recip { scale } => {
                 let mut out_scale = inputs_scale[0];
                 out_scale +=
@@ -314,3 +650,82 @@ impl<F: PrimeField + TensorType + PartialOrd> Op<F> for LookupOp {
         Box::new(self.clone()) // Forward to the derive(Clone) impl
     }
 }
+
+#[allow(missing_docs)]
+/// An enum representing lookups keyed on two inputs, eg. a power with a per-element exponent.
+/// These act on the raw integer values of both inputs.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+pub enum BinaryLookupOp {
+    Pow,
+    Div,
+    Max,
+}
+
+impl BinaryLookupOp {
+    /// Applies the op to a single pair of inputs.
+    /// Negative exponents map to 0, as does division by 0.
+    pub fn apply(&self, a: i128, b: i128) -> i128 {
+        match self {
+            BinaryLookupOp::Pow => u32::try_from(b).map(|b| a.saturating_pow(b)).unwrap_or(0),
+            BinaryLookupOp::Div => match b {
+                0 => 0,
+                _ => (a as f64 / b as f64).round() as i128,
+            },
+            BinaryLookupOp::Max => a.max(b),
+        }
+    }
+}
+
+impl<F: PrimeField + TensorType + PartialOrd> Op<F> for BinaryLookupOp {
+    /// Returns a reference to the Any trait.
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn f(&self, x: &[Tensor<F>]) -> Result<ForwardResult<F>, TensorError> {
+        if x.len() != 2 || x[0].dims() != x[1].dims() {
+            return Err(TensorError::DimMismatch(Op::<F>::as_string(self)));
+        }
+        let mut output: Tensor<F> = x[0]
+            .iter()
+            .zip(x[1].iter())
+            .map(|(a, b)| i128_to_felt(self.apply(felt_to_i128(*a), felt_to_i128(*b))))
+            .into();
+        output.reshape(x[0].dims());
+
+        Ok(ForwardResult {
+            output,
+            intermediate_lookups: vec![],
+        })
+    }
+
+    fn as_string(&self) -> String {
+        match self {
+            BinaryLookupOp::Pow => "BINARY_POW".into(),
+            BinaryLookupOp::Div => "BINARY_DIV".into(),
+            BinaryLookupOp::Max => "BINARY_MAX".into(),
+        }
+    }
+
+    fn layout(
+        &self,
+        config: &mut crate::circuit::BaseConfig<F>,
+        region: &mut RegionCtx<F>,
+        values: &[ValTensor<F>],
+    ) -> Result<Option<ValTensor<F>>, Box<dyn Error>> {
+        Ok(Some(layouts::binary_nonlinearity(
+            config,
+            region,
+            values[..].try_into()?,
+            self,
+        )?))
+    }
+
+    fn out_scale(&self, inputs_scale: Vec<crate::Scale>) -> crate::Scale {
+        inputs_scale[0]
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Op<F>> {
+        Box::new(self.clone()) // Forward to the derive(Clone) impl
+    }
+}
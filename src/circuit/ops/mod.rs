@@ -3,6 +3,8 @@ use std::{any::Any, error::Error};
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    circuit::CircuitError,
+    fieldutils::felt_to_i128,
     graph::quantize_tensor,
     tensor::{self, Tensor, TensorError, TensorType, ValTensor},
 };
@@ -14,6 +16,9 @@ use self::{lookup::LookupOp, region::RegionCtx};
 pub mod base;
 ///
 pub mod chip;
+/// A [halo2_proofs::dev::MockProver] harness for testing a single op, see [chip::BaseConfig::prove_op]
+#[cfg(any(test, feature = "test-utils"))]
+pub mod harness;
 ///
 pub mod hybrid;
 /// Layouts for specific functions (composed of base ops)
@@ -47,6 +52,34 @@ pub trait Op<F: PrimeField + TensorType + PartialOrd>: std::fmt::Debug + Send +
         values: &[ValTensor<F>],
     ) -> Result<Option<ValTensor<F>>, Box<dyn Error>>;
 
+    /// Layouts the operation in a dummy region, where only the output and the rows consumed matter.
+    /// Ops can override this to skip work that has no effect when sizing the circuit.
+    fn dummy_layout(
+        &self,
+        config: &mut crate::circuit::BaseConfig<F>,
+        region: &mut RegionCtx<F>,
+        values: &[ValTensor<F>],
+    ) -> Result<Option<ValTensor<F>>, Box<dyn Error>> {
+        self.layout(config, region, values)
+    }
+
+    /// Matches an [Op] with several outputs, eg. a quotient and a remainder, to all of its outputs.
+    /// Defaults to the single output of [Op::f].
+    fn f_multi(&self, x: &[Tensor<F>]) -> Result<Vec<Tensor<F>>, TensorError> {
+        Ok(vec![self.f(x)?.output])
+    }
+
+    /// Layouts an operation with several outputs, in the same order as [Op::f_multi].
+    /// Defaults to the single output of [Op::layout].
+    fn layout_multi(
+        &self,
+        config: &mut crate::circuit::BaseConfig<F>,
+        region: &mut RegionCtx<F>,
+        values: &[ValTensor<F>],
+    ) -> Result<Vec<ValTensor<F>>, Box<dyn Error>> {
+        Ok(self.layout(config, region, values)?.into_iter().collect())
+    }
+
     /// Returns the scale of the output of the operation.
     fn out_scale(&self, _: Vec<crate::Scale>) -> crate::Scale;
 
@@ -76,12 +109,13 @@ pub trait Op<F: PrimeField + TensorType + PartialOrd>: std::fmt::Debug + Send +
     /// Returns a reference to the Any trait.
     fn as_any(&self) -> &dyn Any;
 
-    /// Safe mode output checl
+    /// Safe mode output check.
+    /// Returns [crate::circuit::CircuitError::SafeModeMismatch] with the first diverging element if the claimed output differs from the reference.
     fn safe_mode_check(
         &self,
         claimed_output: &ValTensor<F>,
         original_values: &[ValTensor<F>],
-    ) -> Result<(), TensorError> {
+    ) -> Result<(), Box<dyn Error>> {
         let felt_evals = original_values
             .iter()
             .map(|v| {
@@ -89,7 +123,7 @@ pub trait Op<F: PrimeField + TensorType + PartialOrd>: std::fmt::Debug + Send +
                 evals.reshape(v.dims());
                 Ok(evals)
             })
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect::<Result<Vec<_>, TensorError>>()?;
 
         let ref_op: Tensor<F> = self.f(&felt_evals)?.output;
 
@@ -98,7 +132,23 @@ pub trait Op<F: PrimeField + TensorType + PartialOrd>: std::fmt::Debug + Send +
             .map_err(|_| TensorError::FeltError)?;
         output.reshape(claimed_output.dims());
 
-        assert_eq!(output, ref_op);
+        if output.dims() != ref_op.dims() {
+            return Err(Box::new(CircuitError::DimMismatch(self.as_string())));
+        }
+
+        if let Some((index, (claimed, expected))) = output
+            .iter()
+            .zip(ref_op.iter())
+            .enumerate()
+            .find(|(_, (c, r))| c != r)
+        {
+            return Err(Box::new(CircuitError::SafeModeMismatch {
+                op: self.as_string(),
+                index,
+                claimed: felt_to_i128(*claimed),
+                expected: felt_to_i128(*expected),
+            }));
+        }
 
         Ok(())
     }
@@ -1,4 +1,12 @@
-use std::{error::Error, marker::PhantomData};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
 use halo2curves::ff::PrimeField;
 
@@ -11,11 +19,11 @@ use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 
 use crate::{
     circuit::CircuitError,
-    fieldutils::i128_to_felt,
-    tensor::{Tensor, TensorType},
+    fieldutils::{felt_to_i128, i128_to_felt, try_i128_to_felt},
+    tensor::{Tensor, TensorError, TensorType},
 };
 
-use crate::circuit::lookup::LookupOp;
+use crate::circuit::lookup::{BinaryLookupOp, LookupOp};
 
 use super::Op;
 
@@ -24,6 +32,44 @@ pub const RANGE_MULTIPLIER: i128 = 2;
 /// The safety factor offset for the number of rows in the lookup table.
 pub const RESERVED_BLINDING_ROWS_PAD: usize = 3;
 
+/// The domain of a lookup table over `bits` bit inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TableRange {
+    /// `[-2^(bits-1), 2^(bits-1))`, for signed inputs
+    #[default]
+    Signed,
+    /// `[0, 2^bits)`, for unsigned (eg. quantized) inputs
+    Unsigned,
+}
+
+impl TableRange {
+    /// Returns the inclusive `(min, max)` bounds of the domain, as expected by [Table::configure].
+    /// Returns [CircuitError::InvalidSignedBits] unless `bits` is in `1..=126`, so that the number of rows fits.
+    pub fn bounds(&self, bits: usize) -> Result<(i128, i128), CircuitError> {
+        if !(1..=126).contains(&bits) {
+            return Err(CircuitError::InvalidSignedBits(bits));
+        }
+        match self {
+            TableRange::Signed => signed_bounds(bits),
+            TableRange::Unsigned => 1i128
+                .checked_shl(bits as u32)
+                .map(|end| (0, end - 1))
+                .ok_or(CircuitError::InvalidSignedBits(bits)),
+        }
+    }
+}
+
+/// Returns the inclusive `(min, max)` bounds of the signed `bits`-bit range, ie. `[-2^(bits-1), 2^(bits-1))`.
+/// Returns [CircuitError::InvalidSignedBits] unless `bits` is in `1..=128`.
+pub fn signed_bounds(bits: usize) -> Result<(i128, i128), CircuitError> {
+    let min = u32::try_from(bits)
+        .ok()
+        .and_then(|bits| 128u32.checked_sub(bits))
+        .and_then(|shift| i128::MIN.checked_shr(shift))
+        .ok_or(CircuitError::InvalidSignedBits(bits))?;
+    Ok((min, !min))
+}
+
 #[derive(Debug, Clone)]
 ///
 pub struct SelectorConstructor<F: PrimeField> {
@@ -92,6 +138,12 @@ pub struct Table<F: PrimeField> {
     pub is_assigned: bool,
     /// Number of bits used in lookup table.
     pub range: (i128, i128),
+    /// Overrides the (input, output) pair checked by disabled lookup rows, see [LookupOp::with_default_pair].
+    pub default_pair: Option<(i128, i128)>,
+    /// Set once the table has been laid out by any of the configs sharing it through a [TableRegistry].
+    pub(crate) shared_assigned: Option<Arc<AtomicBool>>,
+    /// Set once the input columns have been laid out, for tables whose inputs are shared through a [TableRegistry].
+    pub(crate) inputs_assigned: Option<Arc<AtomicBool>>,
     _marker: PhantomData<F>,
 }
 
@@ -118,6 +170,17 @@ impl<F: PrimeField + TensorType + PartialOrd> Table<F> {
         (first_element, op_f.output[0])
     }
 
+    /// The (input, output) pair that disabled lookup rows are checked against for the column `chunk`:
+    /// the [Self::default_pair] override if it lies in that column, the first element of the column otherwise.
+    pub fn get_default_pair(&self, chunk: usize) -> (F, F) {
+        match self.default_pair {
+            Some((x, y)) if (x - self.range.0) / (self.col_size as i128) == chunk as i128 => {
+                (i128_to_felt(x), i128_to_felt(y))
+            }
+            _ => self.get_first_element(chunk),
+        }
+    }
+
     ///
     pub fn cal_col_size(logrows: usize, reserved_blinding_rows: usize) -> usize {
         2usize.pow(logrows as u32) - reserved_blinding_rows
@@ -138,18 +201,81 @@ impl<F: PrimeField + TensorType + PartialOrd> Table<F> {
 }
 
 impl<F: PrimeField + TensorType + PartialOrd> Table<F> {
+    /// Configures a [BinaryTable] over the cross product of the `lhs_range` and `rhs_range` inputs.
+    /// The table must fit in a single column, errors with [CircuitError::LookupBitWidthTooLarge] otherwise.
+    pub fn configure_binary(
+        cs: &mut ConstraintSystem<F>,
+        lhs_range: (i128, i128),
+        rhs_range: (i128, i128),
+        logrows: usize,
+        op: &BinaryLookupOp,
+    ) -> Result<BinaryTable<F>, CircuitError> {
+        if lhs_range.0 > lhs_range.1 || rhs_range.0 > rhs_range.1 {
+            return Err(CircuitError::LookupInstantiation);
+        }
+        for x in [lhs_range.0, lhs_range.1, rhs_range.0, rhs_range.1] {
+            try_i128_to_felt::<F>(x)?;
+        }
+
+        let range_bits =
+            |range: (i128, i128)| u128::BITS - ((range.1 - range.0) as u128).leading_zeros();
+        let bits = range_bits(lhs_range) + range_bits(rhs_range);
+
+        let reserved_rows = cs.blinding_factors() + RESERVED_BLINDING_ROWS_PAD;
+        let rows = (lhs_range.1 - lhs_range.0 + 1).saturating_mul(rhs_range.1 - rhs_range.0 + 1);
+        if 2usize.pow(logrows as u32) <= reserved_rows
+            || rows > Self::cal_col_size(logrows, reserved_rows) as i128
+        {
+            return Err(CircuitError::LookupBitWidthTooLarge { bits, logrows });
+        }
+
+        log::debug!("binary table ranges: {:?} x {:?}", lhs_range, rhs_range);
+
+        Ok(BinaryTable {
+            op: op.clone(),
+            table_lhs: cs.lookup_table_column(),
+            table_rhs: cs.lookup_table_column(),
+            table_output: cs.lookup_table_column(),
+            lhs_range,
+            rhs_range,
+            is_assigned: false,
+            _marker: PhantomData,
+        })
+    }
+
     /// Configures the table.
+    ///
+    /// A range longer than a column is split across several input / output column pairs, each holding `col_size`
+    /// entries, and lookups select the column holding their input. An 18 bit nonlinearity thus fits under a `logrows`
+    /// smaller than 18, at the cost of more table columns rather than more rows.
+    ///
+    /// Each column leaves room for the blinding rows at the end of the circuit. Errors with
+    /// [CircuitError::LookupBitWidthTooLarge] if no rows are left at `logrows`, or if `preexisting_inputs` can't hold the
+    /// range once these are reserved.
     pub fn configure(
         cs: &mut ConstraintSystem<F>,
         range: (i128, i128),
         logrows: usize,
         nonlinearity: &LookupOp,
         preexisting_inputs: Option<Vec<TableColumn>>,
-    ) -> Table<F> {
+    ) -> Result<Table<F>, CircuitError> {
+        if range.0 > range.1 {
+            return Err(CircuitError::LookupInstantiation);
+        }
+        let bits = u128::BITS - range.1.abs_diff(range.0).leading_zeros();
         let factors = cs.blinding_factors() + RESERVED_BLINDING_ROWS_PAD;
+        if 2usize.pow(logrows as u32) <= factors {
+            return Err(CircuitError::LookupBitWidthTooLarge { bits, logrows });
+        }
         let col_size = Self::cal_col_size(logrows, factors);
         // number of cols needed to store the range
         let num_cols = Self::num_cols_required(range, col_size);
+        if preexisting_inputs
+            .as_ref()
+            .map_or(false, |inputs| inputs.len() < num_cols)
+        {
+            return Err(CircuitError::LookupBitWidthTooLarge { bits, logrows });
+        }
 
         log::debug!("table range: {:?}", range);
 
@@ -172,7 +298,7 @@ impl<F: PrimeField + TensorType + PartialOrd> Table<F> {
             .map(|_| cs.lookup_table_column())
             .collect::<Vec<_>>();
 
-        Table {
+        Ok(Table {
             nonlinearity: nonlinearity.clone(),
             table_inputs,
             table_outputs,
@@ -180,10 +306,36 @@ impl<F: PrimeField + TensorType + PartialOrd> Table<F> {
             selector_constructor: SelectorConstructor::new(num_cols),
             col_size,
             range,
+            default_pair: None,
+            shared_assigned: None,
+            inputs_assigned: None,
             _marker: PhantomData,
+        })
+    }
+
+    /// Whether the table has been laid out, by this config or by another one sharing it through a [TableRegistry].
+    pub fn is_laid_out(&self) -> bool {
+        self.is_assigned
+            || self
+                .shared_assigned
+                .as_ref()
+                .map_or(false, |flag| flag.load(Ordering::SeqCst))
+    }
+
+    /// Whether the input columns still need to be laid out, marking them as laid out.
+    /// Tables outside of a [TableRegistry] rely on `first_table` as all tables of a config share their inputs.
+    pub(crate) fn claim_inputs(&self, first_table: bool) -> bool {
+        match &self.inputs_assigned {
+            Some(flag) => !flag.swap(true, Ordering::SeqCst),
+            None => first_table,
         }
     }
 
+    /// Number of rows the table occupies across all of its columns, available before layout.
+    pub fn table_rows(&self) -> usize {
+        (self.range.1 - self.range.0 + 1) as usize
+    }
+
     /// Take a linear coordinate and output the (column, row) position in the storage block.
     pub fn cartesian_coord(&self, linear_coord: usize) -> (usize, usize) {
         let x = linear_coord / self.col_size;
@@ -191,65 +343,196 @@ impl<F: PrimeField + TensorType + PartialOrd> Table<F> {
         (x, y)
     }
 
+    /// Computes the (input, output) pairs of the table.
+    /// This only reads the table so can safely be run for several tables in parallel, unlike [Self::layout] which must be called sequentially on the layouter.
+    pub fn evaluate(&self) -> Result<(Tensor<F>, Tensor<F>), TensorError> {
+        self.evaluate_rows(0, self.table_rows())
+    }
+
+    /// Computes the (input, output) pairs of the `len` rows of the table starting at row `start`, truncated to the end
+    /// of the table, so that a table can be evaluated a batch at a time (see [Self::layout_streamed]).
+    pub fn evaluate_rows(
+        &self,
+        start: usize,
+        len: usize,
+    ) -> Result<(Tensor<F>, Tensor<F>), TensorError> {
+        let first = self.range.0 + start as i128;
+        let last = (first + len as i128 - 1).min(self.range.1);
+        let (inputs, outputs): (Vec<_>, Vec<_>) = self
+            .nonlinearity
+            .tabulate::<F>((first, last))?
+            .into_iter()
+            .map(|(x, y)| (i128_to_felt::<F>(x), y))
+            .unzip();
+        Ok((
+            Tensor::from(inputs.into_iter()),
+            Tensor::from(outputs.into_iter()),
+        ))
+    }
+
+    /// A stable digest of the table's (input, output) rows, eg. to detect that a cached verifying key was generated
+    /// for a different table. This is the 64-bit FNV-1a hash of the canonical byte representations of the rows' field
+    /// elements, so it is stable across runs and platforms and only changes if the contents of the table do.
+    pub fn content_hash(&self) -> Result<u64, TensorError> {
+        const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+        const FNV_PRIME: u64 = 0x100000001b3;
+
+        let (inputs, outputs) = self.evaluate()?;
+        let mut hash = FNV_OFFSET;
+        for felt in inputs.iter().zip(outputs.iter()).flat_map(|(x, y)| [x, y]) {
+            for byte in felt.to_repr().as_ref() {
+                hash ^= *byte as u64;
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
+        }
+        Ok(hash)
+    }
+
+    /// Checks that the `outputs` computed by [Self::evaluate] fit in the signed `bits`-bit range, rather than
+    /// silently embedding over range values in the table.
+    /// Returns [CircuitError::InvalidSignedBits] unless `bits` is in `1..=128`.
+    pub fn check_output_range(&self, outputs: &Tensor<F>, bits: usize) -> Result<(), CircuitError> {
+        let (min, max) = signed_bounds(bits)?;
+        if outputs
+            .iter()
+            .all(|x| (min..=max).contains(&felt_to_i128(*x)))
+        {
+            Ok(())
+        } else {
+            Err(CircuitError::SignedRangeOverflow(
+                Op::<F>::as_string(&self.nonlinearity),
+                bits,
+            ))
+        }
+    }
+
     /// Assigns values to the constraints generated when calling `configure`.
     pub fn layout(
         &mut self,
         layouter: &mut impl Layouter<F>,
         preassigned_input: bool,
     ) -> Result<(), Box<dyn Error>> {
+        let evals = self.evaluate()?;
+        self.layout_evaluated(layouter, preassigned_input, evals)
+    }
+
+    /// Marks the table as assigned, returning whether it still needs to be laid out.
+    fn claim_layout(&mut self) -> Result<bool, CircuitError> {
         if self.is_assigned {
-            return Err(Box::new(CircuitError::TableAlreadyAssigned));
+            return Err(CircuitError::TableAlreadyAssigned);
+        }
+        self.is_assigned = true;
+        // another config sharing the table may have laid it out already
+        if let Some(flag) = &self.shared_assigned {
+            if flag.swap(true, Ordering::SeqCst) {
+                return Ok(false);
+            }
         }
+        Ok(true)
+    }
 
-        let smallest = self.range.0;
-        let largest = self.range.1;
+    /// Assigns the (input, output) pairs of the rows starting at `start`, which must all lie in the same column.
+    fn assign_rows(
+        &self,
+        table: &mut halo2_proofs::circuit::Table<'_, F>,
+        start: usize,
+        (inputs, outputs): (&[F], &[F]),
+        preassigned_input: bool,
+    ) -> Result<(), halo2_proofs::plonk::Error> {
+        let col_multiplier = self
+            .selector_constructor
+            .get_selector_val_at_idx(start / self.col_size);
+        for (i, (input, output)) in inputs.iter().zip(outputs.iter()).enumerate() {
+            let row_offset = start + i;
+            let (x, y) = self.cartesian_coord(row_offset);
+            if !preassigned_input {
+                table.assign_cell(
+                    || format!("nl_i_col row {}", row_offset),
+                    self.table_inputs[x],
+                    y,
+                    || Value::known(*input * col_multiplier),
+                )?;
+            }
+            table.assign_cell(
+                || format!("nl_o_col row {}", row_offset),
+                self.table_outputs[x],
+                y,
+                || Value::known(*output * col_multiplier),
+            )?;
+        }
+        Ok(())
+    }
 
-        let inputs = Tensor::from(smallest..=largest).map(|x| i128_to_felt(x));
-        let evals = Op::<F>::f(&self.nonlinearity, &[inputs.clone()])?;
-        let chunked_inputs = inputs.chunks(self.col_size);
+    /// Evaluates and assigns the table `batch_rows` rows at a time, so that only a single batch of rows is held in
+    /// memory rather than the whole table as with [Self::layout], eg. for tables over 20 bit inputs.
+    /// Lays out the same table as [Self::layout].
+    pub fn layout_streamed(
+        &mut self,
+        layouter: &mut impl Layouter<F>,
+        preassigned_input: bool,
+        batch_rows: usize,
+    ) -> Result<(), Box<dyn Error>> {
+        if !self.claim_layout()? {
+            return Ok(());
+        }
+        let batch_rows = batch_rows.max(1);
+        let table_rows = self.table_rows();
+
+        // each column has to be assigned within a single call to assign_table
+        for col_start in (0..table_rows).step_by(self.col_size) {
+            let col_end = (col_start + self.col_size).min(table_rows);
+            layouter.assign_table(
+                || "nl table",
+                |mut table| {
+                    for start in (col_start..col_end).step_by(batch_rows) {
+                        let (inputs, outputs) = self
+                            .evaluate_rows(start, batch_rows.min(col_end - start))
+                            .map_err(|_| halo2_proofs::plonk::Error::Synthesis)?;
+                        self.assign_rows(
+                            &mut table,
+                            start,
+                            (&inputs[..], &outputs[..]),
+                            preassigned_input,
+                        )?;
+                    }
+                    Ok(())
+                },
+            )?;
+        }
+        Ok(())
+    }
 
-        self.is_assigned = true;
+    /// Assigns previously computed [Self::evaluate] values to the table.
+    pub fn layout_evaluated(
+        &mut self,
+        layouter: &mut impl Layouter<F>,
+        preassigned_input: bool,
+        (inputs, outputs): (Tensor<F>, Tensor<F>),
+    ) -> Result<(), Box<dyn Error>> {
+        if !self.claim_layout()? {
+            return Ok(());
+        }
 
-        let col_multipliers: Vec<F> = (0..chunked_inputs.len())
-            .map(|x| self.selector_constructor.get_selector_val_at_idx(x))
-            .collect();
+        log::debug!(
+            "laying out {} rows for table {}",
+            self.table_rows(),
+            Op::<F>::as_string(&self.nonlinearity)
+        );
 
-        let _ = chunked_inputs
+        let _ = inputs
+            .chunks(self.col_size)
+            .zip(outputs.chunks(self.col_size))
             .enumerate()
-            .map(|(chunk_idx, inputs)| {
+            .map(|(chunk_idx, rows)| {
                 layouter.assign_table(
                     || "nl table",
                     |mut table| {
-                        let _ = inputs
-                            .iter()
-                            .enumerate()
-                            .map(|(mut row_offset, input)| {
-                                let col_multiplier = col_multipliers[chunk_idx];
-
-                                row_offset += chunk_idx * self.col_size;
-                                let (x, y) = self.cartesian_coord(row_offset);
-                                if !preassigned_input {
-                                    table.assign_cell(
-                                        || format!("nl_i_col row {}", row_offset),
-                                        self.table_inputs[x],
-                                        y,
-                                        || Value::known(*input * col_multiplier),
-                                    )?;
-                                }
-
-                                let output = evals.output[row_offset];
-
-                                table.assign_cell(
-                                    || format!("nl_o_col row {}", row_offset),
-                                    self.table_outputs[x],
-                                    y,
-                                    || Value::known(output * col_multiplier),
-                                )?;
-
-                                Ok(())
-                            })
-                            .collect::<Result<Vec<()>, halo2_proofs::plonk::Error>>()?;
-                        Ok(())
+                        self.assign_rows(
+                            &mut table,
+                            chunk_idx * self.col_size,
+                            rows,
+                            preassigned_input,
+                        )
                     },
                 )
             })
@@ -257,3 +540,137 @@ impl<F: PrimeField + TensorType + PartialOrd> Table<F> {
         Ok(())
     }
 }
+
+/// Lookup tables shared between several [crate::circuit::BaseConfig]s, eg. one per layer, so that an identical
+/// `(op, range, logrows)` table is only allocated and laid out once, see [crate::circuit::BaseConfig::configure_lookup_with_registry].
+#[derive(Clone, Debug)]
+pub struct TableRegistry<F: PrimeField> {
+    tables: BTreeMap<(LookupOp, (i128, i128), usize), Table<F>>,
+}
+
+impl<F: PrimeField> Default for TableRegistry<F> {
+    fn default() -> Self {
+        TableRegistry {
+            tables: BTreeMap::new(),
+        }
+    }
+}
+
+impl<F: PrimeField + TensorType + PartialOrd> TableRegistry<F> {
+    /// Number of distinct tables, each with its own set of table columns.
+    pub fn len(&self) -> usize {
+        self.tables.len()
+    }
+
+    /// Whether no table has been registered.
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    /// The table registered for `nl` over `range` at `logrows`, if any.
+    pub fn get(&self, nl: &LookupOp, range: (i128, i128), logrows: usize) -> Option<&Table<F>> {
+        self.tables.get(&(nl.clone(), range, logrows))
+    }
+
+    /// Registers `table`, unless a table for the same op, range, and logrows already is.
+    pub(crate) fn register(&mut self, logrows: usize, table: &Table<F>) {
+        self.tables
+            .entry((table.nonlinearity.clone(), table.range, logrows))
+            .or_insert_with(|| table.clone());
+    }
+}
+
+/// Halo2 lookup table for element wise ops over two inputs, see [Table::configure_binary].
+/// As the number of rows grows with the product of the input ranges, the table is kept to a single column.
+#[derive(Clone, Debug)]
+pub struct BinaryTable<F: PrimeField> {
+    /// Op to be used in table.
+    pub op: BinaryLookupOp,
+    /// First input to table.
+    pub table_lhs: TableColumn,
+    /// Second input to table.
+    pub table_rhs: TableColumn,
+    /// Output of table.
+    pub table_output: TableColumn,
+    /// Range of the first input.
+    pub lhs_range: (i128, i128),
+    /// Range of the second input.
+    pub rhs_range: (i128, i128),
+    /// Flags if table has been previously assigned to.
+    pub is_assigned: bool,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField + TensorType + PartialOrd> BinaryTable<F> {
+    /// Number of rows the table occupies, available before layout.
+    pub fn table_rows(&self) -> usize {
+        ((self.lhs_range.1 - self.lhs_range.0 + 1) * (self.rhs_range.1 - self.rhs_range.0 + 1))
+            as usize
+    }
+
+    /// The (lhs, rhs, output) row that disabled lookups are checked against.
+    pub fn get_first_element(&self) -> (F, F, F) {
+        let (lhs, rhs) = (self.lhs_range.0, self.rhs_range.0);
+        (
+            i128_to_felt(lhs),
+            i128_to_felt(rhs),
+            i128_to_felt(self.op.apply(lhs, rhs)),
+        )
+    }
+
+    /// Computes the (lhs, rhs, output) rows of the table.
+    pub fn evaluate(&self) -> Result<(Tensor<F>, Tensor<F>, Tensor<F>), TensorError> {
+        let (lhs, rhs): (Vec<_>, Vec<_>) = (self.lhs_range.0..=self.lhs_range.1)
+            .flat_map(|a| (self.rhs_range.0..=self.rhs_range.1).map(move |b| (a, b)))
+            .map(|(a, b)| (i128_to_felt(a), i128_to_felt(b)))
+            .unzip();
+        let (lhs, rhs) = (Tensor::from(lhs.into_iter()), Tensor::from(rhs.into_iter()));
+        let evals = Op::<F>::f(&self.op, &[lhs.clone(), rhs.clone()])?;
+        Ok((lhs, rhs, evals.output))
+    }
+
+    /// Assigns values to the constraints generated when calling [Table::configure_binary].
+    pub fn layout(&mut self, layouter: &mut impl Layouter<F>) -> Result<(), Box<dyn Error>> {
+        if self.is_assigned {
+            return Err(Box::new(CircuitError::TableAlreadyAssigned));
+        }
+
+        log::debug!(
+            "laying out {} rows for table {}",
+            self.table_rows(),
+            Op::<F>::as_string(&self.op)
+        );
+
+        let (lhs, rhs, outputs) = self.evaluate()?;
+        self.is_assigned = true;
+
+        layouter.assign_table(
+            || "binary table",
+            |mut table| {
+                for (row, ((a, b), o)) in lhs.iter().zip(rhs.iter()).zip(outputs.iter()).enumerate()
+                {
+                    table.assign_cell(
+                        || format!("lhs row {}", row),
+                        self.table_lhs,
+                        row,
+                        || Value::known(*a),
+                    )?;
+                    table.assign_cell(
+                        || format!("rhs row {}", row),
+                        self.table_rhs,
+                        row,
+                        || Value::known(*b),
+                    )?;
+                    table.assign_cell(
+                        || format!("output row {}", row),
+                        self.table_output,
+                        row,
+                        || Value::known(*o),
+                    )?;
+                }
+                Ok(())
+            },
+        )?;
+        Ok(())
+    }
+}
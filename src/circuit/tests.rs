@@ -1,7 +1,7 @@
 use crate::circuit::ops::hybrid::HybridOp;
 use crate::circuit::ops::poly::PolyOp;
 use crate::circuit::*;
-use crate::tensor::{Tensor, TensorType, ValTensor, VarTensor};
+use crate::tensor::{Tensor, TensorError, TensorType, ValTensor, VarTensor};
 use halo2_proofs::{
     circuit::{Layouter, SimpleFloorPlanner, Value},
     dev::MockProver,
@@ -11,12 +11,108 @@ use halo2curves::bn256::Fr as F;
 use halo2curves::ff::{Field, PrimeField};
 use ops::lookup::LookupOp;
 use ops::region::RegionCtx;
+use ops::{ForwardResult, Op};
 use rand::rngs::OsRng;
-use std::marker::PhantomData;
+use std::{any::Any, marker::PhantomData};
 
 #[derive(Default)]
 struct TestParams;
 
+fn signed(values: &[i64]) -> ValTensor<F> {
+    let values = values.iter().map(|&v| {
+        let felt = F::from(v.unsigned_abs());
+        Value::known(if v < 0 { -felt } else { felt })
+    });
+    ValTensor::from(Tensor::from(values))
+}
+
+/// Identity op whose laid out output is off by one, as a stand-in for an approximated op
+#[derive(Clone, Debug)]
+struct OffByOne;
+
+impl Op<F> for OffByOne {
+    fn f(&self, x: &[Tensor<F>]) -> Result<ForwardResult<F>, TensorError> {
+        Ok(ForwardResult {
+            output: x[0].clone(),
+            intermediate_lookups: vec![],
+        })
+    }
+
+    fn as_string(&self) -> String {
+        "OFF_BY_ONE".into()
+    }
+
+    fn layout(
+        &self,
+        _: &mut BaseConfig<F>,
+        _: &mut RegionCtx<F>,
+        values: &[ValTensor<F>],
+    ) -> Result<Option<ValTensor<F>>, Box<dyn std::error::Error>> {
+        let claimed: Vec<i64> = values[0]
+            .get_int_evals()?
+            .iter()
+            .map(|x| *x as i64 + 1)
+            .collect();
+        Ok(Some(signed(&claimed)))
+    }
+
+    fn out_scale(&self, in_scales: Vec<crate::Scale>) -> crate::Scale {
+        in_scales[0]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Op<F>> {
+        Box::new(self.clone())
+    }
+}
+
+/// Identity op whose laid out output is missing its last element, as a stand-in for a mis-shaped layout
+#[derive(Clone, Debug)]
+struct DropsLast;
+
+impl Op<F> for DropsLast {
+    fn f(&self, x: &[Tensor<F>]) -> Result<ForwardResult<F>, TensorError> {
+        Ok(ForwardResult {
+            output: x[0].clone(),
+            intermediate_lookups: vec![],
+        })
+    }
+
+    fn as_string(&self) -> String {
+        "DROPS_LAST".into()
+    }
+
+    fn layout(
+        &self,
+        _: &mut BaseConfig<F>,
+        _: &mut RegionCtx<F>,
+        values: &[ValTensor<F>],
+    ) -> Result<Option<ValTensor<F>>, Box<dyn std::error::Error>> {
+        let evals = values[0].get_int_evals()?;
+        let claimed: Vec<i64> = evals
+            .iter()
+            .take(evals.len() - 1)
+            .map(|x| *x as i64)
+            .collect();
+        Ok(Some(signed(&claimed)))
+    }
+
+    fn out_scale(&self, in_scales: Vec<crate::Scale>) -> crate::Scale {
+        in_scales[0]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Op<F>> {
+        Box::new(self.clone())
+    }
+}
+
 #[cfg(test)]
 mod matmul {
 
@@ -44,7 +140,7 @@ mod matmul {
             let a = VarTensor::new_advice(cs, K, 1, LEN * LEN);
             let b = VarTensor::new_advice(cs, K, 1, LEN * LEN);
             let output = VarTensor::new_advice(cs, K, 1, LEN * LEN);
-            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
         }
 
         fn synthesize(
@@ -120,7 +216,7 @@ mod matmul_col_overflow {
             let a = VarTensor::new_advice(cs, K, 1, LEN * LEN * LEN);
             let b = VarTensor::new_advice(cs, K, 1, LEN * LEN * LEN);
             let output = VarTensor::new_advice(cs, K, 1, LEN * LEN * LEN);
-            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
         }
 
         fn synthesize(
@@ -153,7 +249,7 @@ mod matmul_col_overflow {
     #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
     fn matmulcircuit() {
         // get some logs fam
-        crate::logger::init_logger();
+        let _ = env_logger::builder().is_test(true).try_init();
         // parameters
         let mut a = Tensor::from((0..LEN * LEN).map(|i| Value::known(F::from((i + 1) as u64))));
         a.reshape(&[LEN, LEN]);
@@ -200,7 +296,7 @@ mod matmul_col_ultra_overflow {
             let a = VarTensor::new_advice(cs, K, 1, LEN * LEN * LEN);
             let b = VarTensor::new_advice(cs, K, 1, LEN * LEN * LEN);
             let output = VarTensor::new_advice(cs, K, 1, LEN * LEN * LEN);
-            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
         }
 
         fn synthesize(
@@ -233,7 +329,7 @@ mod matmul_col_ultra_overflow {
     #[ignore]
     fn matmulcircuit() {
         // get some logs fam
-        crate::logger::init_logger();
+        let _ = env_logger::builder().is_test(true).try_init();
         // parameters
         let mut a = Tensor::from((0..LEN * LEN).map(|i| Value::known(F::from((i + 1) as u64))));
         a.reshape(&[LEN, LEN]);
@@ -314,7 +410,7 @@ mod dot {
             let b = VarTensor::new_advice(cs, K, 1, LEN);
             let output = VarTensor::new_advice(cs, K, 1, LEN);
 
-            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
         }
 
         fn synthesize(
@@ -360,6 +456,181 @@ mod dot {
     }
 }
 
+#[cfg(test)]
+mod is_boolean_mask {
+    use super::*;
+
+    const K: usize = 5;
+    const NUM_INNER_COLS: usize = 2;
+    const LEN: usize = 4;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        mask: ValTensor<F>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, NUM_INNER_COLS, LEN * LEN);
+            let b = VarTensor::new_advice(cs, K, NUM_INNER_COLS, LEN * LEN);
+            let output = VarTensor::new_advice(cs, K, NUM_INNER_COLS, LEN * LEN);
+
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, NUM_INNER_COLS);
+                        let output = config
+                            .layout_is_boolean(&mut region, &self.mask)
+                            .map_err(|_| Error::Synthesis)?;
+                        assert_eq!(output.dims(), &[LEN, LEN]);
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    fn circuit(values: &[u64]) -> MyCircuit<F> {
+        let mut mask = Tensor::from(values.iter().map(|x| Value::known(F::from(*x))));
+        mask.reshape(&[LEN, LEN]);
+        MyCircuit::<F> {
+            mask: ValTensor::from(mask),
+            _marker: PhantomData,
+        }
+    }
+
+    #[test]
+    fn is_boolean_mask_circuit() {
+        let values = (0..LEN * LEN)
+            .map(|i| (i % 3 == 0) as u64)
+            .collect::<Vec<_>>();
+        let prover = MockProver::run(K as u32, &circuit(&values), vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn is_boolean_mask_rejects_non_boolean() {
+        let mut values = (0..LEN * LEN)
+            .map(|i| (i % 3 == 0) as u64)
+            .collect::<Vec<_>>();
+        values[9] = 2;
+        let prover = MockProver::run(K as u32, &circuit(&values), vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[cfg(test)]
+mod dot_continue {
+    use super::*;
+
+    const K: usize = 8;
+    const LEN: usize = 32;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        inputs: [ValTensor<F>; 2],
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, 4 * LEN);
+            let b = VarTensor::new_advice(cs, K, 1, 4 * LEN);
+            let output = VarTensor::new_advice(cs, K, 1, 4 * LEN);
+            VarTensor::constant_cols(cs, K, 2, false);
+
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let chunk = |x: &ValTensor<F>, rng: std::ops::Range<usize>| {
+                            x.get_slice(&[rng]).unwrap()
+                        };
+                        let [a, b] = &self.inputs;
+                        let half = LEN / 2;
+
+                        let full = layouts::dot(&config, &mut region, &self.inputs)
+                            .map_err(|_| Error::Synthesis)?;
+
+                        let partial = layouts::dot(
+                            &config,
+                            &mut region,
+                            &[chunk(a, 0..half), chunk(b, 0..half)],
+                        )
+                        .map_err(|_| Error::Synthesis)?;
+                        let split = config
+                            .layout_dot_continue(
+                                &mut region,
+                                &[chunk(a, half..LEN), chunk(b, half..LEN)],
+                                &partial,
+                            )
+                            .map_err(|_| Error::Synthesis)?;
+
+                        if !full.any_unknowns() {
+                            assert_eq!(
+                                full.get_int_evals().unwrap(),
+                                split.get_int_evals().unwrap()
+                            );
+                        }
+                        region.constrain_equal(&full, &split)
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn dot_continue_circuit() {
+        let a = Tensor::from((0..LEN).map(|i| Value::known(F::from(i as u64 + 1))));
+        let b = Tensor::from((0..LEN).map(|i| Value::known(F::from(i as u64 + 3))));
+
+        let circuit = MyCircuit::<F> {
+            inputs: [ValTensor::from(a), ValTensor::from(b)],
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
 #[cfg(test)]
 mod dot_col_overflow {
     use super::*;
@@ -387,7 +658,7 @@ mod dot_col_overflow {
             let b = VarTensor::new_advice(cs, K, 1, LEN);
             let output = VarTensor::new_advice(cs, K, 1, LEN);
 
-            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
         }
 
         fn synthesize(
@@ -460,7 +731,7 @@ mod sum {
             let b = VarTensor::new_advice(cs, K, 1, LEN);
             let output = VarTensor::new_advice(cs, K, 1, LEN);
 
-            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
         }
 
         fn synthesize(
@@ -502,6 +773,83 @@ mod sum {
     }
 }
 
+#[cfg(test)]
+mod sum_with_bias {
+    use super::*;
+
+    const K: usize = 5;
+    const LEN: usize = 8;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        inputs: [ValTensor<F>; 2],
+        claimed_total: ValTensor<F>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = layouts::sum_with_bias(&config, &mut region, &self.inputs)
+                            .map_err(|_| Error::Synthesis)?;
+
+                        // pins the total to a claimed value
+                        let claimed = region.assign(&config.inputs[0], &self.claimed_total)?;
+                        region.increment(1);
+                        region.constrain_equal(&claimed, &output)
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    fn circuit(claimed_total: i64) -> MyCircuit<F> {
+        MyCircuit::<F> {
+            inputs: [signed(&[1, -2, 3, 4, 5, 6, 7, -8]), signed(&[10])],
+            claimed_total: signed(&[claimed_total]),
+            _marker: PhantomData,
+        }
+    }
+
+    #[test]
+    fn sum_with_bias_circuit() {
+        let prover = MockProver::run(K as u32, &circuit(26), vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn sum_with_bias_wrong_total() {
+        // the total without the bias
+        let prover = MockProver::run(K as u32, &circuit(16), vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
 #[cfg(test)]
 mod sum_col_overflow {
     use super::*;
@@ -529,7 +877,7 @@ mod sum_col_overflow {
             let b = VarTensor::new_advice(cs, K, 1, LEN);
             let output = VarTensor::new_advice(cs, K, 1, LEN);
 
-            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
         }
 
         fn synthesize(
@@ -599,7 +947,7 @@ mod composition {
             let b = VarTensor::new_advice(cs, K, 1, LEN);
             let output = VarTensor::new_advice(cs, K, 1, LEN);
 
-            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
         }
 
         fn synthesize(
@@ -691,7 +1039,7 @@ mod conv {
             let a = VarTensor::new_advice(cs, K, 1, (LEN + 1) * LEN);
             let b = VarTensor::new_advice(cs, K, 1, (LEN + 1) * LEN);
             let output = VarTensor::new_advice(cs, K, 1, (LEN + 1) * LEN);
-            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
         }
 
         fn synthesize(
@@ -819,7 +1167,7 @@ mod conv_col_ultra_overflow {
             let a = VarTensor::new_advice(cs, K, 1, LEN * LEN * LEN);
             let b = VarTensor::new_advice(cs, K, 1, LEN * LEN * LEN);
             let output = VarTensor::new_advice(cs, K, 1, LEN * LEN * LEN);
-            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
         }
 
         fn synthesize(
@@ -863,7 +1211,7 @@ mod conv_col_ultra_overflow {
         let out_channels = 2;
 
         // get some logs fam
-        crate::logger::init_logger();
+        let _ = env_logger::builder().is_test(true).try_init();
         let mut image =
             Tensor::from((0..in_channels * image_height * image_width).map(|i| F::from(i as u64)));
         image.reshape(&[1, in_channels, image_height, image_width]);
@@ -953,7 +1301,8 @@ mod conv_relu_col_ultra_overflow {
             let b = VarTensor::new_advice(cs, K, 1, LEN * LEN * LEN);
             let output = VarTensor::new_advice(cs, K, 1, LEN * LEN * LEN);
             let mut base_config =
-                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE);
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
             // sets up a new relu table
             base_config
                 .configure_lookup(cs, &b, &output, &a, (-3, 3), K, &LookupOp::ReLU)
@@ -1012,7 +1361,7 @@ mod conv_relu_col_ultra_overflow {
         let out_channels = 2;
 
         // get some logs fam
-        crate::logger::init_logger();
+        let _ = env_logger::builder().is_test(true).try_init();
         let mut image =
             Tensor::from((0..in_channels * image_height * image_width).map(|_| F::from(0)));
         image.reshape(&[1, in_channels, image_height, image_width]);
@@ -1097,7 +1446,7 @@ mod sumpool {
             let b = VarTensor::new_advice(cs, K, 1, (LEN + 1) * LEN);
             let output = VarTensor::new_advice(cs, K, 1, (LEN + 1) * LEN);
             VarTensor::constant_cols(cs, K, 2, false);
-            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
         }
 
         fn synthesize(
@@ -1176,7 +1525,7 @@ mod add_w_shape_casting {
             let b = VarTensor::new_advice(cs, K, 1, LEN);
             let output = VarTensor::new_advice(cs, K, 1, LEN);
 
-            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
         }
 
         fn synthesize(
@@ -1243,7 +1592,7 @@ mod add {
             let b = VarTensor::new_advice(cs, K, 1, LEN);
             let output = VarTensor::new_advice(cs, K, 1, LEN);
 
-            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
         }
 
         fn synthesize(
@@ -1284,11 +1633,11 @@ mod add {
 }
 
 #[cfg(test)]
-mod add_with_overflow {
+mod add_three_inputs {
     use super::*;
 
     const K: usize = 4;
-    const LEN: usize = 50;
+    const LEN: usize = 4;
 
     #[derive(Clone)]
     struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
@@ -1306,11 +1655,14 @@ mod add_with_overflow {
         }
 
         fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
-            let a = VarTensor::new_advice(cs, K, 1, LEN);
-            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let inputs = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, LEN))
+                .collect::<Vec<_>>();
             let output = VarTensor::new_advice(cs, K, 1, LEN);
 
-            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+            let config = Self::Config::configure(cs, &inputs, &output, CheckMode::SAFE).unwrap();
+            assert_eq!(config.inputs.len(), 3);
+            config
         }
 
         fn synthesize(
@@ -1334,14 +1686,10 @@ mod add_with_overflow {
     }
 
     #[test]
-    fn addcircuit() {
-        // parameters
-        let a = Tensor::from((0..LEN).map(|i| Value::known(F::from(i as u64 + 1))));
-
-        let b = Tensor::from((0..LEN).map(|i| Value::known(F::from(i as u64 + 1))));
-
+    fn addcircuit_three_inputs() {
+        // two input ops are unaffected by the extra input column
         let circuit = MyCircuit::<F> {
-            inputs: [ValTensor::from(a), ValTensor::from(b)],
+            inputs: [signed(&[1, 2, 3, 4]), signed(&[-1, 5, 0, 2])],
             _marker: PhantomData,
         };
 
@@ -1351,50 +1699,206 @@ mod add_with_overflow {
 }
 
 #[cfg(test)]
-mod add_with_overflow_and_poseidon {
-    use halo2curves::bn256::Fr;
-
-    use crate::circuit::modules::{
-        poseidon::{
-            spec::{PoseidonSpec, POSEIDON_RATE, POSEIDON_WIDTH},
-            PoseidonChip, PoseidonConfig,
-        },
-        Module, ModulePlanner,
-    };
-
+mod mul_add {
     use super::*;
+    use crate::circuit::ops::base::BaseOp;
 
-    const K: usize = 15;
-    const LEN: usize = 50;
-    const WIDTH: usize = POSEIDON_WIDTH;
-    const RATE: usize = POSEIDON_RATE;
-
-    #[derive(Debug, Clone)]
-    struct MyCircuitConfig {
-        base: BaseConfig<Fr>,
-        poseidon: PoseidonConfig<WIDTH, RATE>,
-    }
+    const K: usize = 5;
+    const LEN: usize = 4;
 
     #[derive(Clone)]
-    struct MyCircuit {
-        inputs: [ValTensor<Fr>; 2],
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        inputs: [ValTensor<F>; 3],
+        claimed: ValTensor<F>,
+        _marker: PhantomData<F>,
     }
 
-    impl Circuit<Fr> for MyCircuit {
-        type Config = MyCircuitConfig;
-        type FloorPlanner = ModulePlanner;
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
         type Params = TestParams;
 
         fn without_witnesses(&self) -> Self {
             self.clone()
         }
 
-        fn configure(cs: &mut ConstraintSystem<Fr>) -> Self::Config {
-            let a = VarTensor::new_advice(cs, K, 1, LEN);
-            let b = VarTensor::new_advice(cs, K, 1, LEN);
-            let output = VarTensor::new_advice(cs, K, 1, LEN);
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let inputs = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, 2 * LEN))
+                .collect::<Vec<_>>();
+            let output = VarTensor::new_advice(cs, K, 1, 2 * LEN);
 
-            let base = BaseConfig::configure(cs, &[a, b], &output, CheckMode::SAFE);
+            let mut config =
+                Self::Config::configure(cs, &inputs, &output, CheckMode::SAFE).unwrap();
+            config.configure_base_op(cs, &BaseOp::MulAdd).unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = layouts::mul_add(&config, &mut region, &self.inputs)
+                            .map_err(|_| Error::Synthesis)?;
+
+                        if !output.any_unknowns() {
+                            assert_eq!(output.get_int_evals().unwrap()[..], [5, -7, 3, 14]);
+                        }
+
+                        // pins the result to a claimed value
+                        let claimed = region.assign(&config.output, &self.claimed)?;
+                        region.increment(self.claimed.len());
+                        region.constrain_equal(&claimed, &output)
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    fn circuit(claimed: &[i64]) -> MyCircuit<F> {
+        MyCircuit::<F> {
+            inputs: [
+                signed(&[1, -2, 3, 4]),
+                signed(&[2, 3, 0, 3]),
+                signed(&[3, -1, 3, 2]),
+            ],
+            claimed: signed(claimed),
+            _marker: PhantomData,
+        }
+    }
+
+    #[test]
+    fn muladdcircuit() {
+        let prover = MockProver::run(K as u32, &circuit(&[5, -7, 3, 14]), vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn muladdcircuit_wrong_sum() {
+        let prover = MockProver::run(K as u32, &circuit(&[5, -7, 3, 13]), vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[cfg(test)]
+mod add_with_overflow {
+    use super::*;
+
+    const K: usize = 4;
+    const LEN: usize = 50;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        inputs: [ValTensor<F>; 2],
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        config
+                            .layout(&mut region, &self.inputs.clone(), Box::new(PolyOp::Add))
+                            .map_err(|_| Error::Synthesis)
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn addcircuit() {
+        // parameters
+        let a = Tensor::from((0..LEN).map(|i| Value::known(F::from(i as u64 + 1))));
+
+        let b = Tensor::from((0..LEN).map(|i| Value::known(F::from(i as u64 + 1))));
+
+        let circuit = MyCircuit::<F> {
+            inputs: [ValTensor::from(a), ValTensor::from(b)],
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod add_with_overflow_and_poseidon {
+    use halo2curves::bn256::Fr;
+
+    use crate::circuit::modules::{
+        poseidon::{
+            spec::{PoseidonSpec, POSEIDON_RATE, POSEIDON_WIDTH},
+            PoseidonChip, PoseidonConfig,
+        },
+        Module, ModulePlanner,
+    };
+
+    use super::*;
+
+    const K: usize = 15;
+    const LEN: usize = 50;
+    const WIDTH: usize = POSEIDON_WIDTH;
+    const RATE: usize = POSEIDON_RATE;
+
+    #[derive(Debug, Clone)]
+    struct MyCircuitConfig {
+        base: BaseConfig<Fr>,
+        poseidon: PoseidonConfig<WIDTH, RATE>,
+    }
+
+    #[derive(Clone)]
+    struct MyCircuit {
+        inputs: [ValTensor<Fr>; 2],
+    }
+
+    impl Circuit<Fr> for MyCircuit {
+        type Config = MyCircuitConfig;
+        type FloorPlanner = ModulePlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<Fr>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+
+            let base = BaseConfig::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap();
             VarTensor::constant_cols(cs, K, 2, false);
 
             let poseidon = PoseidonChip::<PoseidonSpec, WIDTH, RATE, WIDTH>::configure(cs, ());
@@ -1514,7 +2018,7 @@ mod sub {
             let b = VarTensor::new_advice(cs, K, 1, LEN);
             let output = VarTensor::new_advice(cs, K, 1, LEN);
 
-            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
         }
 
         fn synthesize(
@@ -1581,7 +2085,7 @@ mod mult {
             let b = VarTensor::new_advice(cs, K, 1, LEN);
             let output = VarTensor::new_advice(cs, K, 1, LEN);
 
-            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
         }
 
         fn synthesize(
@@ -1648,7 +2152,7 @@ mod pow {
             let b = VarTensor::new_advice(cs, K, 1, LEN);
             let output = VarTensor::new_advice(cs, K, 1, LEN);
 
-            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
         }
 
         fn synthesize(
@@ -1713,7 +2217,7 @@ mod pack {
             let b = VarTensor::new_advice(cs, K, 1, LEN);
             let output = VarTensor::new_advice(cs, K, 1, LEN);
 
-            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
         }
 
         fn synthesize(
@@ -1790,7 +2294,8 @@ mod matmul_relu {
             let output = VarTensor::new_advice(cs, K, 1, LEN);
 
             let mut base_config =
-                BaseConfig::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE);
+                BaseConfig::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
             // sets up a new relu table
             base_config
                 .configure_lookup(cs, &b, &output, &a, (-32768, 32768), K, &LookupOp::ReLU)
@@ -1887,7 +2392,8 @@ mod rangecheckpercent {
             let b = VarTensor::new_advice(cs, K, 1, LEN);
             let output = VarTensor::new_advice(cs, K, 1, LEN);
             let mut config =
-                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE);
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
             // set up a new GreaterThan and Recip tables
             let nl = &LookupOp::GreaterThan {
                 a: circuit::utils::F32((RANGE * scale.0) / 100.0),
@@ -1927,6 +2433,7 @@ mod rangecheckpercent {
                                 Box::new(HybridOp::RangeCheck(Tolerance {
                                     val: RANGE,
                                     scale: SCALE.into(),
+                                    abs: 0.0,
                                 })),
                             )
                             .map_err(|_| Error::Synthesis)
@@ -2133,7 +2640,7 @@ mod lookup_ultra_overflow {
     #[ignore]
     fn relucircuit() {
         // get some logs fam
-        crate::logger::init_logger();
+        let _ = env_logger::builder().is_test(true).try_init();
         // parameters
         let a = Tensor::from((0..4).map(|i| Value::known(F::from(i + 1))));
 
@@ -2212,7 +2719,8 @@ mod softmax {
             let a = VarTensor::new_advice(cs, K, 1, LEN);
             let b = VarTensor::new_advice(cs, K, 1, LEN);
             let output = VarTensor::new_advice(cs, K, 1, LEN);
-            let mut config = Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE);
+            let mut config =
+                Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap();
             let advices = (0..3)
                 .map(|_| VarTensor::new_advice(cs, K, 1, LEN))
                 .collect::<Vec<_>>();
@@ -2288,3 +2796,7449 @@ mod softmax {
         prover.assert_satisfied_par();
     }
 }
+
+#[cfg(test)]
+mod div_rem {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+    use crate::tensor::ValType;
+
+    const K: usize = 8;
+    const LEN: usize = 4;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        inputs: [ValTensor<F>; 2],
+        // overrides the honestly computed quotient and remainder
+        witness: Option<[ValTensor<F>; 2]>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            VarTensor::constant_cols(cs, K, 2, false);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config.configure_base_op(cs, &BaseOp::Div).unwrap();
+            config
+                .configure_lookup(cs, &b, &output, &a, (-32, 32), K, &LookupOp::ReLU)
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        match &self.witness {
+                            Some([quotient, remainder]) => layouts::div_rem_witnessed(
+                                &config,
+                                &mut region,
+                                &[
+                                    self.inputs[0].clone(),
+                                    self.inputs[1].clone(),
+                                    quotient.clone(),
+                                    remainder.clone(),
+                                ],
+                            ),
+                            None => layouts::div_rem(&config, &mut region, &self.inputs),
+                        }
+                        .map(|_| ())
+                        .map_err(|_| Error::Synthesis)
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn divcircuit() {
+        // 7 / 3, -7 / 3, 0 / 3, 5 / 3
+        let a = Tensor::from(
+            [
+                F::from(7_u64),
+                -F::from(7_u64),
+                F::from(0_u64),
+                F::from(5_u64),
+            ]
+            .into_iter()
+            .map(Value::known),
+        );
+        let divisor = Tensor::from([ValType::Constant(F::from(3_u64))].into_iter());
+
+        let circuit = MyCircuit::<F> {
+            inputs: [ValTensor::from(a), ValTensor::from(divisor)],
+            witness: None,
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn divcircuit_remainder_out_of_bounds() {
+        // 7 = 1 * 3 + 4 satisfies the gate but not 0 <= remainder < divisor
+        let a = Tensor::from([Value::known(F::from(7_u64))].into_iter());
+        let divisor = Tensor::from([Value::known(F::from(3_u64))].into_iter());
+        let quotient = Tensor::from([Value::known(F::from(1_u64))].into_iter());
+        let remainder = Tensor::from([Value::known(F::from(4_u64))].into_iter());
+
+        let circuit = MyCircuit::<F> {
+            inputs: [ValTensor::from(a), ValTensor::from(divisor)],
+            witness: Some([ValTensor::from(quotient), ValTensor::from(remainder)]),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn divcircuit_field_quotient() {
+        // 7 = (5 / 3) * 3 + 2 over the field, with a remainder in range but an unbounded quotient
+        let a = Tensor::from([Value::known(F::from(7_u64))].into_iter());
+        let divisor = Tensor::from([Value::known(F::from(3_u64))].into_iter());
+        let quotient = Tensor::from(
+            [Value::known(
+                F::from(5_u64) * F::from(3_u64).invert().unwrap(),
+            )]
+            .into_iter(),
+        );
+        let remainder = Tensor::from([Value::known(F::from(2_u64))].into_iter());
+
+        let circuit = MyCircuit::<F> {
+            inputs: [ValTensor::from(a), ValTensor::from(divisor)],
+            witness: Some([ValTensor::from(quotient), ValTensor::from(remainder)]),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[cfg(test)]
+mod pairwise_extremum {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 8;
+    const LEN: usize = 4;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        inputs: [ValTensor<F>; 2],
+        op: BaseOp,
+        // overrides the honestly computed selection bits
+        selection: Option<ValTensor<F>>,
+        expected: Tensor<i128>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config.configure_base_op(cs, &BaseOp::Max).unwrap();
+            config.configure_base_op(cs, &BaseOp::Min).unwrap();
+            config
+                .configure_lookup(cs, &b, &output, &a, (-32, 32), K, &LookupOp::ReLU)
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = match (&self.selection, &self.op) {
+                            (Some(selection), _) => layouts::pairwise_extremum_witnessed(
+                                &config,
+                                &mut region,
+                                &[
+                                    self.inputs[0].clone(),
+                                    self.inputs[1].clone(),
+                                    selection.clone(),
+                                ],
+                                self.op.clone(),
+                            ),
+                            (None, BaseOp::Max) => {
+                                layouts::pairwise_max(&config, &mut region, &self.inputs)
+                            }
+                            (None, _) => layouts::pairwise_min(&config, &mut region, &self.inputs),
+                        }
+                        .map_err(|_| Error::Synthesis)?;
+
+                        if !output.any_unknowns() {
+                            assert_eq!(output.get_int_evals().unwrap(), self.expected);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn maxcircuit() {
+        let circuit = MyCircuit::<F> {
+            inputs: [signed(&[3, -2, -5, 4]), signed(&[-1, -2, 2, 7])],
+            op: BaseOp::Max,
+            selection: None,
+            expected: Tensor::from([3, -2, 2, 7].into_iter()),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn mincircuit() {
+        let circuit = MyCircuit::<F> {
+            inputs: [signed(&[3, -2, -5, 4]), signed(&[-1, -2, 2, 7])],
+            op: BaseOp::Min,
+            selection: None,
+            expected: Tensor::from([-1, -2, -5, 4].into_iter()),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn maxcircuit_flipped_selection() {
+        // the honest selection is [1, 1, 0, 0]
+        let circuit = MyCircuit::<F> {
+            inputs: [signed(&[3, -2, -5, 4]), signed(&[-1, -2, 2, 7])],
+            op: BaseOp::Max,
+            selection: Some(signed(&[0, 0, 1, 1])),
+            expected: Tensor::from([-1, -2, -5, 4].into_iter()),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[cfg(test)]
+mod abs {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 8;
+    const LEN: usize = 3;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        // overrides the honestly computed sign bits
+        sign: Option<ValTensor<F>>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config.configure_base_op(cs, &BaseOp::Abs).unwrap();
+            config
+                .configure_lookup(cs, &b, &output, &a, (-32, 32), K, &LookupOp::ReLU)
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = match &self.sign {
+                            Some(sign) => layouts::abs_witnessed(
+                                &config,
+                                &mut region,
+                                &[self.input.clone(), sign.clone()],
+                            ),
+                            None => layouts::abs(&config, &mut region, &[self.input.clone()]),
+                        }
+                        .map_err(|_| Error::Synthesis)?;
+
+                        if self.sign.is_none() && !output.any_unknowns() {
+                            let expected = self.input.get_int_evals().unwrap().map(|x| x.abs());
+                            assert_eq!(output.get_int_evals().unwrap(), expected);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn abscircuit() {
+        // 5, -5, 0
+        let input = Tensor::from(
+            [F::from(5_u64), -F::from(5_u64), F::from(0_u64)]
+                .into_iter()
+                .map(Value::known),
+        );
+
+        let circuit = MyCircuit::<F> {
+            input: ValTensor::from(input),
+            sign: None,
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn abscircuit_wrong_sign() {
+        let input = Tensor::from([Value::known(F::from(5_u64))].into_iter());
+        let sign = Tensor::from([Value::known(F::from(1_u64))].into_iter());
+
+        let circuit = MyCircuit::<F> {
+            input: ValTensor::from(input),
+            sign: Some(ValTensor::from(sign)),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[cfg(test)]
+mod square {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 4;
+    const LEN: usize = 4;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+
+            let mut config =
+                Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap();
+            config.configure_base_op(cs, &BaseOp::Square).unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = layouts::square(&config, &mut region, &[self.input.clone()])
+                            .map_err(|_| Error::Synthesis)?;
+
+                        if !output.any_unknowns() {
+                            let expected = self.input.get_felt_evals().unwrap().map(|x| x * x);
+                            assert_eq!(output.get_felt_evals().unwrap(), expected);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn squarecircuit() {
+        let input = Tensor::from((0..LEN).map(|i| Value::known(F::from(i as u64 + 1))));
+
+        let circuit = MyCircuit::<F> {
+            input: ValTensor::from(input),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn squarecircuit_wraps_near_modulus() {
+        // 2^127 squared exceeds the modulus, the gate is still satisfied by the wrapped result
+        // so callers must keep inputs within half the bit width of the field
+        let input = Tensor::from([Value::known(F::from_u128(1 << 127))].into_iter());
+
+        let circuit = MyCircuit::<F> {
+            input: ValTensor::from(input),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod clamp {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 9;
+    const LEN: usize = 4;
+    const MIN: i128 = -2;
+    const MAX: i128 = 3;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        // overrides the honestly computed below / above indicators
+        indicators: Option<[ValTensor<F>; 2]>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            VarTensor::constant_cols(cs, K, 2, false);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config
+                .configure_base_op(cs, &BaseOp::Clamp { min: MIN, max: MAX })
+                .unwrap();
+            config
+                .configure_lookup(cs, &b, &output, &a, (-32, 32), K, &LookupOp::ReLU)
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = match &self.indicators {
+                            Some([below, above]) => layouts::clamp_witnessed(
+                                &config,
+                                &mut region,
+                                &[self.input.clone(), below.clone(), above.clone()],
+                                MIN,
+                                MAX,
+                            ),
+                            None => layouts::clamp(
+                                &config,
+                                &mut region,
+                                &[self.input.clone()],
+                                MIN,
+                                MAX,
+                            ),
+                        }
+                        .map_err(|_| Error::Synthesis)?;
+
+                        if self.indicators.is_none() && !output.any_unknowns() {
+                            let expected = self
+                                .input
+                                .get_int_evals()
+                                .unwrap()
+                                .map(|x| x.clamp(MIN, MAX));
+                            assert_eq!(output.get_int_evals().unwrap(), expected);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn clampcircuit() {
+        // below, inside, at the upper bound, and above the range
+        let input = Tensor::from(
+            [
+                -F::from(5_u64),
+                F::from(0_u64),
+                F::from(3_u64),
+                F::from(7_u64),
+            ]
+            .into_iter()
+            .map(Value::known),
+        );
+
+        let circuit = MyCircuit::<F> {
+            input: ValTensor::from(input),
+            indicators: None,
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn clampcircuit_unclamped_claim() {
+        // claims 7 is within range so that the output is left unclamped
+        let input = Tensor::from([Value::known(F::from(7_u64))].into_iter());
+        let below = Tensor::from([Value::known(F::from(0_u64))].into_iter());
+        let above = Tensor::from([Value::known(F::from(0_u64))].into_iter());
+
+        let circuit = MyCircuit::<F> {
+            input: ValTensor::from(input),
+            indicators: Some([ValTensor::from(below), ValTensor::from(above)]),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[cfg(test)]
+mod ternary {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 5;
+    const LEN: usize = 4;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        inputs: [ValTensor<F>; 3],
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+
+            let mut config =
+                Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap();
+            config.configure_base_op(cs, &BaseOp::Select).unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        layouts::ternary(&config, &mut region, &self.inputs)
+                            .map(|_| ())
+                            .map_err(|_| Error::Synthesis)
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn ternarycircuit() {
+        let mask = Tensor::from(
+            [1_u64, 0, 1, 0]
+                .into_iter()
+                .map(|i| Value::known(F::from(i))),
+        );
+        let a = Tensor::from((0..LEN).map(|i| Value::known(F::from(i as u64 + 1))));
+        let b = Tensor::from((0..LEN).map(|i| Value::known(F::from(i as u64 + 5))));
+
+        let circuit = MyCircuit::<F> {
+            inputs: [
+                ValTensor::from(mask),
+                ValTensor::from(a),
+                ValTensor::from(b),
+            ],
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn ternarycircuit_non_boolean_mask() {
+        let mask = Tensor::from([Value::known(F::from(2_u64))].into_iter());
+        let a = Tensor::from([Value::known(F::from(1_u64))].into_iter());
+        let b = Tensor::from([Value::known(F::from(5_u64))].into_iter());
+
+        let circuit = MyCircuit::<F> {
+            inputs: [
+                ValTensor::from(mask),
+                ValTensor::from(a),
+                ValTensor::from(b),
+            ],
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[cfg(test)]
+mod compare {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 8;
+    const LEN: usize = 4;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        inputs: [ValTensor<F>; 2],
+        op: BaseOp,
+        // overrides the honestly computed comparison bits
+        bits: Option<ValTensor<F>>,
+        expected: Tensor<i128>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config.configure_base_op(cs, &BaseOp::Greater).unwrap();
+            config.configure_base_op(cs, &BaseOp::Less).unwrap();
+            config
+                .configure_lookup(cs, &b, &output, &a, (-32, 32), K, &LookupOp::ReLU)
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = match &self.bits {
+                            Some(bits) => layouts::compare_witnessed(
+                                &config,
+                                &mut region,
+                                &[self.inputs[0].clone(), self.inputs[1].clone(), bits.clone()],
+                                self.op.clone(),
+                            ),
+                            None => layouts::compare(
+                                &config,
+                                &mut region,
+                                &self.inputs,
+                                self.op.clone(),
+                            ),
+                        }
+                        .map_err(|_| Error::Synthesis)?;
+
+                        if !output.any_unknowns() {
+                            assert_eq!(output.get_int_evals().unwrap(), self.expected);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn greatercircuit() {
+        // includes equal elements, which are not strictly greater
+        let circuit = MyCircuit::<F> {
+            inputs: [signed(&[3, 2, -1, 4]), signed(&[1, 2, 0, 4])],
+            op: BaseOp::Greater,
+            bits: None,
+            expected: Tensor::from([1, 0, 0, 0].into_iter()),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn lesscircuit() {
+        let circuit = MyCircuit::<F> {
+            inputs: [signed(&[3, 2, -1, 4]), signed(&[1, 2, 0, 4])],
+            op: BaseOp::Less,
+            bits: None,
+            expected: Tensor::from([0, 0, 1, 0].into_iter()),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn greatercircuit_flipped_bit() {
+        let circuit = MyCircuit::<F> {
+            inputs: [signed(&[3]), signed(&[1])],
+            op: BaseOp::Greater,
+            bits: Some(signed(&[0])),
+            expected: Tensor::from([0].into_iter()),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[cfg(test)]
+mod logical {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 6;
+    const LEN: usize = 4;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        inputs: [ValTensor<F>; 2],
+        op: BaseOp,
+        expected: Tensor<i128>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            let mut config =
+                Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap();
+            config.configure_base_op(cs, &BaseOp::And).unwrap();
+            config.configure_base_op(cs, &BaseOp::Or).unwrap();
+            config.configure_base_op(cs, &BaseOp::Xor).unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output =
+                            layouts::logical(&config, &mut region, &self.inputs, self.op.clone())
+                                .map_err(|_| Error::Synthesis)?;
+
+                        if !output.any_unknowns() {
+                            assert_eq!(output.get_int_evals().unwrap(), self.expected);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    fn run(op: BaseOp, a: &[i64], b: &[i64], expected: &[i128]) -> MockProver<F> {
+        let circuit = MyCircuit::<F> {
+            inputs: [signed(a), signed(b)],
+            op,
+            expected: Tensor::from(expected.iter().cloned()),
+            _marker: PhantomData,
+        };
+        MockProver::run(K as u32, &circuit, vec![]).unwrap()
+    }
+
+    #[test]
+    fn truth_tables() {
+        // every combination of the two bits
+        let (a, b) = ([0, 0, 1, 1], [0, 1, 0, 1]);
+        for (op, expected) in [
+            (BaseOp::And, [0, 0, 0, 1]),
+            (BaseOp::Or, [0, 1, 1, 1]),
+            (BaseOp::Xor, [0, 1, 1, 0]),
+        ] {
+            run(op, &a, &b, &expected).assert_satisfied_par();
+        }
+    }
+
+    #[test]
+    fn rejects_non_boolean_inputs() {
+        // the outputs are consistent with the gate's formula, but the operands aren't bits
+        for (op, expected) in [
+            (BaseOp::And, [0, 2, 0, 2]),
+            (BaseOp::Or, [2, 1, 1, 1]),
+            (BaseOp::Xor, [2, -1, 1, -1]),
+        ] {
+            let prover = run(op.clone(), &[2, 2, 0, 1], &[0, 1, 1, 2], &expected);
+            assert!(
+                prover.verify().is_err(),
+                "{} accepted a non-boolean input",
+                op
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod prelu {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 8;
+    const LEN: usize = 32;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        slope: ValTensor<F>,
+        expected: Tensor<i128>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            VarTensor::constant_cols(cs, K, 2, false);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config.configure_base_op(cs, &BaseOp::Greater).unwrap();
+            config.configure_base_op(cs, &BaseOp::Select).unwrap();
+            config
+                .configure_lookup(cs, &b, &output, &a, (-32, 32), K, &LookupOp::ReLU)
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = config
+                            .layout_prelu(&mut region, &self.input, &self.slope)
+                            .map_err(|_| Error::Synthesis)?;
+
+                        if !output.any_unknowns() {
+                            assert_eq!(output.get_int_evals().unwrap(), self.expected);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn per_channel_slopes() {
+        // 3 channels of 4 elements, with a slope per channel
+        let mut input = signed(&[3, -1, 0, -2, 3, -1, 0, -2, -4, 5, -1, 1]);
+        input.reshape(&[3, 4]).unwrap();
+        let mut slope = signed(&[1, 2, 3]);
+        slope.reshape(&[3, 1]).unwrap();
+
+        let mut expected = Tensor::from([3, -1, 0, -2, 3, -2, 0, -4, -12, 5, -3, 1].into_iter());
+        expected.reshape(&[3, 4]);
+
+        let circuit = MyCircuit::<F> {
+            input,
+            slope,
+            expected,
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod max_pool {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 10;
+    const LEN: usize = 16;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        expected: Tensor<i128>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config.configure_base_op(cs, &BaseOp::ReduceMax).unwrap();
+            config
+                .configure_lookup(cs, &b, &output, &a, (-32, 32), K, &LookupOp::ReLU)
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = config
+                            .layout_maxpool(&mut region, &self.input, (2, 2), (2, 2))
+                            .map_err(|_| Error::Synthesis)?;
+
+                        if !output.any_unknowns() {
+                            assert_eq!(output.get_int_evals().unwrap(), self.expected);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn maxpool_4x4() {
+        let values = [5, 2, 3, 0, 4, -1, 3, 1, 6, -7, 8, 2, -3, 0, 2, 9];
+        let mut input = signed(&values);
+        input.reshape(&[4, 4]).unwrap();
+
+        let reference = Tensor::<i128>::new(
+            Some(&values.iter().map(|v| *v as i128).collect::<Vec<_>>()),
+            &[1, 1, 4, 4],
+        )
+        .unwrap();
+        let mut expected =
+            crate::tensor::ops::max_pool2d(&reference, &[(0, 0); 2], &(2, 2), &(2, 2)).unwrap();
+        expected.reshape(&[2, 2]);
+        assert_eq!(expected, Tensor::new(Some(&[5, 3, 6, 9]), &[2, 2]).unwrap());
+
+        let circuit = MyCircuit::<F> {
+            input,
+            expected,
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn maxpool_rejects_oversized_window() {
+        let mut config = BaseConfig::<F>::dummy(12, 1);
+        let mut region = RegionCtx::new_dummy(0, 1);
+        let mut input = signed(&[1, 2, 3, 4]);
+        input.reshape(&[2, 2]).unwrap();
+        assert!(config
+            .layout_maxpool(&mut region, &input, (3, 3), (1, 1))
+            .is_err());
+    }
+}
+
+#[cfg(test)]
+mod accumulated_argmax {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 10;
+    const LEN: usize = 16;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        expected: i128,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            VarTensor::constant_cols(cs, K, 2, false);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config.configure_base_op(cs, &BaseOp::ReduceMax).unwrap();
+            config
+                .configure_lookup(cs, &b, &output, &a, (-32, 32), K, &LookupOp::ReLU)
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = config
+                            .layout_argmax(&mut region, &self.input)
+                            .map_err(|_| Error::Synthesis)?;
+
+                        if !output.any_unknowns() {
+                            assert_eq!(output.get_int_evals().unwrap()[0], self.expected);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn argmax_clear_maximum() {
+        let circuit = MyCircuit::<F> {
+            input: signed(&[1, -3, 7, 3, -2]),
+            expected: 2,
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn argmax_tie_takes_lowest_index() {
+        let circuit = MyCircuit::<F> {
+            input: signed(&[2, 5, 1, 5, -4]),
+            expected: 1,
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod stable_softmax {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 10;
+    const LEN: usize = 16;
+    const SCALE: f32 = 8.0;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        expected: Vec<f64>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config.configure_base_op(cs, &BaseOp::ReduceMax).unwrap();
+            for nl in [
+                LookupOp::ReLU,
+                LookupOp::Exp {
+                    scale: utils::F32(SCALE),
+                },
+                LookupOp::Recip {
+                    scale: utils::F32(SCALE * SCALE),
+                },
+            ] {
+                config
+                    .configure_lookup(cs, &b, &output, &a, (-64, 64), K, &nl)
+                    .unwrap();
+            }
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = config
+                            .layout_softmax(&mut region, &self.input, utils::F32(SCALE))
+                            .map_err(|_| Error::Synthesis)?;
+
+                        if !output.any_unknowns() {
+                            // the output is at scale SCALE^2
+                            let output_scale = (SCALE * SCALE) as f64;
+                            for (claimed, expected) in output
+                                .get_int_evals()
+                                .unwrap()
+                                .iter()
+                                .zip(self.expected.iter())
+                            {
+                                let claimed = *claimed as f64 / output_scale;
+                                assert!((claimed - expected).abs() < 0.05);
+                            }
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn softmax_matches_float_reference() {
+        let input = [1.0f64, 2.0, 0.5, 3.0];
+        let denom: f64 = input.iter().map(|x| x.exp()).sum();
+        let expected = input.iter().map(|x| x.exp() / denom).collect::<Vec<_>>();
+
+        let quantized = input
+            .iter()
+            .map(|x| (x * SCALE as f64).round() as i64)
+            .collect::<Vec<_>>();
+
+        let circuit = MyCircuit::<F> {
+            input: signed(&quantized),
+            expected,
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod saturating_rescale {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 10;
+    const LEN: usize = 16;
+    const BITS: usize = 4;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        expected: Tensor<i128>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            VarTensor::constant_cols(cs, K, 2, false);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config.set_signed_range_bits(Some(BITS)).unwrap();
+            config
+                .configure_base_op(cs, &BaseOp::Clamp { min: -8, max: 7 })
+                .unwrap();
+            for nl in [
+                LookupOp::ReLU,
+                LookupOp::Div {
+                    denom: utils::F32(4.0),
+                },
+            ] {
+                config
+                    .configure_lookup(cs, &b, &output, &a, (-128, 128), K, &nl)
+                    .unwrap();
+            }
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = config
+                            .layout_rescale(
+                                &mut region,
+                                &self.input,
+                                utils::F32(4.0),
+                                utils::F32(1.0),
+                            )
+                            .map_err(|_| Error::Synthesis)?;
+
+                        if !output.any_unknowns() {
+                            assert_eq!(output.get_int_evals().unwrap(), self.expected);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn rescale_saturates_on_overflow() {
+        // 100 / 4 = 25 and -100 / 4 = -25 overflow the signed 4 bit range [-8, 7]
+        let circuit = MyCircuit::<F> {
+            input: signed(&[100, -100, 12, -13]),
+            expected: Tensor::from([7, -8, 3, -3].into_iter()),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod range_lookup_compare {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 8;
+    const LEN: usize = 4;
+    const BITS: usize = 4;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        inputs: [ValTensor<F>; 2],
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config.configure_base_op(cs, &BaseOp::Greater).unwrap();
+            config
+                .configure_range_lookup(cs, &b, &output, &a, BITS, K)
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        layouts::compare(&config, &mut region, &self.inputs, BaseOp::Greater)
+                            .map_err(|_| Error::Synthesis)?;
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn range_lookup_table_is_minimal() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let advices = (0..3)
+            .map(|_| VarTensor::new_advice(&mut cs, K, 1, LEN))
+            .collect::<Vec<_>>();
+        let mut config = BaseConfig::<F>::default();
+        config
+            .configure_range_lookup(&mut cs, &advices[0], &advices[1], &advices[2], BITS, K)
+            .unwrap();
+
+        let table = config
+            .tables
+            .get(&LookupOp::RangeCheck { bits: BITS })
+            .unwrap();
+        assert_eq!(table.range, (0, (1 << BITS) - 1));
+    }
+
+    #[test]
+    fn greater_with_diff_in_range() {
+        let circuit = MyCircuit::<F> {
+            inputs: [signed(&[3, 2, -1, 15]), signed(&[1, 2, 0, 0])],
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn greater_with_diff_out_of_range() {
+        // 20 > 1 witnesses a diff of 18, which is outside of [0, 2^BITS)
+        let circuit = MyCircuit::<F> {
+            inputs: [signed(&[3, 2, -1, 20]), signed(&[1, 2, 0, 1])],
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[cfg(test)]
+mod accumulated_extremum {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 9;
+    const LEN: usize = 16;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        op: BaseOp,
+        // overrides the honestly computed selection bits
+        bits: Option<ValTensor<F>>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config.configure_base_op(cs, &BaseOp::ReduceMax).unwrap();
+            config.configure_base_op(cs, &BaseOp::ReduceMin).unwrap();
+            config
+                .configure_lookup(cs, &b, &output, &a, (-32, 32), K, &LookupOp::ReLU)
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = match (&self.bits, &self.op) {
+                            (Some(bits), _) => layouts::accumulated_extremum_witnessed(
+                                &config,
+                                &mut region,
+                                &[self.input.clone(), bits.clone()],
+                                self.op.clone(),
+                            ),
+                            (None, BaseOp::ReduceMax) => layouts::accumulated_max(
+                                &config,
+                                &mut region,
+                                &[self.input.clone()],
+                            ),
+                            (None, _) => layouts::accumulated_min(
+                                &config,
+                                &mut region,
+                                &[self.input.clone()],
+                            ),
+                        }
+                        .map_err(|_| Error::Synthesis)?;
+
+                        if self.bits.is_none() && !output.any_unknowns() {
+                            let input = self.input.get_int_evals().unwrap();
+                            let expected = match self.op {
+                                BaseOp::ReduceMax => input.iter().max(),
+                                _ => input.iter().min(),
+                            };
+                            assert_eq!(output.get_int_evals().unwrap()[0], *expected.unwrap());
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    fn input() -> ValTensor<F> {
+        let values = (0..LEN as i64)
+            .map(|i| (i * 7) % 13 - 6)
+            .collect::<Vec<_>>();
+        signed(&values)
+    }
+
+    #[test]
+    fn reducemaxcircuit() {
+        let circuit = MyCircuit::<F> {
+            input: input(),
+            op: BaseOp::ReduceMax,
+            bits: None,
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn reducemincircuit() {
+        let circuit = MyCircuit::<F> {
+            input: input(),
+            op: BaseOp::ReduceMin,
+            bits: None,
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn reducemaxcircuit_non_monotonic() {
+        // always taking the new element gives the accumulator [1, 5, 3]
+        let circuit = MyCircuit::<F> {
+            input: signed(&[1, 5, 3]),
+            op: BaseOp::ReduceMax,
+            bits: Some(signed(&[1, 1, 1])),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[cfg(test)]
+mod modulo {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 9;
+    const LEN: usize = 8;
+    const MODULUS: i128 = 7;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        // overrides the honestly computed quotient and residue
+        witness: Option<[ValTensor<F>; 2]>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            VarTensor::constant_cols(cs, K, 2, false);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config
+                .configure_base_op(cs, &BaseOp::Mod { modulus: MODULUS })
+                .unwrap();
+            config
+                .configure_lookup(cs, &b, &output, &a, (-32, 32), K, &LookupOp::ReLU)
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = match &self.witness {
+                            Some([quotient, residue]) => layouts::modulo_witnessed(
+                                &config,
+                                &mut region,
+                                &[self.input.clone(), quotient.clone(), residue.clone()],
+                                MODULUS,
+                            ),
+                            None => layouts::modulo(
+                                &config,
+                                &mut region,
+                                &[self.input.clone()],
+                                MODULUS,
+                            ),
+                        }
+                        .map_err(|_| Error::Synthesis)?;
+
+                        if self.witness.is_none() && !output.any_unknowns() {
+                            let expected = self
+                                .input
+                                .get_int_evals()
+                                .unwrap()
+                                .map(|x| x.rem_euclid(MODULUS));
+                            assert_eq!(output.get_int_evals().unwrap(), expected);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn modcircuit() {
+        let circuit = MyCircuit::<F> {
+            input: signed(&[-10, -7, -1, 0, 3, 7, 13, 20]),
+            witness: None,
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn modcircuit_residue_out_of_range() {
+        // 10 = 0 * 7 + 10 satisfies the gate but not 0 <= residue < 7
+        let circuit = MyCircuit::<F> {
+            input: signed(&[10]),
+            witness: Some([signed(&[0]), signed(&[10])]),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn modcircuit_field_quotient() {
+        // 10 = (9 / 7) * 7 + 1 over the field, with a residue in range but an unbounded quotient
+        let quotient = F::from(9_u64) * F::from(MODULUS as u64).invert().unwrap();
+        let circuit = MyCircuit::<F> {
+            input: signed(&[10]),
+            witness: Some([
+                ValTensor::from(Tensor::from([Value::known(quotient)].into_iter())),
+                signed(&[1]),
+            ]),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[cfg(test)]
+mod is_zero {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 6;
+    const LEN: usize = 4;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        // overrides the honestly computed inverse and indicator
+        witness: Option<[ValTensor<F>; 2]>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            let mut config =
+                Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap();
+            config.configure_base_op(cs, &BaseOp::IsZeroInv).unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let [indicator, inverse] = match &self.witness {
+                            Some([inverse, indicator]) => layouts::is_zero_witnessed(
+                                &config,
+                                &mut region,
+                                &[self.input.clone(), inverse.clone(), indicator.clone()],
+                            ),
+                            None => layouts::is_zero(&config, &mut region, &[self.input.clone()]),
+                        }
+                        .map_err(|_| Error::Synthesis)?;
+
+                        if self.witness.is_none() && !indicator.any_unknowns() {
+                            assert_eq!(
+                                indicator.get_int_evals().unwrap(),
+                                Tensor::new(Some(&[1, 0, 0, 1]), &[LEN]).unwrap()
+                            );
+                            // the inverse is exposed for reuse
+                            let x = self.input.get_felt_evals().unwrap();
+                            let inv = inverse.get_felt_evals().unwrap();
+                            assert_eq!(x[1] * inv[1], F::ONE);
+                            assert_eq!(x[2] * inv[2], F::ONE);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn is_zero_circuit() {
+        let circuit = MyCircuit::<F> {
+            input: signed(&[0, 5, -3, 0]),
+            witness: None,
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn is_zero_fake_indicator() {
+        // claims 5 is zero, with a zero inverse
+        let circuit = MyCircuit::<F> {
+            input: signed(&[5]),
+            witness: Some([signed(&[0]), signed(&[1])]),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[cfg(test)]
+mod cumprod_init {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 9;
+    const LEN: usize = 8;
+    const SCALE: i128 = 16;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            let mut config =
+                Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap();
+            config
+                .configure_base_op(cs, &BaseOp::CumProdInit { init: SCALE })
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let default = layouts::prod(&config, &mut region, &[self.input.clone()])
+                            .map_err(|_| Error::Synthesis)?;
+                        let scaled = layouts::prod_with_init(
+                            &config,
+                            &mut region,
+                            &[self.input.clone()],
+                            SCALE,
+                        )
+                        .map_err(|_| Error::Synthesis)?;
+
+                        if !default.any_unknowns() {
+                            let default = default.get_int_evals().unwrap();
+                            assert_eq!(default[0], 40320);
+                            assert_eq!(scaled.get_int_evals().unwrap(), default.map(|x| x * SCALE));
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn cumprod_init_circuit() {
+        let circuit = MyCircuit::<F> {
+            input: signed(&[1, 2, 3, 4, 5, 6, 7, 8]),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod sub_signed_range {
+    use super::*;
+
+    const K: usize = 4;
+    const LEN: usize = 2;
+    const BITS: usize = 4;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd, const SAFE: bool> {
+        inputs: [ValTensor<F>; 2],
+        _marker: PhantomData<F>,
+    }
+
+    impl<const SAFE: bool> Circuit<F> for MyCircuit<F, SAFE> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            let check_mode = if SAFE {
+                CheckMode::SAFE
+            } else {
+                CheckMode::UNSAFE
+            };
+            let mut config = Self::Config::configure(cs, &[a, b], &output, check_mode).unwrap();
+            config.set_signed_range_bits(Some(BITS)).unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "",
+                |region| {
+                    let mut region = RegionCtx::new(region, 0, 1);
+                    config
+                        .layout(&mut region, &self.inputs.clone(), Box::new(PolyOp::Sub))
+                        .map_err(|_| Error::Synthesis)
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn subcircuit_in_range() {
+        // -8 - (-1) = -7 fits in 4 signed bits
+        let circuit = MyCircuit::<F, true> {
+            inputs: [signed(&[-8]), signed(&[-1])],
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn subcircuit_wraparound_safe() {
+        // -8 - 1 = -9 does not fit in 4 signed bits
+        let circuit = MyCircuit::<F, true> {
+            inputs: [signed(&[-8]), signed(&[1])],
+            _marker: PhantomData,
+        };
+
+        assert!(MockProver::run(K as u32, &circuit, vec![]).is_err());
+    }
+
+    #[test]
+    fn subcircuit_wraparound_unsafe() {
+        let circuit = MyCircuit::<F, false> {
+            inputs: [signed(&[-8]), signed(&[1])],
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn signed_range_bits_are_validated() {
+        let mut config = BaseConfig::<F>::default();
+        for bits in [0, 129] {
+            assert!(matches!(
+                config.set_signed_range_bits(Some(bits)),
+                Err(CircuitError::InvalidSignedBits(b)) if b == bits
+            ));
+        }
+        assert_eq!(config.signed_range_bits(), None);
+
+        config.set_signed_range_bits(Some(128)).unwrap();
+        assert_eq!(config.signed_range_bits(), Some(128));
+        assert_eq!(
+            crate::circuit::table::signed_bounds(128).unwrap(),
+            (i128::MIN, i128::MAX)
+        );
+        assert_eq!(crate::circuit::table::signed_bounds(1).unwrap(), (-1, 0));
+        assert_eq!(crate::circuit::table::signed_bounds(4).unwrap(), (-8, 7));
+    }
+}
+
+#[cfg(test)]
+mod neg_signed_range {
+    use super::*;
+
+    const K: usize = 4;
+    const LEN: usize = 2;
+    const BITS: usize = 4;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            let mut config =
+                Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap();
+            config.set_signed_range_bits(Some(BITS)).unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "",
+                |region| {
+                    let mut region = RegionCtx::new(region, 0, 1);
+                    config
+                        .layout(&mut region, &[self.input.clone()], Box::new(PolyOp::Neg))
+                        .map_err(|_| Error::Synthesis)
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn negcircuit_in_range() {
+        // -(-7) = 7 fits in 4 signed bits
+        let circuit = MyCircuit::<F> {
+            input: signed(&[-7]),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn negcircuit_edge_of_range() {
+        // -(-8) = 8 does not fit in 4 signed bits
+        let circuit = MyCircuit::<F> {
+            input: signed(&[-8]),
+            _marker: PhantomData,
+        };
+
+        assert!(MockProver::run(K as u32, &circuit, vec![]).is_err());
+    }
+}
+
+#[cfg(test)]
+mod sigmoid {
+    use super::*;
+
+    const K: usize = 9;
+    const INBITS: usize = 8;
+    const LEN: usize = 1 << INBITS;
+    const SCALE: f32 = 32.0;
+
+    #[derive(Clone)]
+    struct SigmoidCircuit<F: PrimeField + TensorType + PartialOrd> {
+        pub input: ValTensor<F>,
+    }
+
+    impl Circuit<F> for SigmoidCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, LEN))
+                .collect::<Vec<_>>();
+
+            let mut config = BaseConfig::default();
+            config
+                .configure_lookup(
+                    cs,
+                    &advices[0],
+                    &advices[1],
+                    &advices[2],
+                    (-(LEN as i128) / 2, LEN as i128 / 2),
+                    K,
+                    &LookupOp::Sigmoid {
+                        scale: SCALE.into(),
+                    },
+                )
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = config
+                            .layout(
+                                &mut region,
+                                &[self.input.clone()],
+                                Box::new(LookupOp::Sigmoid {
+                                    scale: SCALE.into(),
+                                }),
+                            )
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+
+                        if !output.any_unknowns() {
+                            let inputs = self.input.get_int_evals().unwrap();
+                            let outputs = output.get_int_evals().unwrap();
+                            // a handful of known points, each within one least-significant unit
+                            for (x, expected) in [
+                                (-128, 0.018),
+                                (-32, 0.269),
+                                (0, 0.5),
+                                (32, 0.731),
+                                (127, 0.981),
+                            ] {
+                                let idx = inputs.iter().position(|i| *i == x).unwrap();
+                                let expected = expected * SCALE as f64;
+                                assert!((outputs[idx] as f64 - expected).abs() <= 1.0);
+                            }
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn sigmoidcircuit() {
+        let half = LEN as i64 / 2;
+        let circuit = SigmoidCircuit::<F> {
+            input: signed(&(-half..half).collect::<Vec<_>>()),
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod tanh {
+    use super::*;
+
+    const K: usize = 9;
+    const LEN: usize = 256;
+    const SCALE: f32 = 16.0;
+
+    #[derive(Clone)]
+    struct TanhCircuit<F: PrimeField + TensorType + PartialOrd> {
+        pub input: ValTensor<F>,
+    }
+
+    impl Circuit<F> for TanhCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, LEN))
+                .collect::<Vec<_>>();
+
+            let mut config = BaseConfig::default();
+            config
+                .configure_lookup(
+                    cs,
+                    &advices[0],
+                    &advices[1],
+                    &advices[2],
+                    (-(LEN as i128) / 2, LEN as i128 / 2),
+                    K,
+                    &LookupOp::Tanh {
+                        scale: SCALE.into(),
+                    },
+                )
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = config
+                            .layout(
+                                &mut region,
+                                &[self.input.clone()],
+                                Box::new(LookupOp::Tanh {
+                                    scale: SCALE.into(),
+                                }),
+                            )
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+
+                        if !output.any_unknowns() {
+                            let outputs = output.get_int_evals().unwrap();
+                            // saturates toward -1 and 1 at the extremes of the range
+                            assert_eq!(outputs[0], -(SCALE as i128));
+                            assert_eq!(outputs[outputs.len() - 1], SCALE as i128);
+                            // negative inputs round trip through the field symmetrically
+                            for i in 1..LEN / 2 {
+                                assert_eq!(outputs[LEN / 2 - i], -outputs[LEN / 2 + i]);
+                            }
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn tanhcircuit() {
+        let half = LEN as i64 / 2;
+        let circuit = TanhCircuit::<F> {
+            input: signed(&(-half..half).collect::<Vec<_>>()),
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod exp {
+    use super::*;
+
+    const K: usize = 9;
+    const LEN: usize = 256;
+    const SCALE: f32 = 16.0;
+
+    #[derive(Clone)]
+    struct ExpCircuit<F: PrimeField + TensorType + PartialOrd> {
+        pub input: ValTensor<F>,
+    }
+
+    impl Circuit<F> for ExpCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, LEN))
+                .collect::<Vec<_>>();
+
+            let mut config = BaseConfig::default();
+            config
+                .configure_lookup(
+                    cs,
+                    &advices[0],
+                    &advices[1],
+                    &advices[2],
+                    (-(LEN as i128) / 2, LEN as i128 / 2),
+                    K,
+                    &LookupOp::Exp {
+                        scale: SCALE.into(),
+                    },
+                )
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = config
+                            .layout(
+                                &mut region,
+                                &[self.input.clone()],
+                                Box::new(LookupOp::Exp {
+                                    scale: SCALE.into(),
+                                }),
+                            )
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+
+                        if !output.any_unknowns() {
+                            let outputs = output.get_int_evals().unwrap();
+                            assert!(outputs.windows(2).all(|w| w[0] <= w[1]));
+                            assert_eq!(outputs[LEN / 2], SCALE as i128);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn expcircuit() {
+        let half = LEN as i64 / 2;
+        let circuit = ExpCircuit::<F> {
+            input: signed(&(-half..half).collect::<Vec<_>>()),
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod leakyrelu {
+    use super::*;
+
+    const K: usize = 11;
+    const LEN: usize = 6;
+    const SLOPE: f32 = 0.01;
+
+    #[derive(Clone)]
+    struct LeakyReLUCircuit<F: PrimeField + TensorType + PartialOrd> {
+        pub input: ValTensor<F>,
+        pub expected: Vec<i128>,
+    }
+
+    impl Circuit<F> for LeakyReLUCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, LEN))
+                .collect::<Vec<_>>();
+
+            let mut config = BaseConfig::default();
+            config
+                .configure_lookup(
+                    cs,
+                    &advices[0],
+                    &advices[1],
+                    &advices[2],
+                    (-512, 512),
+                    K,
+                    &LookupOp::LeakyReLU {
+                        slope: SLOPE.into(),
+                    },
+                )
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = config
+                            .layout(
+                                &mut region,
+                                &[self.input.clone()],
+                                Box::new(LookupOp::LeakyReLU {
+                                    slope: SLOPE.into(),
+                                }),
+                            )
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+
+                        if !output.any_unknowns() {
+                            assert_eq!(output.get_int_evals().unwrap().to_vec(), self.expected);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn leakyrelucircuit() {
+        // positives pass through, negatives are attenuated by the slope
+        let circuit = LeakyReLUCircuit::<F> {
+            input: signed(&[-300, -100, -40, 0, 7, 100]),
+            expected: vec![-3, -1, 0, 0, 7, 100],
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod gelu {
+    use super::*;
+
+    const K: usize = 10;
+    const LEN: usize = 7;
+    const SCALE: f32 = 16.0;
+
+    #[derive(Clone)]
+    struct GeluCircuit<F: PrimeField + TensorType + PartialOrd> {
+        pub input: ValTensor<F>,
+    }
+
+    impl Circuit<F> for GeluCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, LEN))
+                .collect::<Vec<_>>();
+
+            let mut config = BaseConfig::default();
+            config
+                .configure_lookup(
+                    cs,
+                    &advices[0],
+                    &advices[1],
+                    &advices[2],
+                    (-256, 256),
+                    K,
+                    &LookupOp::Gelu {
+                        scale: SCALE.into(),
+                    },
+                )
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = config
+                            .layout(
+                                &mut region,
+                                &[self.input.clone()],
+                                Box::new(LookupOp::Gelu {
+                                    scale: SCALE.into(),
+                                }),
+                            )
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+
+                        if !output.any_unknowns() {
+                            let inputs = self.input.get_int_evals().unwrap();
+                            let outputs = output.get_int_evals().unwrap();
+                            for (x, y) in inputs.iter().zip(outputs.iter()) {
+                                if x.abs() <= 2 {
+                                    // ~0 near zero
+                                    assert!(y.abs() <= 1);
+                                } else if *x >= 4 * SCALE as i128 {
+                                    // approaches x for large positive inputs
+                                    assert_eq!(y, x);
+                                }
+                            }
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn gelucircuit() {
+        let circuit = GeluCircuit::<F> {
+            input: signed(&[-2, -1, 0, 1, 2, 128, 255]),
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod recip {
+    use super::*;
+    use crate::circuit::ops::Op;
+
+    const SCALE: f32 = 128.0;
+
+    #[test]
+    fn recip_field_roundtrip() {
+        let nl = LookupOp::Recip {
+            scale: (SCALE * SCALE).into(),
+        };
+        let input = signed(&[128, -128, -64, 0]).get_felt_evals().unwrap();
+        let output = Op::<F>::f(&nl, &[input]).unwrap().output;
+        // negative outputs keep their sign through the field
+        assert_eq!(
+            output.map(crate::fieldutils::felt_to_i128).to_vec(),
+            vec![128, -128, -256, 0]
+        );
+    }
+}
+
+#[cfg(test)]
+mod multiple_lookups {
+    use super::*;
+
+    const K: usize = 8;
+    const LEN: usize = 8;
+    const SCALE: f32 = 16.0;
+
+    #[derive(Clone)]
+    struct MultipleLookupsCircuit<F: PrimeField + TensorType + PartialOrd> {
+        pub input: ValTensor<F>,
+    }
+
+    impl Circuit<F> for MultipleLookupsCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, LEN))
+                .collect::<Vec<_>>();
+
+            let mut config = BaseConfig::default();
+            // both tables share the one config and its advice columns
+            for nl in [
+                LookupOp::ReLU,
+                LookupOp::Sigmoid {
+                    scale: SCALE.into(),
+                },
+            ] {
+                config
+                    .configure_lookup(cs, &advices[0], &advices[1], &advices[2], (-64, 64), K, &nl)
+                    .unwrap();
+            }
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let first = self.input.get_slice(&[0..LEN / 2]).unwrap();
+                        let second = self.input.get_slice(&[LEN / 2..LEN]).unwrap();
+                        config
+                            .layout(&mut region, &[first], Box::new(LookupOp::ReLU))
+                            .map_err(|_| Error::Synthesis)?;
+                        config
+                            .layout(
+                                &mut region,
+                                &[second],
+                                Box::new(LookupOp::Sigmoid {
+                                    scale: SCALE.into(),
+                                }),
+                            )
+                            .map_err(|_| Error::Synthesis)?;
+                        Ok(())
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn relu_and_sigmoid_circuit() {
+        let circuit = MultipleLookupsCircuit::<F> {
+            input: signed(&[-3, -1, 2, 5, -16, 0, 8, 32]),
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod table_rows {
+    use super::*;
+    use crate::circuit::table::{Table, RESERVED_BLINDING_ROWS_PAD};
+    use crate::fieldutils::i128_to_felt;
+
+    const INBITS: usize = 8;
+
+    #[test]
+    fn table_rows_match_range() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let range = (-(1 << (INBITS - 1)), (1 << (INBITS - 1)) - 1);
+        let table = Table::<F>::configure(&mut cs, range, 10, &LookupOp::ReLU, None).unwrap();
+        assert_eq!(table.table_rows(), 1 << INBITS);
+    }
+
+    #[test]
+    fn table_filling_logrows_reserves_blinding_rows() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let range = (-(1 << (INBITS - 1)), (1 << (INBITS - 1)) - 1);
+        let reserved = cs.blinding_factors() + RESERVED_BLINDING_ROWS_PAD;
+
+        // the blinding rows push the tail of the table into a second column
+        let table = Table::<F>::configure(&mut cs, range, INBITS, &LookupOp::ReLU, None).unwrap();
+        assert_eq!(table.col_size, (1 << INBITS) - reserved);
+        assert_eq!(table.table_inputs.len(), 2);
+
+        // which a single preexisting input column can't provide
+        let inputs = Some(vec![cs.lookup_table_column()]);
+        assert!(matches!(
+            Table::<F>::configure(&mut cs, range, INBITS, &LookupOp::ReLU, inputs),
+            Err(CircuitError::LookupBitWidthTooLarge {
+                bits: 8,
+                logrows: INBITS
+            })
+        ));
+    }
+
+    #[test]
+    fn tabulate_matches_table() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let range = (-20, 20);
+        let table = Table::<F>::configure(&mut cs, range, 10, &LookupOp::ReLU, None).unwrap();
+        let (inputs, outputs) = table.evaluate().unwrap();
+
+        let rows = LookupOp::ReLU.tabulate::<F>(range).unwrap();
+        assert_eq!(rows.len(), table.table_rows());
+        for ((x, y), (input, output)) in rows.iter().zip(inputs.iter().zip(outputs.iter())) {
+            assert_eq!(i128_to_felt::<F>(*x), *input);
+            assert_eq!(y, output);
+            assert_eq!(*y, i128_to_felt::<F>((*x).max(0)));
+        }
+    }
+
+    #[test]
+    fn content_hash() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let range = (-20, 20);
+        let mut table = |nl: LookupOp, range| {
+            Table::<F>::configure(&mut cs, range, 10, &nl, None)
+                .unwrap()
+                .content_hash()
+                .unwrap()
+        };
+        let div = |denom| LookupOp::Div {
+            denom: utils::F32(denom),
+        };
+
+        // identical tables hash equal, even over distinct columns
+        assert_eq!(table(div(4.0), range), table(div(4.0), range));
+        // but any change to the rows is detected
+        assert_ne!(table(div(4.0), range), table(div(3.0), range));
+        assert_ne!(table(div(4.0), range), table(div(4.0), (-20, 21)));
+        assert_ne!(table(LookupOp::ReLU, range), table(LookupOp::Abs, range));
+    }
+
+    #[test]
+    fn evaluate_rows_in_batches() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let table = Table::<F>::configure(&mut cs, (-20, 20), 10, &LookupOp::Abs, None).unwrap();
+        let (inputs, outputs) = table.evaluate().unwrap();
+
+        // batches that don't divide the table are truncated at its end
+        let (mut batched_inputs, mut batched_outputs) = (vec![], vec![]);
+        for start in (0..table.table_rows()).step_by(7) {
+            let (inputs, outputs) = table.evaluate_rows(start, 7).unwrap();
+            batched_inputs.extend(inputs.iter().cloned());
+            batched_outputs.extend(outputs.iter().cloned());
+        }
+        assert_eq!(batched_inputs, inputs.to_vec());
+        assert_eq!(batched_outputs, outputs.to_vec());
+    }
+
+    #[test]
+    fn logrows_within_blinding_rows() {
+        let mut cs = ConstraintSystem::<F>::default();
+        assert!(matches!(
+            Table::<F>::configure(&mut cs, (0, 3), 3, &LookupOp::ReLU, None),
+            Err(CircuitError::LookupBitWidthTooLarge { logrows: 3, .. })
+        ));
+    }
+}
+
+#[cfg(test)]
+mod wide_table {
+    use super::*;
+    use crate::circuit::table::TableRange;
+
+    // an 18 bit table needs 2^18 rows, which are split across several columns at a smaller K
+    const K: usize = 16;
+    const BITS: usize = 18;
+    const LEN: usize = 4;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config
+                .configure_lookup(
+                    cs,
+                    &b,
+                    &output,
+                    &a,
+                    TableRange::Signed.bounds(BITS).unwrap(),
+                    K,
+                    &LookupOp::ReLU,
+                )
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let table = config.tables.get(&LookupOp::ReLU).unwrap();
+            assert!(table.table_inputs.len() > 1 << (BITS - K));
+            assert!(table.col_size < 1 << K);
+
+            config.layout_tables(&mut layouter).unwrap();
+            layouter.assign_region(
+                || "",
+                |region| {
+                    let mut region = RegionCtx::new(region, 0, 1);
+                    config
+                        .layout(&mut region, &[self.input.clone()], Box::new(LookupOp::ReLU))
+                        .map_err(|_| Error::Synthesis)?;
+                    Ok(())
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn eighteen_bit_table_under_smaller_k() {
+        // lookups from the first, a middle and the last column of the table
+        let (min, max) = TableRange::Signed.bounds(BITS).unwrap();
+        let input = signed(&[min as i64, -1, 1 << K, max as i64]);
+        let circuit = MyCircuit::<F> { input };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod relaxed_check_mode {
+    use super::*;
+
+    const K: usize = 4;
+    const LEN: usize = 2;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd, const PERCENT: usize> {
+        input: ValTensor<F>,
+        // lays out DropsLast rather than OffByOne
+        truncate: bool,
+        _marker: PhantomData<F>,
+    }
+
+    impl<const PERCENT: usize> Circuit<F> for MyCircuit<F, PERCENT> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            let mut config =
+                Self::Config::configure(cs, &[a, b], &output, CheckMode::RELAXED).unwrap();
+            config.tolerance = Tolerance::from(PERCENT as f32);
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "",
+                |region| {
+                    let mut region = RegionCtx::new(region, 0, 1);
+                    let op: Box<dyn Op<F>> = if self.truncate {
+                        Box::new(DropsLast)
+                    } else {
+                        Box::new(OffByOne)
+                    };
+                    config
+                        .layout(&mut region, &[self.input.clone()], op)
+                        .map_err(|_| Error::Synthesis)
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn relaxed_within_tolerance() {
+        // 101 is within 1% of 100
+        let circuit = MyCircuit::<F, 1> {
+            input: signed(&[100]),
+            truncate: false,
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn relaxed_zero_tolerance() {
+        let circuit = MyCircuit::<F, 0> {
+            input: signed(&[100]),
+            truncate: false,
+            _marker: PhantomData,
+        };
+
+        assert!(MockProver::run(K as u32, &circuit, vec![]).is_err());
+    }
+
+    #[test]
+    fn relaxed_shape_mismatch() {
+        // the elements that are laid out match exactly, but one is missing
+        let circuit = MyCircuit::<F, 1> {
+            input: signed(&[100, 100]),
+            truncate: true,
+            _marker: PhantomData,
+        };
+
+        assert!(MockProver::run(K as u32, &circuit, vec![]).is_err());
+    }
+}
+
+#[cfg(test)]
+mod tolerance {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn tolerance_from_str() {
+        assert_eq!(Tolerance::from_str("1.5").unwrap(), Tolerance::from(1.5));
+        assert_eq!(Tolerance::from_str("1.5%").unwrap(), Tolerance::from(1.5));
+
+        let abs = Tolerance::from_str("abs:3").unwrap();
+        assert_eq!(abs.val, 0.0);
+        assert_eq!(abs.abs, 3.0);
+
+        assert!(Tolerance::from_str("abs:").is_err());
+        assert!(Tolerance::from_str("1.5%%").is_err());
+        assert!(Tolerance::from_str("percent").is_err());
+    }
+
+    #[test]
+    fn tolerance_accepts_boundaries() {
+        // 1% of 200 is 2
+        let percent = Tolerance::from(1.0);
+        assert!(percent.accepts(202, 200));
+        assert!(percent.accepts(198, 200));
+        assert!(!percent.accepts(203, 200));
+
+        // an absolute band of 0.5 at scale 4 is 2
+        let abs = Tolerance {
+            val: 0.0,
+            scale: utils::F32(4.0),
+            abs: 0.5,
+        };
+        assert!(abs.accepts(2, 0));
+        assert!(!abs.accepts(3, 0));
+
+        // the larger of the two bands applies
+        let both = Tolerance {
+            val: 1.0,
+            scale: utils::F32(4.0),
+            abs: 0.5,
+        };
+        assert!(both.accepts(102, 100));
+        assert!(both.accepts(1002, 1000));
+        assert!(!both.accepts(1011, 1000));
+    }
+
+    #[test]
+    fn tolerance_is_within() {
+        // 1% of 200 is 2, on either side and for negative values
+        let percent = Tolerance::from(1.0);
+        assert!(percent.is_within(202.0, 200.0));
+        assert!(percent.is_within(198.0, 200.0));
+        assert!(percent.is_within(-202.0, -200.0));
+        assert!(!percent.is_within(202.01, 200.0));
+        assert!(!percent.is_within(-197.99, -200.0));
+
+        // the absolute band is in unscaled units, regardless of the scale
+        let abs = Tolerance {
+            val: 0.0,
+            scale: utils::F32(4.0),
+            abs: 0.5,
+        };
+        assert!(abs.is_within(0.5, 0.0));
+        assert!(abs.is_within(-0.5, 0.0));
+        assert!(!abs.is_within(0.51, 0.0));
+
+        // the larger of the two bands applies: the absolute band around 10, the percentage band around 1000
+        let both = Tolerance {
+            val: 1.0,
+            scale: utils::F32(1.0),
+            abs: 0.5,
+        };
+        assert!(both.is_within(10.5, 10.0));
+        assert!(!both.is_within(10.51, 10.0));
+        assert!(both.is_within(1010.0, 1000.0));
+        assert!(!both.is_within(1010.5, 1000.0));
+    }
+}
+
+#[cfg(test)]
+mod check_mode {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn check_mode_from_str() {
+        assert_eq!(CheckMode::from_str("safe").unwrap(), CheckMode::SAFE);
+        assert_eq!(CheckMode::from_str("unsafe").unwrap(), CheckMode::UNSAFE);
+        assert_eq!(CheckMode::from_str("relaxed").unwrap(), CheckMode::RELAXED);
+        assert_eq!(CheckMode::from_str("UnSaFe").unwrap(), CheckMode::UNSAFE);
+        assert!(matches!(
+            CheckMode::from_str("sorta-safe"),
+            Err(CircuitError::InvalidCheckMode(_))
+        ));
+        // the infallible conversion falls back to the default rather than panicking
+        assert_eq!(CheckMode::from("sorta-safe".to_string()), CheckMode::SAFE);
+    }
+
+    const K: usize = 4;
+    const LEN: usize = 2;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        check_mode: CheckMode,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "",
+                |region| {
+                    let mut region = RegionCtx::new(region, 0, 1);
+                    let res = config.with_check_mode(self.check_mode, |config| {
+                        config.layout(&mut region, &[self.input.clone()], Box::new(OffByOne))
+                    });
+                    // the mode configured at `configure` is restored
+                    assert_eq!(config.check_mode, CheckMode::SAFE);
+                    res.map_err(|_| Error::Synthesis)?;
+                    Ok(())
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    fn run(check_mode: CheckMode) -> Result<MockProver<F>, Error> {
+        let circuit = MyCircuit::<F> {
+            input: signed(&[3, 5]),
+            check_mode,
+            _marker: PhantomData,
+        };
+        MockProver::run(K as u32, &circuit, vec![])
+    }
+
+    #[test]
+    fn scoped_check_mode() {
+        // UNSAFE skips the safe_mode_check that SAFE fails on
+        assert!(run(CheckMode::UNSAFE).is_ok());
+        assert!(run(CheckMode::SAFE).is_err());
+    }
+
+    #[test]
+    fn set_check_mode() {
+        let mut config = BaseConfig::<F>::dummy(1 << K, 1);
+        assert_eq!(config.set_check_mode(CheckMode::UNSAFE), CheckMode::SAFE);
+        assert_eq!(config.check_mode, CheckMode::UNSAFE);
+    }
+}
+
+#[cfg(test)]
+mod warn_check_mode {
+    use super::*;
+    use std::sync::{Mutex, OnceLock};
+
+    const K: usize = 4;
+    const LEN: usize = 2;
+
+    /// Captures formatted warnings so they can be asserted on
+    struct CaptureLogger(Mutex<Vec<String>>);
+
+    impl log::Log for CaptureLogger {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.level() <= log::Level::Warn
+        }
+
+        fn log(&self, record: &log::Record) {
+            if self.enabled(record.metadata()) {
+                self.0.lock().unwrap().push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CaptureLogger = CaptureLogger(Mutex::new(vec![]));
+
+    /// Installs [LOGGER] once for all the tests of this module, returning whether it is the installed logger, as
+    /// another test may have already installed one in which case we can't capture
+    fn capturing() -> bool {
+        static INSTALLED: OnceLock<bool> = OnceLock::new();
+        *INSTALLED.get_or_init(|| {
+            let installed = log::set_logger(&LOGGER).is_ok();
+            if installed {
+                log::set_max_level(log::LevelFilter::Warn);
+            }
+            installed
+        })
+    }
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        // lays out DropsLast rather than OffByOne
+        truncate: bool,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::WARN).unwrap()
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "",
+                |region| {
+                    let mut region = RegionCtx::new(region, 0, 1);
+                    let op: Box<dyn Op<F>> = if self.truncate {
+                        Box::new(DropsLast)
+                    } else {
+                        Box::new(OffByOne)
+                    };
+                    let res = config.layout(&mut region, &[self.input.clone()], op);
+                    assert!(res.is_ok());
+                    Ok(())
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn warn_does_not_fail() {
+        let capturing = capturing();
+
+        let circuit = MyCircuit::<F> {
+            input: signed(&[3, 5]),
+            truncate: false,
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+
+        if capturing {
+            let warnings = LOGGER.0.lock().unwrap();
+            assert!(warnings
+                .iter()
+                .any(|w| w.contains("OFF_BY_ONE") && w.contains("[0, 1]")));
+        }
+    }
+
+    #[test]
+    fn warn_on_shape_mismatch() {
+        let capturing = capturing();
+
+        let circuit = MyCircuit::<F> {
+            input: signed(&[3, 5]),
+            truncate: true,
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+
+        if capturing {
+            let warnings = LOGGER.0.lock().unwrap();
+            assert!(warnings
+                .iter()
+                .any(|w| w.contains("DROPS_LAST") && w.contains("shape [1]")));
+        }
+    }
+}
+
+#[cfg(test)]
+mod lookup_bit_width {
+    use super::*;
+
+    const K: usize = 6;
+
+    #[test]
+    fn lookup_range_exceeds_shared_columns() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let advices = (0..3)
+            .map(|_| VarTensor::new_advice(&mut cs, K, 1, 4))
+            .collect::<Vec<_>>();
+        let mut config = BaseConfig::<F>::default();
+
+        // the first table fits in a single column, and its input columns are shared with later tables
+        config
+            .configure_lookup(
+                &mut cs,
+                &advices[0],
+                &advices[1],
+                &advices[2],
+                (-8, 8),
+                K,
+                &LookupOp::ReLU,
+            )
+            .unwrap();
+
+        let res = config.configure_lookup(
+            &mut cs,
+            &advices[0],
+            &advices[1],
+            &advices[2],
+            (-1024, 1024),
+            K,
+            &LookupOp::Abs,
+        );
+        assert!(matches!(
+            res,
+            Err(CircuitError::LookupBitWidthTooLarge {
+                bits: 12,
+                logrows: K
+            })
+        ));
+    }
+
+    #[test]
+    fn lookup_logrows_too_small() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let advices = (0..3)
+            .map(|_| VarTensor::new_advice(&mut cs, K, 1, 1))
+            .collect::<Vec<_>>();
+        let mut config = BaseConfig::<F>::default();
+
+        let res = config.configure_lookup(
+            &mut cs,
+            &advices[0],
+            &advices[1],
+            &advices[2],
+            (-2, 2),
+            2,
+            &LookupOp::ReLU,
+        );
+        assert!(matches!(
+            res,
+            Err(CircuitError::LookupBitWidthTooLarge { logrows: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn lookup_range_width_overflow() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let advices = (0..3)
+            .map(|_| VarTensor::new_advice(&mut cs, K, 1, 1))
+            .collect::<Vec<_>>();
+        let mut config = BaseConfig::<F>::default();
+
+        let res = config.configure_lookup(
+            &mut cs,
+            &advices[0],
+            &advices[1],
+            &advices[2],
+            (i128::MIN, i128::MAX),
+            K,
+            &LookupOp::ReLU,
+        );
+        assert!(matches!(
+            res,
+            Err(CircuitError::LookupBitWidthTooLarge {
+                bits: 128,
+                logrows: K
+            })
+        ));
+        assert!(config.tables.is_empty());
+    }
+    #[test]
+    fn lookup_range_outside_field_encoding() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let advices = (0..3)
+            .map(|_| VarTensor::new_advice(&mut cs, K, 1, 1))
+            .collect::<Vec<_>>();
+        let mut config = BaseConfig::<F>::default();
+
+        // narrow enough for the table, but i128::MIN has no signed encoding
+        let res = config.configure_lookup(
+            &mut cs,
+            &advices[0],
+            &advices[1],
+            &advices[2],
+            (i128::MIN, i128::MIN + 4),
+            K,
+            &LookupOp::ReLU,
+        );
+        assert!(matches!(res, Err(CircuitError::FeltOutOfRange(i128::MIN))));
+        assert!(config.tables.is_empty());
+
+        let res = config.configure_lookup(
+            &mut cs,
+            &advices[0],
+            &advices[1],
+            &advices[2],
+            (-i128::MAX, -i128::MAX + 4),
+            K,
+            &LookupOp::ReLU,
+        );
+        assert!(res.is_ok());
+    }
+}
+
+#[cfg(test)]
+mod configure_validation {
+    use super::*;
+
+    const K: usize = 4;
+
+    #[test]
+    fn configure_mismatched_inner_cols() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let a = VarTensor::new_advice(&mut cs, K, 1, 2);
+        let b = VarTensor::new_advice(&mut cs, K, 1, 2);
+        let output = VarTensor::new_advice(&mut cs, K, 2, 2);
+
+        let res = BaseConfig::<F>::configure(&mut cs, &[a, b], &output, CheckMode::SAFE);
+        assert!(matches!(res, Err(CircuitError::DimMismatch(_))));
+    }
+
+    #[test]
+    fn configure_mismatched_column_counts() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let a = VarTensor::new_advice(&mut cs, K, 2, 2);
+        let b = VarTensor::new_advice(&mut cs, K, 1, 2);
+        let output = VarTensor::new_advice(&mut cs, K, 2, 2);
+
+        match BaseConfig::<F>::configure(&mut cs, &[a, b], &output, CheckMode::SAFE) {
+            Err(CircuitError::DimMismatch(msg)) => assert!(msg.contains("have 2 and 1 columns")),
+            _ => panic!("expected a dimension mismatch"),
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn configure_unchecked_mismatched_column_counts() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let a = VarTensor::new_advice(&mut cs, K, 2, 2);
+        let b = VarTensor::new_advice(&mut cs, K, 1, 2);
+        let output = VarTensor::new_advice(&mut cs, K, 2, 2);
+
+        BaseConfig::<F>::configure_unchecked(&mut cs, &[a, b], &output, CheckMode::SAFE);
+    }
+
+    #[test]
+    fn configure_non_advice_input() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let a = VarTensor::new_advice(&mut cs, K, 1, 2);
+        let output = VarTensor::new_advice(&mut cs, K, 1, 2);
+
+        let res =
+            BaseConfig::<F>::configure(&mut cs, &[a, VarTensor::Empty], &output, CheckMode::SAFE);
+        assert!(matches!(res, Err(CircuitError::DimMismatch(_))));
+    }
+
+    #[test]
+    fn configure_too_few_inputs() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let a = VarTensor::new_advice(&mut cs, K, 1, 2);
+        let output = VarTensor::new_advice(&mut cs, K, 1, 2);
+
+        let res = BaseConfig::<F>::configure(&mut cs, &[a], &output, CheckMode::SAFE);
+        assert!(matches!(res, Err(CircuitError::DimMismatch(_))));
+    }
+
+    #[test]
+    fn configure_mismatched_third_input() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let a = VarTensor::new_advice(&mut cs, K, 1, 2);
+        let b = VarTensor::new_advice(&mut cs, K, 1, 2);
+        let c = VarTensor::new_advice(&mut cs, K, 2, 2);
+        let output = VarTensor::new_advice(&mut cs, K, 1, 2);
+
+        match BaseConfig::<F>::configure(&mut cs, &[a, b, c], &output, CheckMode::SAFE) {
+            Err(CircuitError::DimMismatch(msg)) => {
+                assert!(msg.contains("have 1 and 1 and 2 columns"))
+            }
+            _ => panic!("expected a dimension mismatch"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod shared_lookups {
+    use super::*;
+
+    const K: usize = 8;
+    const LEN: usize = 4;
+    const SCALE: f32 = 16.0;
+
+    fn nls() -> Vec<LookupOp> {
+        vec![
+            LookupOp::ReLU,
+            LookupOp::Sigmoid {
+                scale: SCALE.into(),
+            },
+            LookupOp::Tanh {
+                scale: SCALE.into(),
+            },
+        ]
+    }
+
+    #[test]
+    fn shared_lookups_minimize_columns() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let advices = (0..3)
+            .map(|_| VarTensor::new_advice(&mut cs, K, 1, LEN))
+            .collect::<Vec<_>>();
+        let mut config = BaseConfig::<F>::default();
+
+        config
+            .configure_lookups(
+                &mut cs,
+                &advices[0],
+                &advices[1],
+                &advices[2],
+                (-32, 32),
+                K,
+                &nls(),
+            )
+            .unwrap();
+
+        // input, output and index advice columns are shared by every op
+        assert_eq!(cs.num_advice_columns(), 3);
+        // as is the table input column, leaving one table output column per op
+        let table_inputs = config
+            .tables
+            .values()
+            .map(|table| table.table_inputs.clone())
+            .collect::<Vec<_>>();
+        assert!(table_inputs.windows(2).all(|w| w[0] == w[1]));
+        assert_eq!(table_inputs[0].len(), 1);
+    }
+
+    #[test]
+    fn shared_lookups_incompatible_range() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let advices = (0..3)
+            .map(|_| VarTensor::new_advice(&mut cs, K, 1, LEN))
+            .collect::<Vec<_>>();
+        let mut config = BaseConfig::<F>::default();
+
+        config
+            .configure_lookup(
+                &mut cs,
+                &advices[0],
+                &advices[1],
+                &advices[2],
+                (-16, 16),
+                K,
+                &LookupOp::Abs,
+            )
+            .unwrap();
+
+        let res = config.configure_lookups(
+            &mut cs,
+            &advices[0],
+            &advices[1],
+            &advices[2],
+            (-32, 32),
+            K,
+            &nls(),
+        );
+        assert!(matches!(res, Err(CircuitError::LookupInstantiation)));
+    }
+}
+
+#[cfg(test)]
+mod apply_by_name {
+    use super::*;
+
+    const K: usize = 8;
+    const LEN: usize = 4;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        inputs: [ValTensor<F>; 2],
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, 2 * LEN);
+            let b = VarTensor::new_advice(cs, K, 1, 2 * LEN);
+            let output = VarTensor::new_advice(cs, K, 1, 2 * LEN);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config
+                .configure_lookup(cs, &b, &output, &a, (-32, 32), K, &LookupOp::ReLU)
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let sum = config
+                            .apply(&mut region, "add", &self.inputs)
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+                        let relu = config
+                            .apply(&mut region, "ReLU", &[sum.clone()])
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+
+                        if !relu.any_unknowns() {
+                            assert_eq!(sum.get_int_evals().unwrap()[..], [-4, 2, 0, 10]);
+                            assert_eq!(relu.get_int_evals().unwrap()[..], [0, 2, 0, 10]);
+                        }
+
+                        let err = config.apply(&mut region, "frobnicate", &[sum]).unwrap_err();
+                        assert!(matches!(
+                            err.downcast_ref::<CircuitError>(),
+                            Some(CircuitError::UnsupportedOp)
+                        ));
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn apply_circuit() {
+        let circuit = MyCircuit::<F> {
+            inputs: [signed(&[-1, 0, 3, 4]), signed(&[-3, 2, -3, 6])],
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod config_layout {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 8;
+    const LEN: usize = 4;
+
+    fn configure(cs: &mut ConstraintSystem<F>) -> BaseConfig<F> {
+        let a = VarTensor::new_advice(cs, K, 1, LEN);
+        let b = VarTensor::new_advice(cs, K, 1, LEN);
+        let output = VarTensor::new_advice(cs, K, 1, LEN);
+        let mut config =
+            BaseConfig::<F>::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                .unwrap();
+        config
+            .configure_lookup(cs, &a, &b, &output, (-32, 32), K, &LookupOp::ReLU)
+            .unwrap();
+        config
+    }
+
+    #[test]
+    fn config_layout_roundtrip() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let snapshot = configure(&mut cs).layout_snapshot();
+
+        let serialized = serde_json::to_string(&snapshot).unwrap();
+        let deserialized: BaseConfigLayout = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(snapshot, deserialized);
+
+        // a fresh configuration lays out the same columns
+        let mut cs = ConstraintSystem::<F>::default();
+        assert_eq!(configure(&mut cs).layout_snapshot(), deserialized);
+        assert_eq!(deserialized.tables, vec![LookupOp::ReLU]);
+    }
+
+    #[test]
+    fn config_report() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let mut config = BaseConfig::<F>::default();
+        let a = VarTensor::new_advice(&mut cs, K, 1, LEN);
+        let b = VarTensor::new_advice(&mut cs, K, 1, LEN);
+        let output = VarTensor::new_advice(&mut cs, K, 1, LEN);
+        config.inputs = vec![a.clone(), b.clone()];
+        config.output = output.clone();
+        config
+            .configure_base_op(&mut cs, &BaseOp::Mod { modulus: 3 })
+            .unwrap();
+        config
+            .configure_lookup(&mut cs, &a, &b, &output, (-32, 32), K, &LookupOp::ReLU)
+            .unwrap();
+
+        let report = config.report();
+        assert_eq!(
+            report.ops,
+            vec![
+                OpReport {
+                    name: "MOD(modulus=3)".to_string(),
+                    selectors: 1,
+                    advice_columns: 3,
+                    table_columns: 0,
+                    table_rows: 0,
+                },
+                OpReport {
+                    name: "RELU".to_string(),
+                    selectors: 1,
+                    advice_columns: 3,
+                    table_columns: 2,
+                    table_rows: 65,
+                },
+            ]
+        );
+        assert_eq!(report.total_table_rows, 65);
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.contains("\"name\":\"MOD(modulus=3)\""));
+        assert!(json.contains("\"name\":\"RELU\""));
+        assert!(json.contains("\"total_table_rows\":65"));
+    }
+
+    #[test]
+    fn config_cost_estimate() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let mut config = BaseConfig::<F>::default();
+        let a = VarTensor::new_advice(&mut cs, K, 1, LEN);
+        let b = VarTensor::new_advice(&mut cs, K, 1, LEN);
+        let output = VarTensor::new_advice(&mut cs, K, 1, LEN);
+        config.inputs = vec![a.clone(), b.clone()];
+        config.output = output.clone();
+        config
+            .configure_base_op(&mut cs, &BaseOp::Clamp { min: -1, max: 1 })
+            .unwrap();
+        config
+            .configure_base_op(&mut cs, &BaseOp::Mod { modulus: 3 })
+            .unwrap();
+        config
+            .configure_lookup(&mut cs, &a, &b, &output, (-32, 32), K, &LookupOp::ReLU)
+            .unwrap();
+
+        assert_eq!(
+            config.cost_estimate(),
+            BaseConfigCost {
+                // one column each for the two inputs and the output
+                advice_columns: 3,
+                // a single block with a single inner column per op
+                selectors: 2,
+                lookup_selectors: 1,
+                // -32..=32
+                table_rows: 65,
+            }
+        );
+    }
+
+    #[test]
+    fn configured_ops() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let mut config = BaseConfig::<F>::default();
+        let a = VarTensor::new_advice(&mut cs, K, 2, LEN);
+        let b = VarTensor::new_advice(&mut cs, K, 2, LEN);
+        let output = VarTensor::new_advice(&mut cs, K, 2, LEN);
+        config.inputs = vec![a.clone(), b.clone()];
+        config.output = output.clone();
+        config
+            .configure_base_op(&mut cs, &BaseOp::Mod { modulus: 3 })
+            .unwrap();
+        for nl in [LookupOp::ReLU, LookupOp::Abs] {
+            config
+                .configure_lookup(&mut cs, &a, &b, &output, (-32, 32), K, &nl)
+                .unwrap();
+        }
+
+        // one entry per inner column
+        assert_eq!(
+            config.configured_base_ops().cloned().collect::<Vec<_>>(),
+            vec![
+                (BaseOp::Mod { modulus: 3 }, 0, 0),
+                (BaseOp::Mod { modulus: 3 }, 0, 1),
+            ]
+        );
+        let lookups = config.configured_lookup_ops().cloned().collect::<Vec<_>>();
+        assert_eq!(lookups.len(), 4);
+        for nl in [LookupOp::ReLU, LookupOp::Abs] {
+            for col in 0..2 {
+                assert!(lookups.contains(&(nl.clone(), 0, col)));
+            }
+        }
+    }
+
+    #[test]
+    fn op_cost_estimate() {
+        let config = BaseConfig::<F>::dummy(1 << 10, 1);
+        let elements = 64;
+
+        let narrow = config.estimate_op_cost(&LookupOp::ReLU, 4).unwrap();
+        assert_eq!(
+            narrow.lookup,
+            StrategyCost {
+                rows_per_element: 1,
+                table_rows: 16,
+                // a single input and output column
+                fixed_columns: 2,
+            }
+        );
+        // offset, 4 booleans, 4 dot rows, the equality and the product with the sign
+        assert_eq!(narrow.decomposed.as_ref().unwrap().rows_per_element, 11);
+        assert!(narrow.prefers_lookup(elements));
+
+        let wide = config.estimate_op_cost(&LookupOp::ReLU, 20).unwrap();
+        assert_eq!(wide.lookup.table_rows, 1 << 20);
+        assert_eq!(wide.lookup.fixed_columns, 2 * 1024);
+        assert_eq!(wide.decomposed.as_ref().unwrap().rows_per_element, 43);
+        assert!(!wide.prefers_lookup(elements));
+
+        // ops without a decomposition are always looked up
+        assert!(config
+            .estimate_op_cost(
+                &LookupOp::Sigmoid {
+                    scale: utils::F32(1.0)
+                },
+                20
+            )
+            .unwrap()
+            .prefers_lookup(elements));
+
+        // an already configured table costs no extra rows
+        let mut cs = ConstraintSystem::<F>::default();
+        let configured = configure(&mut cs)
+            .estimate_op_cost(&LookupOp::ReLU, 20)
+            .unwrap();
+        assert_eq!(configured.lookup.table_rows, 0);
+        assert!(configured.prefers_lookup(elements));
+    }
+}
+
+#[cfg(test)]
+mod op_strings {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+    use std::str::FromStr;
+
+    pub(super) fn base_ops() -> Vec<BaseOp> {
+        vec![
+            BaseOp::Dot,
+            BaseOp::CumProd,
+            BaseOp::Identity,
+            BaseOp::Add,
+            BaseOp::Mult,
+            BaseOp::Sub,
+            BaseOp::Sum,
+            BaseOp::Neg,
+            BaseOp::Range { tol: 2 },
+            BaseOp::IsZero,
+            BaseOp::IsBoolean,
+            BaseOp::Div,
+            BaseOp::Max,
+            BaseOp::Min,
+            BaseOp::Abs,
+            BaseOp::Square,
+            BaseOp::Clamp { min: -3, max: 5 },
+            BaseOp::Select,
+            BaseOp::Greater,
+            BaseOp::Less,
+            BaseOp::ReduceMax,
+            BaseOp::ReduceMin,
+            BaseOp::Mod { modulus: 7 },
+            BaseOp::CumProdInit { init: 16 },
+            BaseOp::IsZeroInv,
+            BaseOp::MulAdd,
+            BaseOp::AddConst { c: -4 },
+            BaseOp::MultConst { c: 128 },
+            BaseOp::And,
+            BaseOp::Or,
+            BaseOp::Xor,
+            BaseOp::SaturatingAdd {
+                min: -128,
+                max: 127,
+            },
+            BaseOp::SaturatingSub { min: -8, max: 7 },
+        ]
+    }
+
+    pub(super) fn lookup_ops() -> Vec<LookupOp> {
+        let scale = utils::F32(16.0);
+        vec![
+            LookupOp::Abs,
+            LookupOp::Div {
+                denom: utils::F32(2.5),
+            },
+            LookupOp::ReLU,
+            LookupOp::Max {
+                scales: (1, 2),
+                a: utils::F32(0.5),
+            },
+            LookupOp::Min {
+                scales: (0, 3),
+                a: utils::F32(-1.5),
+            },
+            LookupOp::Ceil { scale },
+            LookupOp::Floor { scale },
+            LookupOp::Round { scale },
+            LookupOp::RoundHalfToEven { scale },
+            LookupOp::Sqrt { scale },
+            LookupOp::Rsqrt { scale },
+            LookupOp::Recip { scale },
+            LookupOp::LeakyReLU {
+                slope: utils::F32(0.1),
+            },
+            LookupOp::Sigmoid { scale },
+            LookupOp::Silu { scale },
+            LookupOp::Mish { scale },
+            LookupOp::Elu {
+                scale,
+                alpha: utils::F32(1.0),
+            },
+            LookupOp::Selu {
+                scale,
+                alpha: utils::F32(1.6732632),
+                gamma: utils::F32(1.050701),
+            },
+            LookupOp::HardSigmoid { scale },
+            LookupOp::HardTanh { scale },
+            LookupOp::Ln { scale },
+            LookupOp::Exp { scale },
+            LookupOp::Cos { scale },
+            LookupOp::ACos { scale },
+            LookupOp::Cosh { scale },
+            LookupOp::ACosh { scale },
+            LookupOp::Sin { scale },
+            LookupOp::ASin { scale },
+            LookupOp::Sinh { scale },
+            LookupOp::ASinh { scale },
+            LookupOp::Tan { scale },
+            LookupOp::ATan { scale },
+            LookupOp::Tanh { scale },
+            LookupOp::ATanh { scale },
+            LookupOp::Erf { scale },
+            LookupOp::Gelu { scale },
+            LookupOp::ExactGelu { scale },
+            LookupOp::Softplus { scale },
+            LookupOp::GreaterThan { a: utils::F32(1.0) },
+            LookupOp::LessThan { a: utils::F32(2.0) },
+            LookupOp::GreaterThanEqual { a: utils::F32(3.0) },
+            LookupOp::LessThanEqual { a: utils::F32(4.0) },
+            LookupOp::Sign,
+            LookupOp::KroneckerDelta,
+            LookupOp::RangeCheck { bits: 8 },
+            LookupOp::RightShift { bits: 3 },
+            LookupOp::Pow {
+                scale,
+                a: utils::F32(3.0),
+            },
+        ]
+    }
+
+    #[test]
+    fn base_op_string_roundtrip() {
+        for op in base_ops() {
+            assert_eq!(BaseOp::from_str(&op.as_string()).unwrap(), op);
+        }
+    }
+
+    #[test]
+    fn lookup_op_string_roundtrip() {
+        for op in lookup_ops() {
+            assert_eq!(LookupOp::from_str(&op.to_string()).unwrap(), op);
+        }
+    }
+
+    #[test]
+    fn comparison_names_omit_threshold() {
+        let op = LookupOp::GreaterThan { a: utils::F32(1.0) };
+        assert_eq!(Op::<F>::as_string(&op), "GREATER_THAN");
+        assert_eq!(op.to_string(), "GREATER_THAN(a=1)");
+        let op = LookupOp::LessThanEqual { a: utils::F32(3.0) };
+        assert_eq!(Op::<F>::as_string(&op), "LESS_THAN_EQUAL");
+    }
+
+    #[test]
+    fn invalid_op_strings() {
+        assert!(matches!(
+            BaseOp::from_str("NOT_AN_OP"),
+            Err(CircuitError::InvalidOpString(_))
+        ));
+        assert!(matches!(
+            BaseOp::from_str("RANGE"),
+            Err(CircuitError::InvalidOpString(_))
+        ));
+        assert!(matches!(
+            LookupOp::from_str("SIGMOID(scale=)"),
+            Err(CircuitError::InvalidOpString(_))
+        ));
+    }
+}
+
+#[cfg(all(test, feature = "python-bindings"))]
+mod python_op_conversions {
+    use super::op_strings::{base_ops, lookup_ops};
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+    use pyo3::prelude::*;
+
+    #[test]
+    fn base_op_py_roundtrip() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            for op in base_ops() {
+                let obj = op.clone().into_py(py);
+                assert_eq!(obj.extract::<String>(py).unwrap(), op.as_string());
+                assert_eq!(obj.extract::<BaseOp>(py).unwrap(), op);
+            }
+        });
+    }
+
+    #[test]
+    fn lookup_op_py_roundtrip() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            for op in lookup_ops() {
+                let obj = op.clone().into_py(py);
+                assert_eq!(obj.extract::<LookupOp>(py).unwrap(), op);
+            }
+            assert!("NOT_AN_OP".into_py(py).extract::<LookupOp>(py).is_err());
+        });
+    }
+}
+
+#[cfg(test)]
+mod binary_lookup {
+    use super::*;
+    use crate::circuit::ops::lookup::BinaryLookupOp;
+
+    const K: usize = 8;
+    const LEN: usize = 4;
+    // base and exponent are both 3 bit
+    const RANGE: (i128, i128) = (0, 7);
+
+    #[derive(Clone)]
+    struct PowCircuit<F: PrimeField + TensorType + PartialOrd> {
+        pub inputs: [ValTensor<F>; 2],
+    }
+
+    impl Circuit<F> for PowCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, LEN))
+                .collect::<Vec<_>>();
+
+            let mut config = BaseConfig::default();
+            config
+                .configure_binary_lookup(
+                    cs,
+                    &advices[0],
+                    &advices[1],
+                    &advices[2],
+                    RANGE,
+                    RANGE,
+                    K,
+                    &BinaryLookupOp::Pow,
+                )
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = config
+                            .layout(&mut region, &self.inputs, Box::new(BinaryLookupOp::Pow))
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+
+                        if !output.any_unknowns() {
+                            assert_eq!(
+                                output.get_int_evals().unwrap(),
+                                Tensor::<i128>::new(Some(&[8, 9, 7, 1]), &[4]).unwrap()
+                            );
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn binary_pow_circuit() {
+        let circuit = PowCircuit::<F> {
+            inputs: [signed(&[2, 3, 7, 0]), signed(&[3, 2, 1, 0])],
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn binary_table_too_large() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let advices = (0..3)
+            .map(|_| VarTensor::new_advice(&mut cs, K, 1, LEN))
+            .collect::<Vec<_>>();
+        let mut config = BaseConfig::<F>::default();
+
+        // 64 rows don't fit alongside the blinding rows at logrows 6
+        let res = config.configure_binary_lookup(
+            &mut cs,
+            &advices[0],
+            &advices[1],
+            &advices[2],
+            RANGE,
+            RANGE,
+            6,
+            &BinaryLookupOp::Pow,
+        );
+        assert!(matches!(
+            res,
+            Err(CircuitError::LookupBitWidthTooLarge {
+                bits: 6,
+                logrows: 6
+            })
+        ));
+        assert!(config.binary_tables.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod range_check_lookup {
+    use super::*;
+
+    const K: usize = 8;
+    const LEN: usize = 4;
+    const BITS: usize = 4;
+
+    #[derive(Clone)]
+    struct RangeCheckCircuit<F: PrimeField + TensorType + PartialOrd> {
+        pub input: ValTensor<F>,
+    }
+
+    impl Circuit<F> for RangeCheckCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, LEN))
+                .collect::<Vec<_>>();
+
+            let mut config = BaseConfig::default();
+            // the table range covers values either side of the checked range
+            config
+                .configure_lookup(
+                    cs,
+                    &advices[0],
+                    &advices[1],
+                    &advices[2],
+                    (-32, 32),
+                    K,
+                    &LookupOp::RangeCheck { bits: BITS },
+                )
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        config
+                            .layout(
+                                &mut region,
+                                &[self.input.clone()],
+                                Box::new(LookupOp::RangeCheck { bits: BITS }),
+                            )
+                            .map_err(|_| Error::Synthesis)?;
+                        Ok(())
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn range_check_in_range() {
+        let circuit = RangeCheckCircuit::<F> {
+            input: signed(&[0, 1, 7, 15]),
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn range_check_rejects_upper_bound() {
+        let circuit = RangeCheckCircuit::<F> {
+            input: signed(&[0, 1, 7, 1 << BITS]),
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn range_check_rejects_negative() {
+        let circuit = RangeCheckCircuit::<F> {
+            input: signed(&[0, 1, 7, -1]),
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[cfg(test)]
+mod bit_decomposition {
+    use super::*;
+    use crate::circuit::ops::layouts;
+
+    const K: usize = 6;
+    const LEN: usize = 4;
+    const N_BITS: usize = 4;
+
+    #[derive(Clone)]
+    struct BitDecompositionCircuit<F: PrimeField + TensorType + PartialOrd> {
+        value: ValTensor<F>,
+        // witnessed bits, decomposed in the circuit if not provided
+        bits: Option<ValTensor<F>>,
+    }
+
+    impl Circuit<F> for BitDecompositionCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            // the powers of two are laid out as constants
+            VarTensor::constant_cols(cs, K, N_BITS, false);
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let bits = match &self.bits {
+                            Some(bits) => {
+                                layouts::bit_recomposition(&config, &mut region, &self.value, bits)
+                            }
+                            None => {
+                                config.layout_bit_decomposition(&mut region, &self.value, N_BITS)
+                            }
+                        }
+                        .map_err(|_| Error::Synthesis)?;
+
+                        if self.bits.is_none() && !bits.any_unknowns() {
+                            assert_eq!(
+                                bits.get_int_evals().unwrap(),
+                                Tensor::<i128>::new(Some(&[1, 1, 0, 1]), &[N_BITS]).unwrap()
+                            );
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn bit_decomposition_circuit() {
+        let circuit = BitDecompositionCircuit::<F> {
+            value: signed(&[0b1011]),
+            bits: None,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn bit_decomposition_rejects_wrong_bits() {
+        let circuit = BitDecompositionCircuit::<F> {
+            value: signed(&[0b1011]),
+            bits: Some(signed(&[1, 0, 0, 1])),
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn bit_decomposition_rejects_non_boolean_bits() {
+        // recomposes to the right value, but 3 isn't a bit
+        let circuit = BitDecompositionCircuit::<F> {
+            value: signed(&[0b1011]),
+            bits: Some(signed(&[3, 0, 0, 1])),
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[cfg(test)]
+mod softplus {
+    use super::*;
+    use crate::tensor::ops::nonlinearities::SOFTPLUS_THRESHOLD;
+
+    const K: usize = 11;
+    const LEN: usize = 6;
+    const SCALE: f32 = 16.0;
+
+    fn nl() -> LookupOp {
+        LookupOp::Softplus {
+            scale: SCALE.into(),
+        }
+    }
+
+    #[derive(Clone)]
+    struct SoftplusCircuit<F: PrimeField + TensorType + PartialOrd> {
+        pub input: ValTensor<F>,
+    }
+
+    impl Circuit<F> for SoftplusCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, LEN))
+                .collect::<Vec<_>>();
+
+            let mut config = BaseConfig::default();
+            config
+                .configure_lookup(
+                    cs,
+                    &advices[0],
+                    &advices[1],
+                    &advices[2],
+                    (-512, 512),
+                    K,
+                    &nl(),
+                )
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = config
+                            .layout(&mut region, &[self.input.clone()], Box::new(nl()))
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+
+                        if !output.any_unknowns() {
+                            let outputs = output.get_int_evals().unwrap();
+                            // softplus(0) = ln(2)
+                            let ln2 = (std::f64::consts::LN_2 * SCALE as f64).round() as i128;
+                            assert_eq!(outputs[0], ln2);
+                            // the asymptote picks up where the tabulated values leave off
+                            for w in outputs[1..5].windows(2) {
+                                assert!((0..=1).contains(&(w[1] - w[0])));
+                            }
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn softpluscircuit() {
+        let threshold = (SOFTPLUS_THRESHOLD * SCALE as f64) as i64;
+        let circuit = SoftplusCircuit::<F> {
+            input: signed(&[
+                0,
+                threshold - 1,
+                threshold,
+                threshold + 1,
+                threshold + 2,
+                -512,
+            ]),
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod silu {
+    use super::*;
+
+    const K: usize = 10;
+    const LEN: usize = 7;
+    const SCALE: f32 = 32.0;
+
+    fn nl() -> LookupOp {
+        LookupOp::Silu {
+            scale: SCALE.into(),
+        }
+    }
+
+    #[derive(Clone)]
+    struct SiluCircuit<F: PrimeField + TensorType + PartialOrd> {
+        pub input: ValTensor<F>,
+    }
+
+    impl Circuit<F> for SiluCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, LEN))
+                .collect::<Vec<_>>();
+
+            let mut config = BaseConfig::default();
+            config
+                .configure_lookup(
+                    cs,
+                    &advices[0],
+                    &advices[1],
+                    &advices[2],
+                    (-512, 512),
+                    K,
+                    &nl(),
+                )
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = config
+                            .layout(&mut region, &[self.input.clone()], Box::new(nl()))
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+
+                        if !output.any_unknowns() {
+                            let inputs = self.input.get_int_evals().unwrap();
+                            let outputs = output.get_int_evals().unwrap();
+                            for (x, y) in inputs.iter().zip(outputs.iter()) {
+                                let x = *x as f64 / SCALE as f64;
+                                let reference = SCALE as f64 * x / (1.0 + (-x).exp());
+                                assert!((*y as f64 - reference).abs() <= 1.0);
+                            }
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn silucircuit() {
+        let circuit = SiluCircuit::<F> {
+            input: signed(&[-512, -64, -1, 0, 1, 64, 512]),
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod mish {
+    use super::*;
+
+    const K: usize = 11;
+    const LEN: usize = 7;
+    const SCALE: f32 = 32.0;
+    const RANGE: (i128, i128) = (-1024, 1024);
+
+    fn nl() -> LookupOp {
+        LookupOp::Mish {
+            scale: SCALE.into(),
+        }
+    }
+
+    #[derive(Clone)]
+    struct MishCircuit<F: PrimeField + TensorType + PartialOrd> {
+        pub input: ValTensor<F>,
+    }
+
+    impl Circuit<F> for MishCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, LEN))
+                .collect::<Vec<_>>();
+
+            let mut config = BaseConfig::default();
+            config
+                .configure_lookup(cs, &advices[0], &advices[1], &advices[2], RANGE, K, &nl())
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = config
+                            .layout(&mut region, &[self.input.clone()], Box::new(nl()))
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+
+                        if !output.any_unknowns() {
+                            let inputs = self.input.get_int_evals().unwrap();
+                            let outputs = output.get_int_evals().unwrap();
+                            for (x, y) in inputs.iter().zip(outputs.iter()) {
+                                let x = *x as f64 / SCALE as f64;
+                                let softplus = x.exp().ln_1p();
+                                let reference = SCALE as f64 * x * softplus.tanh();
+                                assert!((*y as f64 - reference).abs() <= 1.0);
+                            }
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn mishcircuit() {
+        let circuit = MishCircuit::<F> {
+            input: signed(&[-1024, -64, -1, 0, 1, 64, 1024]),
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn mish_table_is_continuous() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let table =
+            crate::circuit::table::Table::<F>::configure(&mut cs, RANGE, K, &nl(), None).unwrap();
+        let (_, outputs) = table.evaluate().unwrap();
+        let outputs = outputs.map(crate::fieldutils::felt_to_i128);
+
+        // mish has a slope of at most ~1.1, so neighbouring entries differ by at most 2
+        for w in outputs.windows(2) {
+            assert!((w[1] - w[0]).abs() <= 2, "{:?}", w);
+        }
+        // the tails approach 0 and x
+        assert_eq!(outputs[0], 0);
+        assert_eq!(outputs[outputs.len() - 1], RANGE.1);
+    }
+}
+
+#[cfg(test)]
+mod elu {
+    use super::*;
+
+    const K: usize = 10;
+    const LEN: usize = 6;
+    const SCALE: f32 = 32.0;
+    const ALPHA: f32 = 1.5;
+    const GAMMA: f32 = 1.050701;
+
+    fn elu() -> LookupOp {
+        LookupOp::Elu {
+            scale: SCALE.into(),
+            alpha: utils::F32(ALPHA),
+        }
+    }
+
+    fn selu() -> LookupOp {
+        LookupOp::Selu {
+            scale: SCALE.into(),
+            alpha: utils::F32(ALPHA),
+            gamma: utils::F32(GAMMA),
+        }
+    }
+
+    #[derive(Clone)]
+    struct EluCircuit<F: PrimeField + TensorType + PartialOrd> {
+        pub input: ValTensor<F>,
+    }
+
+    impl Circuit<F> for EluCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, 2 * LEN))
+                .collect::<Vec<_>>();
+
+            let mut config = BaseConfig::default();
+            config
+                .configure_lookups(
+                    cs,
+                    &advices[0],
+                    &advices[1],
+                    &advices[2],
+                    (-512, 512),
+                    K,
+                    &[elu(), selu()],
+                )
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let elu_out = config
+                            .layout(&mut region, &[self.input.clone()], Box::new(elu()))
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+                        let selu_out = config
+                            .layout(&mut region, &[self.input.clone()], Box::new(selu()))
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+
+                        if !elu_out.any_unknowns() {
+                            let inputs = self.input.get_int_evals().unwrap();
+                            let elu_out = elu_out.get_int_evals().unwrap();
+                            let selu_out = selu_out.get_int_evals().unwrap();
+                            let floor = -(SCALE * ALPHA).round() as i128;
+                            let selu_floor = -(SCALE * ALPHA * GAMMA).round() as i128;
+
+                            for ((x, e), s) in
+                                inputs.iter().zip(elu_out.iter()).zip(selu_out.iter())
+                            {
+                                if *x > 0 {
+                                    // positive pass through
+                                    assert_eq!(e, x);
+                                    assert_eq!(*s, (*x as f32 * GAMMA).round() as i128);
+                                } else if *x == 0 {
+                                    // continuous at 0
+                                    assert_eq!((*e, *s), (0, 0));
+                                } else {
+                                    // saturates toward -alpha but never undershoots it
+                                    assert!(*e < 0 && *e >= floor);
+                                    assert!(*s < 0 && *s >= selu_floor);
+                                }
+                            }
+                            // the most negative input is saturated
+                            assert_eq!(elu_out[0], floor);
+                            assert_eq!(selu_out[0], selu_floor);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn elucircuit() {
+        let circuit = EluCircuit::<F> {
+            input: signed(&[-512, -32, -1, 0, 1, 512]),
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod periodic {
+    use super::*;
+    use crate::circuit::table::Table;
+    use crate::fieldutils::felt_to_i128;
+
+    const K: usize = 11;
+    const SCALE: f32 = 64.0;
+    const RANGE: (i128, i128) = (-1000, 1000);
+
+    fn table(nl: LookupOp) -> (Vec<i128>, Vec<i128>) {
+        let mut cs = ConstraintSystem::<F>::default();
+        let table = Table::<F>::configure(&mut cs, RANGE, K, &nl, None).unwrap();
+        let (inputs, outputs) = table.evaluate().unwrap();
+        (
+            inputs.map(felt_to_i128).to_vec(),
+            outputs.map(felt_to_i128).to_vec(),
+        )
+    }
+
+    #[test]
+    fn sin_cos_at_zero_and_periodicity() {
+        let scale = utils::F32(SCALE);
+        let period = (std::f64::consts::TAU * SCALE as f64).round() as usize;
+
+        for (nl, at_zero) in [
+            (LookupOp::Sin { scale }, 0),
+            (LookupOp::Cos { scale }, SCALE as i128),
+        ] {
+            let (inputs, outputs) = table(nl);
+            let zero = inputs.iter().position(|x| *x == 0).unwrap();
+            assert_eq!(outputs[zero], at_zero);
+
+            // the period is rounded to the fixed point grid, which costs at most a unit
+            for i in 0..outputs.len() - period {
+                assert_eq!(inputs[i + period] - inputs[i], period as i128);
+                assert!((outputs[i + period] - outputs[i]).abs() <= 1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod hard_nonlinearities {
+    use super::*;
+
+    const K: usize = 10;
+    const LEN: usize = 9;
+    const SCALE: i128 = 16;
+
+    fn hard_sigmoid() -> LookupOp {
+        LookupOp::HardSigmoid {
+            scale: (SCALE as f32).into(),
+        }
+    }
+
+    fn hard_tanh() -> LookupOp {
+        LookupOp::HardTanh {
+            scale: (SCALE as f32).into(),
+        }
+    }
+
+    #[derive(Clone)]
+    struct HardCircuit<F: PrimeField + TensorType + PartialOrd> {
+        pub input: ValTensor<F>,
+    }
+
+    impl Circuit<F> for HardCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, LEN))
+                .collect::<Vec<_>>();
+
+            let mut config = BaseConfig::default();
+            config
+                .configure_lookups(
+                    cs,
+                    &advices[0],
+                    &advices[1],
+                    &advices[2],
+                    (-128, 128),
+                    K,
+                    &[hard_sigmoid(), hard_tanh()],
+                )
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let sigmoid = config
+                            .layout(&mut region, &[self.input.clone()], Box::new(hard_sigmoid()))
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+                        let tanh = config
+                            .layout(&mut region, &[self.input.clone()], Box::new(hard_tanh()))
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+
+                        if !sigmoid.any_unknowns() && !tanh.any_unknowns() {
+                            let inputs = self.input.get_int_evals().unwrap();
+                            let sigmoid = sigmoid.get_int_evals().unwrap();
+                            let tanh = tanh.get_int_evals().unwrap();
+                            for ((x, s), t) in inputs.iter().zip(sigmoid.iter()).zip(tanh.iter()) {
+                                // the knees of hard sigmoid are at ±2.5
+                                if 2 * x <= -5 * SCALE {
+                                    assert_eq!(*s, 0);
+                                } else if 2 * x >= 5 * SCALE {
+                                    assert_eq!(*s, SCALE);
+                                } else if x % 5 == 0 {
+                                    // exactly 0.2 per unit of input in the linear region
+                                    assert_eq!(*s, SCALE / 2 + x / 5);
+                                }
+                                // and of hard tanh at ±1
+                                assert_eq!(*t, (*x).clamp(-SCALE, SCALE));
+                            }
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn hardcircuit() {
+        let circuit = HardCircuit::<F> {
+            input: signed(&[-128, -40, -16, -5, 0, 5, 16, 40, 128]),
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod erf {
+    use super::*;
+
+    const K: usize = 12;
+    const LEN: usize = 7;
+    const SCALE: f32 = 128.0;
+
+    fn erf() -> LookupOp {
+        LookupOp::Erf {
+            scale: SCALE.into(),
+        }
+    }
+
+    fn exact_gelu() -> LookupOp {
+        LookupOp::ExactGelu {
+            scale: SCALE.into(),
+        }
+    }
+
+    #[derive(Clone)]
+    struct ErfCircuit<F: PrimeField + TensorType + PartialOrd> {
+        pub input: ValTensor<F>,
+    }
+
+    impl Circuit<F> for ErfCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, LEN))
+                .collect::<Vec<_>>();
+
+            let mut config = BaseConfig::default();
+            config
+                .configure_lookups(
+                    cs,
+                    &advices[0],
+                    &advices[1],
+                    &advices[2],
+                    (-1024, 1024),
+                    K,
+                    &[erf(), exact_gelu()],
+                )
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let erf = config
+                            .layout(&mut region, &[self.input.clone()], Box::new(erf()))
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+                        let gelu = config
+                            .layout(&mut region, &[self.input.clone()], Box::new(exact_gelu()))
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+
+                        if !erf.any_unknowns() && !gelu.any_unknowns() {
+                            let erf = erf.get_int_evals().unwrap();
+                            let gelu = gelu.get_int_evals().unwrap();
+                            // erf(0), erf(0.5), erf(1), erf(2), erf(-1)
+                            let known = [
+                                0.0,
+                                0.5204998778130465,
+                                0.8427007929497149,
+                                0.9953222650189527,
+                                -0.8427007929497149,
+                            ];
+                            for (y, k) in erf.iter().zip(known) {
+                                assert!((*y as f64 - SCALE as f64 * k).abs() <= 1.0);
+                            }
+                            // saturates at the range extremes
+                            assert_eq!(erf[5], SCALE as i128);
+                            assert_eq!(erf[6], -SCALE as i128);
+                            assert_eq!(gelu[5], 1024);
+                            assert_eq!(gelu[6], 0);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn erfcircuit() {
+        let circuit = ErfCircuit::<F> {
+            input: signed(&[0, 64, 128, 256, -128, 1024, -1024]),
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod rounding {
+    use super::*;
+
+    const K: usize = 8;
+    const LEN: usize = 9;
+    const SCALE: f32 = 4.0;
+
+    fn ops() -> Vec<LookupOp> {
+        let scale = SCALE.into();
+        vec![
+            LookupOp::Floor { scale },
+            LookupOp::Ceil { scale },
+            LookupOp::Round { scale },
+            LookupOp::RoundHalfToEven { scale },
+        ]
+    }
+
+    #[derive(Clone)]
+    struct RoundingCircuit<F: PrimeField + TensorType + PartialOrd> {
+        pub input: ValTensor<F>,
+        pub expected: Vec<Tensor<i128>>,
+    }
+
+    impl Circuit<F> for RoundingCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, LEN))
+                .collect::<Vec<_>>();
+
+            let mut config = BaseConfig::default();
+            config
+                .configure_lookups(
+                    cs,
+                    &advices[0],
+                    &advices[1],
+                    &advices[2],
+                    (-16, 16),
+                    K,
+                    &ops(),
+                )
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        for (op, expected) in ops().into_iter().zip(self.expected.iter()) {
+                            let output = config
+                                .layout(&mut region, &[self.input.clone()], Box::new(op))
+                                .map_err(|_| Error::Synthesis)?
+                                .unwrap();
+
+                            if !output.any_unknowns() {
+                                assert_eq!(&output.get_int_evals().unwrap(), expected);
+                            }
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn roundingcircuit() {
+        // -1.5, -1.25, -1, -0.5, -0.25, 0, 0.5, 1.5, 2.5
+        let input = [-6, -5, -4, -2, -1, 0, 2, 6, 10];
+        let expected = [
+            // floor
+            [-2, -2, -1, -1, -1, 0, 0, 1, 2],
+            // ceil
+            [-1, -1, -1, 0, 0, 0, 1, 2, 3],
+            // round, ties away from zero
+            [-2, -1, -1, -1, 0, 0, 1, 2, 3],
+            // round, ties to even
+            [-2, -1, -1, 0, 0, 0, 0, 2, 2],
+        ];
+        let circuit = RoundingCircuit::<F> {
+            input: signed(&input),
+            expected: expected
+                .iter()
+                .map(|e| Tensor::new(Some(&e[..]), &[LEN]).unwrap())
+                .collect(),
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod table_range {
+    use super::*;
+    use crate::circuit::table::{Table, TableRange};
+    use crate::fieldutils::felt_to_i128;
+
+    const K: usize = 10;
+    const BITS: usize = 8;
+
+    #[test]
+    fn table_range_bounds() {
+        assert_eq!(TableRange::Signed.bounds(BITS).unwrap(), (-128, 127));
+        assert_eq!(TableRange::Unsigned.bounds(BITS).unwrap(), (0, 255));
+        assert_eq!(TableRange::Signed.bounds(1).unwrap(), (-1, 0));
+        assert_eq!(
+            TableRange::Unsigned.bounds(126).unwrap(),
+            (0, (1 << 126) - 1)
+        );
+    }
+
+    #[test]
+    fn table_range_bits_are_validated() {
+        for range in [TableRange::Signed, TableRange::Unsigned] {
+            for bits in [0, 127, 128, 200] {
+                assert!(matches!(
+                    range.bounds(bits),
+                    Err(CircuitError::InvalidSignedBits(b)) if b == bits
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn unsigned_relu_table() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let range = TableRange::Unsigned.bounds(BITS).unwrap();
+        let table = Table::<F>::configure(&mut cs, range, K, &LookupOp::ReLU, None).unwrap();
+
+        let (inputs, outputs) = table.evaluate().unwrap();
+        let inputs = inputs.map(felt_to_i128);
+        // no negative inputs, and the inputs round trip through the field
+        assert_eq!(inputs.to_vec(), (0..1 << BITS).collect::<Vec<i128>>());
+        // relu is the identity over the unsigned range
+        assert_eq!(outputs.map(felt_to_i128), inputs);
+    }
+}
+
+#[cfg(test)]
+mod table_registry {
+    use super::*;
+    use crate::circuit::table::TableRegistry;
+
+    const K: usize = 6;
+    const RANGE: (i128, i128) = (-8, 8);
+
+    #[derive(Clone)]
+    struct LayerConfigs {
+        first: BaseConfig<F>,
+        second: BaseConfig<F>,
+    }
+
+    fn configure_layer(
+        cs: &mut ConstraintSystem<F>,
+        registry: Option<&mut TableRegistry<F>>,
+        nls: &[LookupOp],
+    ) -> BaseConfig<F> {
+        let advices = (0..3)
+            .map(|_| VarTensor::new_advice(cs, K, 1, 4))
+            .collect::<Vec<_>>();
+        let mut config = BaseConfig::default();
+        match registry {
+            Some(registry) => {
+                for nl in nls {
+                    config
+                        .configure_lookup_with_registry(
+                            cs,
+                            &advices[0],
+                            &advices[1],
+                            &advices[2],
+                            RANGE,
+                            K,
+                            nl,
+                            registry,
+                        )
+                        .unwrap();
+                }
+            }
+            None => {
+                for nl in nls {
+                    config
+                        .configure_lookup(cs, &advices[0], &advices[1], &advices[2], RANGE, K, nl)
+                        .unwrap();
+                }
+            }
+        }
+        config
+    }
+
+    fn configure_layers(
+        cs: &mut ConstraintSystem<F>,
+        registry: Option<&mut TableRegistry<F>>,
+    ) -> LayerConfigs {
+        let (first, second) = match registry {
+            Some(registry) => (
+                configure_layer(cs, Some(registry), &[LookupOp::ReLU]),
+                configure_layer(cs, Some(registry), &[LookupOp::ReLU, LookupOp::Abs]),
+            ),
+            None => (
+                configure_layer(cs, None, &[LookupOp::ReLU]),
+                configure_layer(cs, None, &[LookupOp::ReLU, LookupOp::Abs]),
+            ),
+        };
+        LayerConfigs { first, second }
+    }
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = LayerConfigs;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            configure_layers(cs, Some(&mut TableRegistry::default()))
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            // the shared tables are only laid out by the first layer
+            config.first.layout_tables(&mut layouter).unwrap();
+            config.second.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let relu = config
+                            .first
+                            .layout(&mut region, &[self.input.clone()], Box::new(LookupOp::ReLU))
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+                        let abs = config
+                            .second
+                            .layout(&mut region, &[self.input.clone()], Box::new(LookupOp::Abs))
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+                        config
+                            .second
+                            .layout(&mut region, &[abs], Box::new(LookupOp::ReLU))
+                            .map_err(|_| Error::Synthesis)?;
+                        assert_eq!(relu.get_int_evals().unwrap()[..], [0, 0, 2, 7]);
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn shared_table_circuit() {
+        let circuit = MyCircuit::<F> {
+            input: signed(&[-3, 0, 2, 7]),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn shared_table_allocated_once() {
+        let mut registry = TableRegistry::default();
+        let mut cs = ConstraintSystem::<F>::default();
+        let shared = configure_layers(&mut cs, Some(&mut registry));
+        let shared_columns = cs.num_fixed_columns();
+
+        let mut cs = ConstraintSystem::<F>::default();
+        configure_layers(&mut cs, None);
+        let separate_columns = cs.num_fixed_columns();
+
+        // the ReLU and Abs tables, each with one output column over one shared input column
+        assert_eq!(registry.len(), 2);
+        assert_eq!(shared_columns, 3);
+        // each layer allocates its own ReLU table
+        assert_eq!(separate_columns, 5);
+
+        let (first, second) = (
+            &shared.first.tables[&LookupOp::ReLU],
+            &shared.second.tables[&LookupOp::ReLU],
+        );
+        assert_eq!(first.table_inputs, second.table_inputs);
+        assert_eq!(first.table_outputs, second.table_outputs);
+    }
+}
+
+#[cfg(test)]
+mod lookup_output_bits {
+    use super::*;
+
+    const K: usize = 5;
+    const RANGE: (i128, i128) = (-8, 8);
+
+    #[derive(Clone)]
+    struct AbsCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        output_bits: usize,
+    }
+
+    impl Circuit<F> for AbsCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, 4))
+                .collect::<Vec<_>>();
+            let mut config = BaseConfig::default();
+            config
+                .configure_lookup(
+                    cs,
+                    &advices[0],
+                    &advices[1],
+                    &advices[2],
+                    RANGE,
+                    K,
+                    &LookupOp::Abs,
+                )
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config
+                .set_lookup_output_bits(Some(self.output_bits))
+                .map_err(|_| Error::Synthesis)?;
+            config
+                .layout_tables(&mut layouter)
+                .map_err(|_| Error::Synthesis)?;
+            layouter.assign_region(
+                || "",
+                |region| {
+                    let mut region = RegionCtx::new(region, 0, 1);
+                    config
+                        .layout(&mut region, &[self.input.clone()], Box::new(LookupOp::Abs))
+                        .map_err(|_| Error::Synthesis)?;
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn lookup_outputs_within_bits() {
+        let circuit = AbsCircuit::<F> {
+            input: signed(&[-8, -1, 0, 7]),
+            output_bits: 5,
+        };
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn lookup_outputs_exceed_bits() {
+        // |-8| = 8 is out of the signed 4-bit range [-8, 7]
+        let circuit = AbsCircuit::<F> {
+            input: signed(&[-1, 0, 1, 2]),
+            output_bits: 4,
+        };
+        assert!(MockProver::run(K as u32, &circuit, vec![]).is_err());
+
+        let mut cs = ConstraintSystem::<F>::default();
+        let config = AbsCircuit::<F>::configure(&mut cs);
+        let table = &config.tables[&LookupOp::Abs];
+        let (_, outputs) = table.evaluate().unwrap();
+        assert!(table.check_output_range(&outputs, 5).is_ok());
+        assert!(matches!(
+            table.check_output_range(&outputs, 4),
+            Err(CircuitError::SignedRangeOverflow(_, 4))
+        ));
+    }
+
+    #[test]
+    fn lookup_output_bits_are_validated() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let mut config = AbsCircuit::<F>::configure(&mut cs);
+        assert!(matches!(
+            config.set_lookup_output_bits(Some(0)),
+            Err(CircuitError::InvalidSignedBits(0))
+        ));
+        assert_eq!(config.lookup_output_bits(), None);
+
+        let table = &config.tables[&LookupOp::Abs];
+        let (_, outputs) = table.evaluate().unwrap();
+        assert!(matches!(
+            table.check_output_range(&outputs, 0),
+            Err(CircuitError::InvalidSignedBits(0))
+        ));
+    }
+}
+
+#[cfg(test)]
+mod lookup_default_pair {
+    use super::*;
+
+    const K: usize = 5;
+    const RANGE: (i128, i128) = (-6, 6);
+
+    fn configure(
+        cs: &mut ConstraintSystem<F>,
+        default_pair: Option<(i128, i128)>,
+    ) -> Result<BaseConfig<F>, CircuitError> {
+        let advices = (0..3)
+            .map(|_| VarTensor::new_advice(cs, K, 1, 4))
+            .collect::<Vec<_>>();
+
+        let nl = match default_pair {
+            Some((x, y)) => LookupOp::ReLU.with_default_pair(x, y),
+            None => (&LookupOp::ReLU).into(),
+        };
+        let mut config = BaseConfig::default();
+        config.configure_lookup(cs, &advices[0], &advices[1], &advices[2], RANGE, K, &nl)?;
+        Ok(config)
+    }
+
+    #[derive(Clone)]
+    struct ReLUCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+    }
+
+    impl Circuit<F> for ReLUCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            // 2 is a real input, unlike the default -6
+            configure(cs, Some((2, 2))).unwrap()
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = config
+                            .layout(&mut region, &[self.input.clone()], Box::new(LookupOp::ReLU))
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+                        assert_eq!(output.get_int_evals().unwrap()[..], [0, 0, 2, 5]);
+                        Ok(())
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn default_pair_override_circuit() {
+        let circuit = ReLUCircuit::<F> {
+            input: signed(&[-3, 0, 2, 5]),
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn default_pair_override_pads_with_pair() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let config = configure(&mut cs, None).unwrap();
+        let table = &config.tables[&LookupOp::ReLU];
+        assert_eq!(table.get_default_pair(0), (-F::from(6), F::ZERO));
+
+        let mut cs = ConstraintSystem::<F>::default();
+        let config = configure(&mut cs, Some((2, 2))).unwrap();
+        let table = &config.tables[&LookupOp::ReLU];
+        assert_eq!(table.default_pair, Some((2, 2)));
+        assert_eq!(table.get_default_pair(0), (F::from(2), F::from(2)));
+        // the first element is still the padding for the table itself
+        assert_eq!(table.get_first_element(0), (-F::from(6), F::ZERO));
+    }
+
+    #[test]
+    fn default_pair_must_be_table_row() {
+        for pair in [(2, 1), (7, 7)] {
+            let mut cs = ConstraintSystem::<F>::default();
+            assert!(matches!(
+                configure(&mut cs, Some(pair)),
+                Err(CircuitError::LookupInstantiation)
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+mod parameterized_lookup_keygen {
+    use super::*;
+
+    const K: usize = 9;
+    const LEN: usize = 4;
+
+    /// the op is carried through the circuit params so that configure sees its parameters
+    #[derive(Clone, Debug)]
+    struct NlParams(LookupOp);
+
+    impl Default for NlParams {
+        fn default() -> Self {
+            NlParams(LookupOp::ReLU)
+        }
+    }
+
+    #[derive(Clone)]
+    struct NlCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        op: LookupOp,
+    }
+
+    impl Circuit<F> for NlCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = NlParams;
+
+        fn without_witnesses(&self) -> Self {
+            // blank the assignments but keep the (parameterized) op, so that keygen and
+            // proving lay out the same tables
+            let blank = Tensor::new(Some(&vec![Value::unknown(); LEN]), &[LEN]).unwrap();
+            NlCircuit {
+                input: ValTensor::from(blank),
+                op: self.op.clone(),
+            }
+        }
+
+        fn params(&self) -> Self::Params {
+            NlParams(self.op.clone())
+        }
+
+        fn configure_with_params(
+            cs: &mut ConstraintSystem<F>,
+            params: Self::Params,
+        ) -> Self::Config {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K, 1, LEN))
+                .collect::<Vec<_>>();
+
+            let mut config = BaseConfig::default();
+            config
+                .configure_lookup(
+                    cs,
+                    &advices[0],
+                    &advices[1],
+                    &advices[2],
+                    (-128, 128),
+                    K,
+                    &params.0,
+                )
+                .unwrap();
+            config
+        }
+
+        fn configure(_cs: &mut ConstraintSystem<F>) -> Self::Config {
+            unimplemented!("call configure_with_params instead")
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter.assign_region(
+                || "",
+                |region| {
+                    let mut region = RegionCtx::new(region, 0, 1);
+                    config
+                        .layout(
+                            &mut region,
+                            &[self.input.clone()],
+                            Box::new(self.op.clone()),
+                        )
+                        .map_err(|_| Error::Synthesis)?;
+                    Ok(())
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn without_witnesses_keeps_op_params() {
+        let op = LookupOp::Softplus {
+            scale: utils::F32(8.0),
+        };
+        let circuit = NlCircuit::<F> {
+            input: signed(&[-3, 0, 2, 40]),
+            op: op.clone(),
+        };
+        let empty = circuit.without_witnesses();
+        assert_eq!(empty.op, op);
+        assert!(empty.input.any_unknowns());
+
+        let params = crate::pfsys::srs::gen_srs::<
+            halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme<_>,
+        >(K as u32);
+
+        // keys generated from the blank circuit and from the witnessed circuit commit to the
+        // same fixed (table) columns
+        let keygen_vk = halo2_proofs::plonk::keygen_vk(&params, &empty).unwrap();
+        let proving_vk = halo2_proofs::plonk::keygen_vk(&params, &circuit).unwrap();
+        assert_eq!(
+            keygen_vk.fixed_commitments(),
+            proving_vk.fixed_commitments()
+        );
+
+        // whereas a differently parameterized op yields a different table
+        let other = NlCircuit::<F> {
+            input: circuit.input.clone(),
+            op: LookupOp::Softplus {
+                scale: utils::F32(4.0),
+            },
+        };
+        let other_vk = halo2_proofs::plonk::keygen_vk(&params, &other.without_witnesses()).unwrap();
+        assert_ne!(keygen_vk.fixed_commitments(), other_vk.fixed_commitments());
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+}
+
+#[cfg(test)]
+mod safe_mode_mismatch {
+    use super::*;
+
+    #[test]
+    fn reports_first_mismatching_element() {
+        let input = signed(&[-1, 2, 3, 4]);
+        let op: Box<dyn Op<F>> = Box::new(LookupOp::ReLU);
+
+        assert!(op
+            .safe_mode_check(&signed(&[0, 2, 3, 4]), &[input.clone()])
+            .is_ok());
+
+        // element 2 is deliberately wrong, element 3 too but only the first is reported
+        let err = op
+            .safe_mode_check(&signed(&[0, 2, -7, 5]), &[input])
+            .unwrap_err();
+        match err.downcast_ref::<CircuitError>() {
+            Some(CircuitError::SafeModeMismatch {
+                index,
+                claimed,
+                expected,
+                ..
+            }) => assert_eq!((*index, *claimed, *expected), (2, -7, 3)),
+            _ => panic!("unexpected error: {}", err),
+        }
+        let msg = err.to_string();
+        assert!(msg.contains("element 2 is -7, expected 3"), "{}", msg);
+    }
+}
+
+#[cfg(test)]
+mod constant_folding {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+    use crate::graph::Visibility;
+
+    const K: usize = 5;
+    const LEN: usize = 4;
+    // the folded gate is configured for this one constant, multiplications by any other are laid out as usual
+    const SCALE: u64 = 128;
+
+    fn configure(cs: &mut ConstraintSystem<F>, fold: bool) -> BaseConfig<F> {
+        let a = VarTensor::new_advice(cs, K, 1, 4 * LEN);
+        let b = VarTensor::new_advice(cs, K, 1, 4 * LEN);
+        let output = VarTensor::new_advice(cs, K, 1, 4 * LEN);
+
+        let mut config = BaseConfig::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap();
+        if fold {
+            config
+                .configure_base_op(cs, &BaseOp::MultConst { c: SCALE as i128 })
+                .unwrap();
+        }
+        config
+    }
+
+    fn scale_constant() -> ValTensor<F> {
+        constant(SCALE)
+    }
+
+    fn constant(c: u64) -> ValTensor<F> {
+        let mut constant = Tensor::from(vec![F::from(c)].into_iter());
+        constant.set_visibility(&Visibility::Fixed);
+        constant.into()
+    }
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(cs, true)
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let folded = config
+                            .layout(
+                                &mut region,
+                                &[self.input.clone(), scale_constant()],
+                                Box::new(PolyOp::Mult),
+                            )
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+
+                        let advice_scale = signed(&[SCALE as i64; LEN]);
+                        let unfolded = config
+                            .layout(
+                                &mut region,
+                                &[self.input.clone(), advice_scale],
+                                Box::new(PolyOp::Mult),
+                            )
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+
+                        if !folded.any_unknowns() {
+                            assert_eq!(folded.get_int_evals().unwrap()[..], [128, -256, 0, 640]);
+                        }
+                        region.constrain_equal(&folded, &unfolded)
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn mult_by_constant_circuit() {
+        let circuit = MyCircuit::<F> {
+            input: signed(&[1, -2, 0, 5]),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn mult_by_constant_assigns_fewer_cells() {
+        let input = signed(&[1, -2, 0, 5]);
+        let values = [input, scale_constant()];
+
+        let mut cs = ConstraintSystem::<F>::default();
+        let mut folding = configure(&mut cs, true);
+        let mut region = RegionCtx::new_dummy(0, 1);
+        folding
+            .layout(&mut region, &values, Box::new(PolyOp::Mult))
+            .unwrap();
+        let folded_constants = region.total_constants();
+
+        // without the folded gate the constant is broadcast into an advice column
+        let mut cs = ConstraintSystem::<F>::default();
+        let mut plain = configure(&mut cs, false);
+        let mut region = RegionCtx::new_dummy(0, 1);
+        plain
+            .layout(&mut region, &values, Box::new(PolyOp::Mult))
+            .unwrap();
+
+        assert_eq!(folded_constants, 0);
+        assert_eq!(region.total_constants(), LEN);
+    }
+
+    #[test]
+    fn only_the_configured_constant_is_folded() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let mut folding = configure(&mut cs, true);
+        let mut region = RegionCtx::new_dummy(0, 1);
+        folding
+            .layout(
+                &mut region,
+                &[signed(&[1, -2, 0, 5]), constant(SCALE / 2)],
+                Box::new(PolyOp::Mult),
+            )
+            .unwrap();
+
+        // MultConst { c: 128 } doesn't cover a multiplication by 64
+        assert_eq!(region.total_constants(), LEN);
+    }
+}
+
+#[cfg(test)]
+mod dummy_layout {
+    use super::*;
+    use crate::tensor::ValType;
+
+    #[test]
+    fn dummy_lookup_layout_matches_full_layout() {
+        let op = LookupOp::ReLU;
+        let mut config = BaseConfig::<F>::dummy(16, 1);
+
+        let mut values: Tensor<ValType<F>> = Tensor::from(
+            vec![
+                Value::<F>::unknown().into(),
+                ValType::Constant(-F::from(3)),
+                Value::<F>::unknown().into(),
+                Value::<F>::unknown().into(),
+                ValType::Constant(F::from(2)),
+                Value::<F>::unknown().into(),
+            ]
+            .into_iter(),
+        );
+        values.reshape(&[2, 3]);
+        let input = ValTensor::from(values);
+
+        // the shortcut taken by BaseConfig::layout in a dummy region
+        let mut fast = RegionCtx::new_dummy(3, 1);
+        let fast_output = config
+            .layout(&mut fast, &[input.clone()], Box::new(op.clone()))
+            .unwrap()
+            .unwrap();
+
+        let mut full = RegionCtx::new_dummy(3, 1);
+        let full_output = layouts::nonlinearity(&config, &mut full, &[input.clone()], &op).unwrap();
+
+        assert_eq!(fast_output.dims(), &[2, 3]);
+        assert_eq!(fast_output.dims(), full_output.dims());
+        assert_eq!(fast.row(), full.row());
+        assert_eq!(fast.linear_coord(), full.linear_coord());
+        assert_eq!(fast.total_constants(), full.total_constants());
+        // constants still propagate through the op
+        let fast_inner = fast_output.get_inner_tensor().unwrap();
+        assert_eq!(fast_inner.get_flat_index(1).get_felt_eval(), Some(F::ZERO));
+        assert_eq!(
+            fast_inner.get_flat_index(4).get_felt_eval(),
+            Some(F::from(2))
+        );
+        assert!(fast_inner.get_flat_index(0).get_felt_eval().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "onnx")]
+    fn wrapped_lookup_dummy_layout() {
+        use crate::graph::node::{RebaseScale, SupportedOp};
+
+        let mut config = BaseConfig::<F>::dummy(16, 1);
+        let input = signed(&[-3, 0, 2, 6]);
+
+        let mut region = RegionCtx::new_dummy(0, 1);
+        let expected =
+            layouts::dummy_nonlinearity(&mut region, &[input.clone()], &LookupOp::ReLU).unwrap();
+
+        // the wrapper forwards to the shortcut of the inner lookup
+        let op = SupportedOp::Nonlinear(LookupOp::ReLU);
+        let mut wrapped = RegionCtx::new_dummy(0, 1);
+        let output = config
+            .layout(&mut wrapped, &[input.clone()], Box::new(op.clone()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(wrapped.row(), region.row());
+        assert_eq!(
+            output.get_int_evals().unwrap(),
+            expected.get_int_evals().unwrap()
+        );
+
+        // rebasing adds a second lookup over the output of the first
+        let op = SupportedOp::RebaseScale(RebaseScale {
+            inner: Box::new(op),
+            multiplier: 2.0,
+            target_scale: 0,
+            original_scale: 1,
+        });
+        let mut rebased = RegionCtx::new_dummy(0, 1);
+        let output = config
+            .layout(&mut rebased, &[input], Box::new(op))
+            .unwrap()
+            .unwrap();
+        assert_eq!(rebased.row(), 2 * region.row());
+        assert_eq!(
+            output.get_int_evals().unwrap(),
+            Tensor::from([0, 0, 1, 3].into_iter())
+        );
+    }
+}
+
+#[cfg(test)]
+mod table_input_reuse {
+    use super::*;
+
+    const K: usize = 6;
+    const RANGE: (i128, i128) = (-8, 8);
+
+    #[test]
+    fn second_op_reuses_first_op_inputs() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let advices = (0..3)
+            .map(|_| VarTensor::new_advice(&mut cs, K, 1, 4))
+            .collect::<Vec<_>>();
+        let mut config = BaseConfig::<F>::default();
+
+        // nothing to reuse before the first table is configured
+        assert!(config.can_reuse_table_inputs(&LookupOp::ReLU).is_none());
+        config
+            .configure_lookup(
+                &mut cs,
+                &advices[0],
+                &advices[1],
+                &advices[2],
+                RANGE,
+                K,
+                &LookupOp::ReLU,
+            )
+            .unwrap();
+        let first_inputs = config.tables[&LookupOp::ReLU].table_inputs.clone();
+
+        // an already configured op allocates nothing
+        assert!(config.can_reuse_table_inputs(&LookupOp::ReLU).is_none());
+        assert_eq!(
+            config.can_reuse_table_inputs(&LookupOp::Abs),
+            Some(first_inputs.clone())
+        );
+
+        let fixed_columns = cs.num_fixed_columns();
+        config
+            .configure_lookup(
+                &mut cs,
+                &advices[0],
+                &advices[1],
+                &advices[2],
+                RANGE,
+                K,
+                &LookupOp::Abs,
+            )
+            .unwrap();
+        assert_eq!(config.tables[&LookupOp::Abs].table_inputs, first_inputs);
+        // only the output columns of the second table are allocated
+        assert_eq!(
+            cs.num_fixed_columns() - fixed_columns,
+            config.tables[&LookupOp::Abs].table_outputs.len()
+        );
+    }
+}
+
+#[cfg(test)]
+mod op_harness {
+    use super::*;
+
+    #[test]
+    fn harness_add() {
+        BaseConfig::prove_op(
+            Box::new(PolyOp::Add),
+            &[signed(&[1, -2, 3, 4]), signed(&[5, 6, -7, 8])],
+            &Tensor::from([6, 4, -4, 12].into_iter()),
+        );
+    }
+
+    #[test]
+    fn harness_dot() {
+        BaseConfig::prove_op(
+            Box::new(PolyOp::Einsum {
+                equation: "i,i->".to_string(),
+            }),
+            &[signed(&[1, -2, 3, 4]), signed(&[5, 6, -7, 8])],
+            &Tensor::from([5 - 12 - 21 + 32].into_iter()),
+        );
+    }
+
+    #[test]
+    fn harness_relu() {
+        // lookups required by the op are configured by the harness
+        BaseConfig::prove_op(
+            Box::new(LookupOp::ReLU),
+            &[signed(&[-3, 0, 7, -1])],
+            &Tensor::from([0, 0, 7, 0].into_iter()),
+        );
+    }
+
+    #[test]
+    fn harness_right_shift() {
+        // ties round away from zero, on both sides of 0
+        BaseConfig::prove_op(
+            Box::new(LookupOp::RightShift { bits: 2 }),
+            &[signed(&[-256, -7, -6, -5, -2, -1, 0, 1, 2, 5, 6, 7, 255])],
+            &Tensor::from([-64, -2, -2, -1, -1, 0, 0, 0, 1, 1, 2, 2, 64].into_iter()),
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn harness_rejects_wrong_expected() {
+        BaseConfig::prove_op(
+            Box::new(PolyOp::Add),
+            &[signed(&[1, 2]), signed(&[3, 4])],
+            &Tensor::from([4, 7].into_iter()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod per_op_lookup_columns {
+    use super::*;
+
+    const K: usize = 8;
+    const LEN: usize = 8;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config.per_op_lookup_columns = true;
+            for nl in [LookupOp::ReLU, LookupOp::Abs] {
+                // the shared columns are the base op columns, which the composite ops also assign to
+                config
+                    .configure_lookup(cs, &b, &output, &a, (-32, 32), K, &nl)
+                    .unwrap();
+            }
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        // relu(x) - |x| and |x| + relu(x), each mixing lookups with base ops
+                        let relu = config
+                            .layout(&mut region, &[self.input.clone()], Box::new(LookupOp::ReLU))
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+                        let abs = config
+                            .layout(&mut region, &[self.input.clone()], Box::new(LookupOp::Abs))
+                            .map_err(|_| Error::Synthesis)?
+                            .unwrap();
+                        config
+                            .layout(
+                                &mut region,
+                                &[relu.clone(), abs.clone()],
+                                Box::new(PolyOp::Sub),
+                            )
+                            .map_err(|_| Error::Synthesis)?;
+                        config
+                            .layout(&mut region, &[abs, relu], Box::new(PolyOp::Add))
+                            .map_err(|_| Error::Synthesis)?;
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn per_op_columns_are_distinct() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let config = MyCircuit::<F>::configure(&mut cs);
+
+        let (relu_input, relu_output, _) = config.lookup_vars(&LookupOp::ReLU);
+        let (abs_input, abs_output, _) = config.lookup_vars(&LookupOp::Abs);
+        assert_ne!(relu_input, abs_input);
+        assert_ne!(relu_output, abs_output);
+        // neither collides with the base op columns
+        for var in [relu_input, relu_output, abs_input, abs_output] {
+            assert!(!config.inputs.contains(var));
+            assert_ne!(var, &config.output);
+        }
+        // the shared columns are left unset
+        assert_eq!(config.lookup_input, VarTensor::Empty);
+        // 3 base columns, and 3 per lookup op
+        assert_eq!(cs.num_advice_columns(), 9);
+    }
+
+    #[test]
+    fn composite_ops_with_per_op_columns() {
+        let circuit = MyCircuit::<F> {
+            input: signed(&[-3, 0, 7, -1]),
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod lookup_non_advice {
+    use super::*;
+    use crate::circuit::ops::lookup::BinaryLookupOp;
+
+    const K: usize = 8;
+    const LEN: usize = 4;
+
+    #[test]
+    fn lookup_rejects_non_advice() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let advices = (0..2)
+            .map(|_| VarTensor::new_advice(&mut cs, K, 1, LEN))
+            .collect::<Vec<_>>();
+        let mut config = BaseConfig::<F>::default();
+
+        let res = config.configure_lookup(
+            &mut cs,
+            &VarTensor::Empty,
+            &advices[0],
+            &advices[1],
+            (-16, 16),
+            K,
+            &LookupOp::ReLU,
+        );
+        assert!(matches!(res, Err(CircuitError::DimMismatch(_))));
+        // nothing is registered
+        assert!(config.tables.is_empty());
+    }
+
+    #[test]
+    fn binary_lookup_rejects_non_advice() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let advices = (0..2)
+            .map(|_| VarTensor::new_advice(&mut cs, K, 1, LEN))
+            .collect::<Vec<_>>();
+        let mut config = BaseConfig::<F>::default();
+
+        let res = config.configure_binary_lookup(
+            &mut cs,
+            &advices[0],
+            &advices[1],
+            &VarTensor::Empty,
+            (-4, 4),
+            (0, 4),
+            K,
+            &BinaryLookupOp::Pow,
+        );
+        assert!(matches!(res, Err(CircuitError::DimMismatch(_))));
+        assert!(config.binary_tables.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod accumulated_op {
+    use super::*;
+    use crate::circuit::ops::base::AccumulatedOp;
+    use halo2_proofs::plonk::{Expression, Selector};
+    use std::collections::BTreeMap;
+
+    const K: usize = 6;
+    const LEN: usize = 4;
+
+    /// Doubles its input, defined outside of [BaseOp]
+    struct Double;
+
+    impl AccumulatedOp for Double {
+        fn name(&self) -> &'static str {
+            "DOUBLE"
+        }
+
+        fn query_offset_rng(&self) -> (i32, usize) {
+            (0, 1)
+        }
+
+        fn num_inputs(&self) -> usize {
+            1
+        }
+
+        fn constraint_idx(&self) -> usize {
+            0
+        }
+
+        fn constraints<F: PrimeField>(
+            &self,
+            inputs: &[Expression<F>],
+            outputs: &[Expression<F>],
+        ) -> Vec<Expression<F>> {
+            vec![outputs[0].clone() - (inputs[1].clone() + inputs[1].clone())]
+        }
+    }
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        output: ValTensor<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = (BaseConfig<F>, BTreeMap<(usize, usize), Selector>);
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            let config = BaseConfig::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap();
+            let selectors = config.configure_accumulated_op(cs, &Double).unwrap();
+            (config, selectors)
+        }
+
+        fn synthesize(
+            &self,
+            (config, selectors): Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "",
+                |region| {
+                    let mut region = RegionCtx::new(region, 0, 1);
+                    region.assign(&config.inputs[1], &self.input)?;
+                    let output = region.assign(&config.output, &self.output)?;
+                    for i in 0..output.len() {
+                        let (x, y, z) = config.output.cartesian_coord(region.linear_coord() + i);
+                        region.enable(selectors.get(&(x, y)), z)?;
+                    }
+                    region.increment(output.len());
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn external_op_is_proven() {
+        let circuit = MyCircuit::<F> {
+            input: signed(&[-3, 0, 1, 7]),
+            output: signed(&[-6, 0, 2, 14]),
+        };
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn external_op_rejects_wrong_output() {
+        let circuit = MyCircuit::<F> {
+            input: signed(&[-3, 0, 1, 7]),
+            output: signed(&[-6, 0, 2, 15]),
+        };
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn external_op_requires_enough_inputs() {
+        struct Triple;
+        impl AccumulatedOp for Triple {
+            fn name(&self) -> &'static str {
+                "TRIPLE"
+            }
+            fn query_offset_rng(&self) -> (i32, usize) {
+                (0, 1)
+            }
+            fn num_inputs(&self) -> usize {
+                3
+            }
+            fn constraint_idx(&self) -> usize {
+                0
+            }
+            fn constraints<F: PrimeField>(
+                &self,
+                inputs: &[Expression<F>],
+                outputs: &[Expression<F>],
+            ) -> Vec<Expression<F>> {
+                vec![outputs[0].clone() - inputs[0].clone() - inputs[1].clone() - inputs[2].clone()]
+            }
+        }
+
+        let mut cs = ConstraintSystem::<F>::default();
+        let config = MyCircuit::<F>::configure(&mut cs).0;
+        assert!(matches!(
+            config.configure_accumulated_op(&mut cs, &Triple),
+            Err(CircuitError::DimMismatch(_))
+        ));
+    }
+}
+
+#[cfg(test)]
+mod suggest_columns {
+    use super::*;
+
+    #[test]
+    fn columns_fit_under_logrows() {
+        let cs = ConstraintSystem::<F>::default();
+        for logrows in [4, 6, 10] {
+            let max_rows = VarTensor::max_rows(&cs, logrows);
+            assert!(max_rows < 2usize.pow(logrows as u32));
+            for num_constraints in [
+                0,
+                1,
+                max_rows - 1,
+                max_rows,
+                max_rows + 1,
+                5 * max_rows,
+                1000,
+            ] {
+                let cols = BaseConfig::<F>::suggest_columns(&cs, num_constraints, logrows).unwrap();
+                // each column past the first carries over a cell from the previous one
+                let fits = |cols: usize| cols * max_rows - (cols - 1) >= num_constraints;
+                assert!(fits(cols), "{} columns at logrows {}", cols, logrows);
+                // and no fewer columns would do
+                assert!(cols == 1 || !fits(cols - 1));
+            }
+        }
+    }
+
+    #[test]
+    fn rows_per_column_under_limit() {
+        let cs = ConstraintSystem::<F>::default();
+        let (num_constraints, logrows) = (100, 5);
+        let cols = BaseConfig::<F>::suggest_columns(&cs, num_constraints, logrows).unwrap();
+        let rows_per_col = (num_constraints + cols - 1) / cols + 1;
+        assert!(rows_per_col <= VarTensor::max_rows(&cs, logrows));
+    }
+
+    #[test]
+    fn logrows_too_small() {
+        let cs = ConstraintSystem::<F>::default();
+        assert!(matches!(
+            BaseConfig::<F>::suggest_columns(&cs, 4, 2),
+            Err(CircuitError::DimMismatch(_))
+        ));
+    }
+}
+
+#[cfg(test)]
+mod lookup_disabled_rows {
+    use super::*;
+
+    const K: usize = 8;
+    const LEN: usize = 8;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        lookup_input: Option<ValTensor<F>>,
+        add_inputs: [ValTensor<F>; 2],
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config
+                .configure_lookup(cs, &b, &output, &a, (-16, 16), K, &LookupOp::ReLU)
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        if let Some(input) = &self.lookup_input {
+                            config
+                                .layout(&mut region, &[input.clone()], Box::new(LookupOp::ReLU))
+                                .map_err(|_| Error::Synthesis)?;
+                        }
+                        // these rows share the lookup columns but hold values outside of the table
+                        config
+                            .layout(&mut region, &self.add_inputs, Box::new(PolyOp::Add))
+                            .map_err(|_| Error::Synthesis)?;
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn mixed_region() {
+        let circuit = MyCircuit::<F> {
+            lookup_input: Some(signed(&[-3, 0, 5, -16])),
+            add_inputs: [signed(&[1000, -1000, 3]), signed(&[500, 7, -64])],
+        };
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn no_lookup_rows() {
+        let circuit = MyCircuit::<F> {
+            lookup_input: None,
+            add_inputs: [signed(&[1000, -1000, 3]), signed(&[500, 7, -64])],
+        };
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+}
+
+#[cfg(test)]
+mod accumulated_pow {
+    use super::*;
+
+    const K: usize = 8;
+    const LEN: usize = 32;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        base: ValTensor<F>,
+        exp: usize,
+        expected: Tensor<i128>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = config
+                            .layout_pow(&mut region, &self.base, self.exp)
+                            .map_err(|_| Error::Synthesis)?;
+
+                        assert_eq!(output.dims(), self.base.dims());
+                        if !output.any_unknowns() {
+                            assert_eq!(output.get_int_evals().unwrap(), self.expected);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn matches_reference() {
+        let base = [-3, 2, 1, 4, -1];
+        for exp in [0, 1, 2, 5] {
+            let expected = base.iter().map(|&x| (x as i128).pow(exp as u32));
+            let circuit = MyCircuit::<F> {
+                base: signed(&base),
+                exp,
+                expected: Tensor::from(expected),
+            };
+            let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+            prover.assert_satisfied_par();
+        }
+    }
+}
+
+#[cfg(test)]
+mod matvec {
+    use super::*;
+
+    const K: usize = 8;
+    const LEN: usize = 64;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        matrix: ValTensor<F>,
+        vector: ValTensor<F>,
+        expected: Tensor<i128>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = config
+                            .layout_matvec(&mut region, &self.matrix, &self.vector)
+                            .map_err(|_| Error::Synthesis)?;
+
+                        if !output.any_unknowns() {
+                            assert_eq!(output.get_int_evals().unwrap(), self.expected);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn matches_reference() {
+        let (m, n) = (4, 8);
+        let matrix = (0..m * n).map(|i| (i as i64 % 7) - 3).collect::<Vec<_>>();
+        let vector = (0..n).map(|i| 2 - i as i64).collect::<Vec<_>>();
+        let expected = (0..m).map(|i| {
+            (0..n)
+                .map(|j| (matrix[i * n + j] * vector[j]) as i128)
+                .sum::<i128>()
+        });
+
+        let mut matrix = signed(&matrix);
+        matrix.reshape(&[m, n]).unwrap();
+        let circuit = MyCircuit::<F> {
+            matrix,
+            vector: signed(&vector),
+            expected: Tensor::from(expected),
+        };
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn rejects_mismatched_dims() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let mut config = MyCircuit::<F>::configure(&mut cs);
+        let mut matrix = signed(&[1, 2, 3, 4, 5, 6]);
+        matrix.reshape(&[2, 3]).unwrap();
+
+        let mut region = RegionCtx::new_dummy(0, 1);
+        assert!(config
+            .layout_matvec(&mut region, &matrix, &signed(&[1, 2]))
+            .is_err());
+    }
+}
+
+#[cfg(test)]
+mod im2col_conv {
+    use super::*;
+
+    const K: usize = 10;
+    const LEN: usize = 256;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        kernels: ValTensor<F>,
+        stride: (usize, usize),
+        padding: [(usize, usize); 2],
+        expected: Tensor<i128>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap()
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let output = config
+                            .layout_conv2d(
+                                &mut region,
+                                &self.input,
+                                &self.kernels,
+                                self.stride,
+                                self.padding,
+                            )
+                            .map_err(|_| Error::Synthesis)?;
+
+                        assert_eq!(output.dims()[0], 1);
+                        if !output.any_unknowns() {
+                            assert_eq!(output.get_int_evals().unwrap(), self.expected);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    /// Direct single channel, single kernel convolution over the zero padded input.
+    fn reference(
+        input: &[i64],
+        (h, w): (usize, usize),
+        kernel: &[i64; 9],
+        stride: usize,
+        pad: usize,
+    ) -> Tensor<i128> {
+        let at = |i: isize, j: isize| {
+            if i < 0 || j < 0 || i >= h as isize || j >= w as isize {
+                0
+            } else {
+                input[i as usize * w + j as usize]
+            }
+        };
+        let (oh, ow) = (
+            (h + 2 * pad - 3) / stride + 1,
+            (w + 2 * pad - 3) / stride + 1,
+        );
+        let mut out = vec![];
+        for i in 0..oh {
+            for j in 0..ow {
+                let mut acc = 0;
+                for ki in 0..3 {
+                    for kj in 0..3 {
+                        let (r, c) = (
+                            (i * stride + ki) as isize - pad as isize,
+                            (j * stride + kj) as isize - pad as isize,
+                        );
+                        acc += at(r, c) * kernel[ki * 3 + kj];
+                    }
+                }
+                out.push(acc as i128);
+            }
+        }
+        Tensor::from(out.into_iter())
+    }
+
+    fn run(stride: usize, pad: usize) {
+        let (h, w) = (5, 4);
+        let input = (0..(h * w) as i64).map(|i| (i % 5) - 2).collect::<Vec<_>>();
+        let kernel = [1, 0, -1, 2, 3, -2, 0, 1, 1];
+
+        let mut input_tensor = signed(&input);
+        input_tensor.reshape(&[1, h, w]).unwrap();
+        let mut kernels = signed(&kernel);
+        kernels.reshape(&[1, 1, 3, 3]).unwrap();
+
+        let circuit = MyCircuit::<F> {
+            input: input_tensor,
+            kernels,
+            stride: (stride, stride),
+            padding: [(pad, pad), (pad, pad)],
+            expected: reference(&input, (h, w), &kernel, stride, pad),
+        };
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn same_padding() {
+        run(1, 1);
+    }
+
+    #[test]
+    fn strided_without_padding() {
+        run(2, 0);
+    }
+}
+
+#[cfg(test)]
+mod configure_with_ops {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 6;
+    const LEN: usize = 4;
+
+    fn columns(cs: &mut ConstraintSystem<F>) -> (Vec<VarTensor>, VarTensor) {
+        let inputs = (0..2)
+            .map(|_| VarTensor::new_advice(cs, K, 1, LEN))
+            .collect::<Vec<_>>();
+        (inputs, VarTensor::new_advice(cs, K, 1, LEN))
+    }
+
+    #[test]
+    fn restricted_ops_have_fewer_selectors() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let (inputs, output) = columns(&mut cs);
+        let full = BaseConfig::<F>::configure(&mut cs, &inputs, &output, CheckMode::SAFE).unwrap();
+        let full_gates = cs.gates().len();
+
+        let mut cs = ConstraintSystem::<F>::default();
+        let (inputs, output) = columns(&mut cs);
+        let restricted = BaseConfig::<F>::configure_with_ops(
+            &mut cs,
+            &inputs,
+            &output,
+            CheckMode::SAFE,
+            &[BaseOp::Add, BaseOp::Mult],
+        )
+        .unwrap();
+
+        assert!(restricted.selectors.len() < full.selectors.len());
+        assert!(cs.gates().len() < full_gates);
+        assert_eq!(
+            restricted
+                .configured_base_ops()
+                .map(|(op, _, _)| op.clone())
+                .collect::<Vec<_>>(),
+            vec![BaseOp::Add, BaseOp::Mult]
+        );
+    }
+
+    #[test]
+    fn ops_need_enough_inputs() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let (inputs, output) = columns(&mut cs);
+        let res = BaseConfig::<F>::configure_with_ops(
+            &mut cs,
+            &inputs,
+            &output,
+            CheckMode::SAFE,
+            &[BaseOp::Add, BaseOp::MulAdd],
+        );
+        assert!(matches!(res, Err(CircuitError::DimMismatch(_))));
+    }
+
+    #[test]
+    fn base_op_needs_enough_inputs() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let (inputs, output) = columns(&mut cs);
+        let mut config =
+            BaseConfig::<F>::configure(&mut cs, &inputs, &output, CheckMode::SAFE).unwrap();
+        let res = config.configure_base_op(&mut cs, &BaseOp::MulAdd);
+        assert!(matches!(res, Err(CircuitError::DimMismatch(_))));
+        assert!(config
+            .configured_base_ops()
+            .all(|(op, _, _)| op != &BaseOp::MulAdd));
+    }
+}
+
+#[cfg(test)]
+mod saturating_pairwise {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 9;
+    const LEN: usize = 4;
+    const BITS: usize = 4;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        inputs: [ValTensor<F>; 2],
+        sub: bool,
+        expected: Tensor<i128>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            VarTensor::constant_cols(cs, K, 2, false);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config.set_signed_range_bits(Some(BITS)).unwrap();
+            // int4 saturation
+            let (min, max) = (-8, 7);
+            config
+                .configure_base_op(cs, &BaseOp::SaturatingAdd { min, max })
+                .unwrap();
+            config
+                .configure_base_op(cs, &BaseOp::SaturatingSub { min, max })
+                .unwrap();
+            config
+                .configure_lookup(cs, &b, &output, &a, (-32, 32), K, &LookupOp::ReLU)
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let [a, b] = &self.inputs;
+                        let output = if self.sub {
+                            config.layout_saturating_sub(&mut region, a, b)
+                        } else {
+                            config.layout_saturating_add(&mut region, a, b)
+                        }
+                        .map_err(|_| Error::Synthesis)?;
+
+                        if !output.any_unknowns() {
+                            assert_eq!(output.get_int_evals().unwrap(), self.expected);
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    fn run(a: &[i64], b: &[i64], sub: bool, expected: &[i128]) {
+        let circuit = MyCircuit::<F> {
+            inputs: [signed(a), signed(b)],
+            sub,
+            expected: Tensor::from(expected.iter().cloned()),
+        };
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn in_range() {
+        run(&[3, -4, 0, 7], &[2, -4, -8, 0], false, &[5, -8, -8, 7]);
+        run(&[3, -4, 0, 7], &[2, 4, 8, 0], true, &[1, -8, -8, 7]);
+    }
+
+    #[test]
+    fn overflow_high() {
+        run(&[6, 7, 4], &[5, 7, 4], false, &[7, 7, 7]);
+        run(&[5, 7, 0], &[-6, -7, -8], true, &[7, 7, 7]);
+    }
+
+    #[test]
+    fn overflow_low() {
+        run(&[-6, -8, -5], &[-5, -8, -4], false, &[-8, -8, -8]);
+        run(&[-7, -8, 0], &[3, 8, 9], true, &[-8, -8, -8]);
+    }
+
+    #[test]
+    fn requires_signed_range_bits() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let mut config = MyCircuit::<F>::configure(&mut cs);
+        config.set_signed_range_bits(None).unwrap();
+        let mut region = RegionCtx::new_dummy(0, 1);
+        assert!(config
+            .layout_saturating_add(&mut region, &signed(&[1]), &signed(&[2]))
+            .is_err());
+    }
+}
+
+#[cfg(test)]
+mod enforce_equal {
+    use super::*;
+    use crate::fieldutils::i128_to_felt;
+
+    const K: usize = 6;
+    const LEN: usize = 4;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        computed: ValTensor<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = (BaseConfig<F>, ValTensor<F>);
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            VarTensor::constant_cols(cs, K, 1, false);
+            let instance = ValTensor::new_instance(cs, vec![vec![LEN]], 0);
+            let config = BaseConfig::configure(cs, &[a, b], &output, CheckMode::SAFE).unwrap();
+            (config, instance)
+        }
+
+        fn synthesize(
+            &self,
+            (mut config, instance): Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        config
+                            .layout_enforce_equal(&mut region, &self.computed, &instance)
+                            .map_err(|_| Error::Synthesis)?;
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    fn prover(computed: &[i64], instance: &[i64]) -> MockProver<F> {
+        let circuit = MyCircuit::<F> {
+            computed: signed(computed),
+        };
+        let instance = instance.iter().map(|x| i128_to_felt(*x as i128)).collect();
+        MockProver::run(K as u32, &circuit, vec![instance]).unwrap()
+    }
+
+    #[test]
+    fn matching() {
+        prover(&[1, -2, 0, 5], &[1, -2, 0, 5]).assert_satisfied();
+    }
+
+    #[test]
+    fn one_element_differs() {
+        assert!(prover(&[1, -2, 0, 5], &[1, -2, 1, 5]).verify().is_err());
+    }
+
+    #[test]
+    fn length_mismatch() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let (mut config, instance) = MyCircuit::<F>::configure(&mut cs);
+        let mut region = RegionCtx::new_dummy(0, 1);
+        assert!(config
+            .layout_enforce_equal(&mut region, &signed(&[1, 2]), &instance)
+            .is_err());
+    }
+}
+
+#[cfg(test)]
+mod div_mod_multi {
+    use super::*;
+    use crate::circuit::ops::base::BaseOp;
+
+    const K: usize = 8;
+    const LEN: usize = 4;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        inputs: [ValTensor<F>; 2],
+        expected: [Tensor<i128>; 2],
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            VarTensor::constant_cols(cs, K, 2, false);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config.configure_base_op(cs, &BaseOp::Div).unwrap();
+            config
+                .configure_lookup(cs, &b, &output, &a, (-32, 32), K, &LookupOp::ReLU)
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1);
+                        let outputs = config
+                            .layout_multi(&mut region, &self.inputs, Box::new(HybridOp::DivMod))
+                            .map_err(|_| Error::Synthesis)?;
+
+                        assert_eq!(outputs.len(), 2);
+                        for (output, expected) in outputs.iter().zip(self.expected.iter()) {
+                            if !output.any_unknowns() {
+                                assert_eq!(output.get_int_evals().unwrap(), *expected);
+                            }
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn quotient_and_remainder() {
+        let circuit = MyCircuit::<F> {
+            inputs: [signed(&[7, -7, 0, 5]), signed(&[3, 3, 3, 2])],
+            expected: [
+                Tensor::from([2, -3, 0, 2].into_iter()),
+                Tensor::from([1, 2, 0, 1].into_iter()),
+            ],
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn f_multi_matches_f() {
+        let inputs = [
+            signed(&[9, -4]).get_felt_evals().unwrap(),
+            signed(&[4, 4]).get_felt_evals().unwrap(),
+        ];
+        let outputs = Op::<F>::f_multi(&HybridOp::DivMod, &inputs).unwrap();
+        assert_eq!(outputs.len(), 2);
+        // the single output is the quotient
+        assert_eq!(
+            Op::<F>::f(&HybridOp::DivMod, &inputs).unwrap().output,
+            outputs[0]
+        );
+        assert_eq!(
+            outputs[1]
+                .iter()
+                .map(|x| crate::fieldutils::felt_to_i128(*x))
+                .collect::<Vec<_>>(),
+            vec![1, 0]
+        );
+
+        // single output ops default to a single element
+        assert_eq!(
+            Op::<F>::f_multi(&HybridOp::Greater, &inputs).unwrap().len(),
+            1
+        );
+    }
+}
+
+#[cfg(test)]
+mod streamed_table {
+    use super::*;
+
+    const K: usize = 6;
+    const LEN: usize = 6;
+    // spans two columns at K = 6
+    const RANGE: (i128, i128) = (-40, 40);
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        batch_rows: Option<usize>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap();
+            config
+                .configure_lookup(cs, &b, &output, &a, RANGE, K, &LookupOp::ReLU)
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            match self.batch_rows {
+                Some(batch_rows) => {
+                    let table = config.tables.get_mut(&LookupOp::ReLU).unwrap();
+                    assert!(table.table_inputs.len() > 1);
+                    table
+                        .layout_streamed(&mut layouter, false, batch_rows)
+                        .unwrap();
+                }
+                None => config.layout_tables(&mut layouter).unwrap(),
+            }
+            layouter.assign_region(
+                || "",
+                |region| {
+                    let mut region = RegionCtx::new(region, 0, 1);
+                    config
+                        .layout(&mut region, &[self.input.clone()], Box::new(LookupOp::ReLU))
+                        .map_err(|_| Error::Synthesis)?;
+                    Ok(())
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn streamed_matches_monolithic() {
+        // lookups from both columns of the table
+        let input = signed(&[-40, -3, 0, 15, 39, 40]);
+        for batch_rows in [None, Some(1), Some(7), Some(1 << K)] {
+            let circuit = MyCircuit::<F> {
+                input: input.clone(),
+                batch_rows,
+            };
+            let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+            prover.assert_satisfied_par();
+        }
+    }
+}
+
+#[cfg(test)]
+mod preset_lookup_columns {
+    use super::*;
+
+    const K: usize = 8;
+    const LEN: usize = 4;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        input: ValTensor<F>,
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+            let lookup_input = VarTensor::new_advice(cs, K, 1, LEN);
+            let lookup_output = VarTensor::new_advice(cs, K, 1, LEN);
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE)
+                    .unwrap()
+                    .with_lookup_columns(&lookup_input, &lookup_output)
+                    .unwrap();
+            config
+                .configure_lookup(cs, &b, &output, &a, (-32, 32), K, &LookupOp::ReLU)
+                .unwrap();
+            assert_eq!(config.lookup_input, lookup_input);
+            assert_eq!(config.lookup_output, lookup_output);
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter.assign_region(
+                || "",
+                |region| {
+                    let mut region = RegionCtx::new(region, 0, 1);
+                    config
+                        .layout(&mut region, &[self.input.clone()], Box::new(LookupOp::ReLU))
+                        .map_err(|_| Error::Synthesis)?;
+                    Ok(())
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn lookups_use_preset_columns() {
+        let circuit = MyCircuit::<F> {
+            input: signed(&[-3, 0, 5, 31]),
+            _marker: PhantomData,
+        };
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    fn preset_columns_set_once() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let advices = (0..3)
+            .map(|_| VarTensor::new_advice(&mut cs, K, 1, LEN))
+            .collect::<Vec<_>>();
+
+        let config = BaseConfig::<F>::default()
+            .with_lookup_columns(&advices[0], &advices[1])
+            .unwrap();
+        assert!(matches!(
+            config.with_lookup_columns(&advices[0], &advices[1]),
+            Err(CircuitError::LookupColumnsAlreadyAssigned)
+        ));
+
+        // nor after a lookup has set them
+        let mut config = BaseConfig::<F>::default();
+        config
+            .configure_lookup(
+                &mut cs,
+                &advices[0],
+                &advices[1],
+                &advices[2],
+                (-16, 16),
+                K,
+                &LookupOp::ReLU,
+            )
+            .unwrap();
+        assert!(matches!(
+            config.with_lookup_columns(&advices[0], &advices[1]),
+            Err(CircuitError::LookupColumnsAlreadyAssigned)
+        ));
+
+        assert!(matches!(
+            BaseConfig::<F>::default().with_lookup_columns(&VarTensor::Empty, &advices[1]),
+            Err(CircuitError::DimMismatch(_))
+        ));
+    }
+}
@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 
 #[derive(Debug, Default, Clone, Copy)]
 /// f32 wrapper
@@ -126,12 +127,75 @@ impl fmt::Display for F32 {
     }
 }
 
+impl FromStr for F32 {
+    type Err = std::num::ParseFloatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<f32>().map(F32)
+    }
+}
+
+// --------------------------------------------------------------------------------------------
+//
+// Op string utils to parse the names produced by `as_string` back into ops
+//
+//
+
+/// Splits an op string such as `MAX(scales=(0, 1), a=2)` into its name and `key=value` parameters.
+/// Returns `None` if the string is malformed.
+pub fn parse_op_string(s: &str) -> Option<(&str, Vec<(&str, &str)>)> {
+    let s = s.trim();
+    let (name, params) = match s.find('(') {
+        Some(idx) => (&s[..idx], s[idx + 1..].strip_suffix(')')?),
+        None => return Some((s, vec![])),
+    };
+
+    // only split on commas that aren't nested within a tuple parameter
+    let mut split = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in params.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                split.push(&params[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    split.push(&params[start..]);
+
+    let params = split
+        .into_iter()
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| p.split_once('=').map(|(k, v)| (k.trim(), v.trim())))
+        .collect::<Option<Vec<_>>>()?;
+    Some((name.trim(), params))
+}
+
+/// Parses the parameter `key` from the output of [parse_op_string].
+pub fn parse_op_param<T: FromStr>(params: &[(&str, &str)], key: &str) -> Option<T> {
+    params
+        .iter()
+        .find(|(k, _)| *k == key)
+        .and_then(|(_, v)| v.parse().ok())
+}
+
+/// Parses a `(a, b)` tuple parameter from the output of [parse_op_string].
+pub fn parse_op_pair_param<T: FromStr>(params: &[(&str, &str)], key: &str) -> Option<(T, T)> {
+    let pair = params.iter().find(|(k, _)| *k == key)?.1;
+    let (a, b) = pair.strip_prefix('(')?.strip_suffix(')')?.split_once(',')?;
+    Some((a.trim().parse().ok()?, b.trim().parse().ok()?))
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
 
-    use super::F32;
+    use super::{parse_op_pair_param, parse_op_param, parse_op_string, F32};
 
     fn calculate_hash<T: Hash>(t: &T) -> u64 {
         let mut s = DefaultHasher::new();
@@ -160,4 +224,21 @@ mod tests {
         assert!(calculate_hash(&F32(0.0)) == calculate_hash(&F32(-0.0)));
         assert!(calculate_hash(&F32(std::f32::NAN)) == calculate_hash(&F32(-std::f32::NAN)));
     }
+
+    #[test]
+    fn op_string_parsing() {
+        assert_eq!(parse_op_string("RELU"), Some(("RELU", vec![])));
+
+        let (name, params) = parse_op_string("MAX(scales=(0, 1), a=2.5)").unwrap();
+        assert_eq!(name, "MAX");
+        assert_eq!(
+            parse_op_pair_param::<usize>(&params, "scales"),
+            Some((0, 1))
+        );
+        assert_eq!(parse_op_param::<F32>(&params, "a"), Some(F32(2.5)));
+        assert_eq!(parse_op_param::<F32>(&params, "b"), None);
+
+        assert_eq!(parse_op_string("MAX(scales=(0, 1)"), None);
+        assert_eq!(parse_op_string("MAX(scales)"), None);
+    }
 }
@@ -2,6 +2,8 @@ use halo2_proofs::arithmetic::Field;
 /// Utilities for converting from Halo2 PrimeField types to integers (and vice-versa).
 use halo2curves::ff::PrimeField;
 
+use crate::circuit::CircuitError;
+
 /// Converts an i32 to a PrimeField element.
 pub fn i32_to_felt<F: PrimeField>(x: i32) -> F {
     if x >= 0 {
@@ -20,6 +22,16 @@ pub fn i128_to_felt<F: PrimeField>(x: i128) -> F {
     }
 }
 
+/// Converts an i128 to a PrimeField element, checking that it lies in the signed band that [felt_to_i128] decodes,
+/// ie. that its magnitude is below both `2^127` and a quarter of the field size.
+pub fn try_i128_to_felt<F: PrimeField>(x: i128) -> Result<F, CircuitError> {
+    let bound_bits = (F::NUM_BITS - 2).min(i128::BITS - 1);
+    if x.unsigned_abs() >> bound_bits != 0 {
+        return Err(CircuitError::FeltOutOfRange(x));
+    }
+    Ok(i128_to_felt(x))
+}
+
 /// Converts a PrimeField element to an i32.
 pub fn felt_to_i32<F: PrimeField + PartialOrd + Field>(x: F) -> i32 {
     if x > F::from(i32::MAX as u64) {
@@ -35,6 +47,23 @@ pub fn felt_to_i32<F: PrimeField + PartialOrd + Field>(x: F) -> i32 {
     }
 }
 
+/// Converts a PrimeField element to an i32, inverting [i32_to_felt].
+/// Returns `None` for elements outside of the signed band `[i32::MIN, i32::MAX]`, which [felt_to_i32] would truncate.
+pub fn try_felt_to_i32<F: PrimeField + PartialOrd + Field>(x: F) -> Option<i32> {
+    let lower_64 = |x: F| {
+        let rep = x.to_repr();
+        let tmp: &[u8] = rep.as_ref();
+        u64::from_le_bytes(tmp[..8].try_into().unwrap())
+    };
+    if x <= F::from(i32::MAX as u64) {
+        Some(lower_64(x) as i32)
+    } else if -x <= F::from(i32::MIN.unsigned_abs() as u64) {
+        Some(-(lower_64(-x) as i64) as i32)
+    } else {
+        None
+    }
+}
+
 /// Converts a PrimeField element to an i128.
 pub fn felt_to_f64<F: PrimeField + PartialOrd + Field>(x: F) -> f64 {
     if x > F::from_u128(i128::MAX as u128) {
@@ -86,6 +115,20 @@ mod test {
         assert_eq!(res, F::from(131072));
     }
 
+    #[test]
+    fn test_try_conv() {
+        for x in [0, 1, -1, i128::MAX, -i128::MAX] {
+            let res: F = try_i128_to_felt(x).unwrap();
+            assert_eq!(res, i128_to_felt(x));
+            assert_eq!(felt_to_i128(res), x);
+        }
+
+        assert!(matches!(
+            try_i128_to_felt::<F>(i128::MIN),
+            Err(CircuitError::FeltOutOfRange(i128::MIN))
+        ));
+    }
+
     #[test]
     fn felttoi32() {
         for x in -(2i32.pow(16))..(2i32.pow(16)) {
@@ -95,6 +138,21 @@ mod test {
         }
     }
 
+    #[test]
+    fn tryfelttoi32() {
+        for x in i16::MIN..=i16::MAX {
+            let fieldx: F = i32_to_felt::<F>(x as i32);
+            assert_eq!(try_felt_to_i32::<F>(fieldx), Some(x as i32));
+        }
+
+        for x in [i32::MIN, i32::MAX] {
+            assert_eq!(try_felt_to_i32::<F>(i32_to_felt(x)), Some(x));
+        }
+        for x in [i32::MIN as i128 - 1, i32::MAX as i128 + 1, 1 << 100] {
+            assert_eq!(try_felt_to_i32::<F>(i128_to_felt(x)), None);
+        }
+    }
+
     #[test]
     fn felttoi128() {
         for x in -(2i128.pow(20))..(2i128.pow(20)) {
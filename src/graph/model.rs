@@ -1044,12 +1044,8 @@ impl Model {
     ) -> Result<PolyConfig<Fp>, Box<dyn Error>> {
         info!("configuring model");
 
-        let mut base_gate = PolyConfig::configure(
-            meta,
-            vars.advices[0..2].try_into()?,
-            &vars.advices[2],
-            check_mode,
-        );
+        let mut base_gate =
+            PolyConfig::configure(meta, &vars.advices[0..2], &vars.advices[2], check_mode)?;
         // set scale for HybridOp::RangeCheck and call self.conf_lookup on that op for percentage tolerance case
         let input = &vars.advices[0];
         let output = &vars.advices[1];
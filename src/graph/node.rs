@@ -116,6 +116,24 @@ impl Op<Fp> for Rescaled {
         self.inner.layout(config, region, res)
     }
 
+    fn dummy_layout(
+        &self,
+        config: &mut crate::circuit::BaseConfig<Fp>,
+        region: &mut crate::circuit::region::RegionCtx<Fp>,
+        values: &[crate::tensor::ValTensor<Fp>],
+    ) -> Result<Option<crate::tensor::ValTensor<Fp>>, Box<dyn Error>> {
+        if self.scale.len() != values.len() {
+            return Err(Box::new(TensorError::DimMismatch(
+                "rescaled inputs".to_string(),
+            )));
+        }
+
+        let res =
+            &crate::circuit::layouts::rescale(config, region, values[..].try_into()?, &self.scale)?
+                [..];
+        self.inner.dummy_layout(config, region, res)
+    }
+
     fn clone_dyn(&self) -> Box<dyn Op<Fp>> {
         Box::new(self.clone()) // Forward to the derive(Clone) impl
     }
@@ -251,6 +269,24 @@ impl Op<Fp> for RebaseScale {
         )?))
     }
 
+    fn dummy_layout(
+        &self,
+        config: &mut crate::circuit::BaseConfig<Fp>,
+        region: &mut crate::circuit::region::RegionCtx<Fp>,
+        values: &[crate::tensor::ValTensor<Fp>],
+    ) -> Result<Option<crate::tensor::ValTensor<Fp>>, Box<dyn Error>> {
+        let original_res = self.inner.dummy_layout(config, region, values)?.unwrap();
+
+        Op::<Fp>::dummy_layout(
+            &LookupOp::Div {
+                denom: crate::circuit::utils::F32(self.multiplier as f32),
+            },
+            config,
+            region,
+            &[original_res],
+        )
+    }
+
     fn clone_dyn(&self) -> Box<dyn Op<Fp>> {
         Box::new(self.clone()) // Forward to the derive(Clone) impl
     }
@@ -398,6 +434,58 @@ impl Op<Fp> for SupportedOp {
         }
     }
 
+    fn dummy_layout(
+        &self,
+        config: &mut crate::circuit::BaseConfig<Fp>,
+        region: &mut crate::circuit::region::RegionCtx<Fp>,
+        values: &[crate::tensor::ValTensor<Fp>],
+    ) -> Result<Option<crate::tensor::ValTensor<Fp>>, Box<dyn Error>> {
+        match self {
+            SupportedOp::Linear(op) => op.dummy_layout(config, region, values),
+            SupportedOp::Nonlinear(op) => op.dummy_layout(config, region, values),
+            SupportedOp::Hybrid(op) => op.dummy_layout(config, region, values),
+            SupportedOp::Input(op) => op.dummy_layout(config, region, values),
+            SupportedOp::Constant(op) => op.dummy_layout(config, region, values),
+            SupportedOp::Unknown(op) => op.dummy_layout(config, region, values),
+            SupportedOp::Rescaled(op) => op.dummy_layout(config, region, values),
+            SupportedOp::RebaseScale(op) => op.dummy_layout(config, region, values),
+        }
+    }
+
+    fn f_multi(
+        &self,
+        inputs: &[Tensor<Fp>],
+    ) -> Result<Vec<Tensor<Fp>>, crate::tensor::TensorError> {
+        match self {
+            SupportedOp::Linear(op) => op.f_multi(inputs),
+            SupportedOp::Nonlinear(op) => op.f_multi(inputs),
+            SupportedOp::Hybrid(op) => op.f_multi(inputs),
+            SupportedOp::Input(op) => op.f_multi(inputs),
+            SupportedOp::Constant(op) => op.f_multi(inputs),
+            SupportedOp::Unknown(op) => op.f_multi(inputs),
+            SupportedOp::Rescaled(op) => op.f_multi(inputs),
+            SupportedOp::RebaseScale(op) => op.f_multi(inputs),
+        }
+    }
+
+    fn layout_multi(
+        &self,
+        config: &mut crate::circuit::BaseConfig<Fp>,
+        region: &mut crate::circuit::region::RegionCtx<Fp>,
+        values: &[crate::tensor::ValTensor<Fp>],
+    ) -> Result<Vec<crate::tensor::ValTensor<Fp>>, Box<dyn Error>> {
+        match self {
+            SupportedOp::Linear(op) => op.layout_multi(config, region, values),
+            SupportedOp::Nonlinear(op) => op.layout_multi(config, region, values),
+            SupportedOp::Hybrid(op) => op.layout_multi(config, region, values),
+            SupportedOp::Input(op) => op.layout_multi(config, region, values),
+            SupportedOp::Constant(op) => op.layout_multi(config, region, values),
+            SupportedOp::Unknown(op) => op.layout_multi(config, region, values),
+            SupportedOp::Rescaled(op) => op.layout_multi(config, region, values),
+            SupportedOp::RebaseScale(op) => op.layout_multi(config, region, values),
+        }
+    }
+
     fn is_input(&self) -> bool {
         match self {
             SupportedOp::Linear(op) => Op::<Fp>::is_input(op),
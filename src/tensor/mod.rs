@@ -1471,4 +1471,13 @@ mod tests {
         let b = Tensor::<i32>::new(Some(&[1, 4]), &[2, 1]).unwrap();
         assert_eq!(a.get_slice(&[0..2, 0..1]).unwrap(), b);
     }
+
+    #[test]
+    fn tensor_flatten_roundtrip() {
+        let dims = [2, 3, 1, 4];
+        let a = Tensor::<i32>::new(Some(&(0..24).collect::<Vec<_>>()), &dims).unwrap();
+        let flat = ops::flatten(a.clone());
+        assert_eq!(flat, (0..24).collect::<Vec<_>>());
+        assert_eq!(ops::unflatten(flat, &dims).unwrap(), a);
+    }
 }
@@ -2507,6 +2507,57 @@ pub fn pad<T: TensorType>(
     Ok(output)
 }
 
+/// Flattens a tensor of any rank into its elements, in row-major order.
+/// See [unflatten] for the inverse.
+/// # Arguments
+///
+/// * `a` - Tensor.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::flatten;
+///
+/// let x = Tensor::<i128>::new(
+///     Some(&[1, 2, 3, 4, 5, 6, 7, 8]),
+///     &[2, 1, 2, 2],
+/// ).unwrap();
+/// assert_eq!(flatten(x), vec![1, 2, 3, 4, 5, 6, 7, 8]);
+/// ```
+pub fn flatten<T: TensorType>(a: Tensor<T>) -> Vec<T> {
+    a.inner
+}
+
+/// Rebuilds a tensor of shape `dims` from its elements in row-major order, as returned by [flatten].
+/// # Arguments
+///
+/// * `values` - Elements of the tensor.
+/// * `dims` - Shape of the tensor.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::tensor::ops::unflatten;
+///
+/// let result = unflatten(vec![1, 2, 3, 4, 5, 6], &[3, 1, 2]).unwrap();
+/// let expected = Tensor::<i128>::new(
+///     Some(&[1, 2, 3, 4, 5, 6]),
+///     &[3, 1, 2],
+/// ).unwrap();
+/// assert_eq!(result, expected);
+///
+/// assert!(unflatten(vec![1, 2, 3], &[2, 2]).is_err());
+/// ```
+///
+/// # Errors
+/// Returns a TensorError if the number of `values` doesn't match `dims`.
+pub fn unflatten<T: TensorType>(values: Vec<T>, dims: &[usize]) -> Result<Tensor<T>, TensorError> {
+    let mut output = Tensor::from(values.into_iter());
+    if output.len() != dims.iter().product::<usize>() {
+        return Err(TensorError::DimError);
+    }
+    output.reshape(dims);
+    Ok(output)
+}
+
 /// Packs a multi-dim tensor into a single elem tensor
 /// # Arguments
 ///
@@ -2704,7 +2755,7 @@ pub fn slice<T: TensorType + Send + Sync>(
 pub mod nonlinearities {
     use super::*;
 
-    /// Ceiling operator.
+    /// Ceiling operator, divides out `scale` rounding towards positive infinity.
     /// # Arguments
     /// * `a` - Tensor
     /// * `scale` - Single value
@@ -2720,6 +2771,10 @@ pub mod nonlinearities {
     /// let result = ceil(&x, 2.0);
     /// let expected = Tensor::<i128>::new(Some(&[1, 1, 2, 2, 3, 3]), &[3, 2]).unwrap();
     /// assert_eq!(result, expected);
+    /// let x = Tensor::<i128>::new(Some(&[-5, -4, -3, -1]), &[4]).unwrap();
+    /// let result = ceil(&x, 2.0);
+    /// let expected = Tensor::<i128>::new(Some(&[-2, -2, -1, 0]), &[4]).unwrap();
+    /// assert_eq!(result, expected);
     /// ```
     pub fn ceil(a: &Tensor<i128>, scale: f64) -> Tensor<i128> {
         a.par_enum_map(|_, a_i| {
@@ -2730,7 +2785,7 @@ pub mod nonlinearities {
         .unwrap()
     }
 
-    /// Floor operator.
+    /// Floor operator, divides out `scale` rounding towards negative infinity.
     /// # Arguments
     /// * `a` - Tensor
     /// * `scale` - Single value
@@ -2745,6 +2800,10 @@ pub mod nonlinearities {
     /// let result = floor(&x, 2.0);
     /// let expected = Tensor::<i128>::new(Some(&[0, 1, 1, 2, 2, 3]), &[3, 2]).unwrap();
     /// assert_eq!(result, expected);
+    /// let x = Tensor::<i128>::new(Some(&[-5, -4, -3, -1]), &[4]).unwrap();
+    /// let result = floor(&x, 2.0);
+    /// let expected = Tensor::<i128>::new(Some(&[-3, -2, -2, -1]), &[4]).unwrap();
+    /// assert_eq!(result, expected);
     /// ```
     pub fn floor(a: &Tensor<i128>, scale: f64) -> Tensor<i128> {
         a.par_enum_map(|_, a_i| {
@@ -2755,7 +2814,8 @@ pub mod nonlinearities {
         .unwrap()
     }
 
-    /// Round operator.
+    /// Round operator, divides out `scale` rounding to the nearest integer.
+    /// Ties round away from zero, see [round_half_to_even] for banker's rounding.
     /// # Arguments
     /// * `a` - Tensor
     /// * `scale` - Single value
@@ -2770,6 +2830,10 @@ pub mod nonlinearities {
     /// let result = round(&x, 2.0);
     /// let expected = Tensor::<i128>::new(Some(&[1, 1, 2, 2, 3, 3]), &[3, 2]).unwrap();
     /// assert_eq!(result, expected);
+    /// let x = Tensor::<i128>::new(Some(&[-5, -4, -3, -1]), &[4]).unwrap();
+    /// let result = round(&x, 2.0);
+    /// let expected = Tensor::<i128>::new(Some(&[-3, -2, -2, -1]), &[4]).unwrap();
+    /// assert_eq!(result, expected);
     /// ```
     pub fn round(a: &Tensor<i128>, scale: f64) -> Tensor<i128> {
         a.par_enum_map(|_, a_i| {
@@ -2780,7 +2844,8 @@ pub mod nonlinearities {
         .unwrap()
     }
 
-    /// Round half to even operator.
+    /// Round half to even operator, divides out `scale` rounding to the nearest integer.
+    /// Ties round to the nearest even integer, which avoids the upward bias of [round] when requantizing.
     /// # Arguments
     /// * `a` - Tensor
     /// * `scale` - Single value
@@ -2795,6 +2860,10 @@ pub mod nonlinearities {
     /// let result = round_half_to_even(&x, 2.0);
     /// let expected = Tensor::<i128>::new(Some(&[0, 1, 2, 2, 2, 3]), &[3, 2]).unwrap();
     /// assert_eq!(result, expected);
+    /// let x = Tensor::<i128>::new(Some(&[-5, -4, -3, -1]), &[4]).unwrap();
+    /// let result = round_half_to_even(&x, 2.0);
+    /// let expected = Tensor::<i128>::new(Some(&[-2, -2, -2, 0]), &[4]).unwrap();
+    /// assert_eq!(result, expected);
     /// ```
     pub fn round_half_to_even(a: &Tensor<i128>, scale: f64) -> Tensor<i128> {
         a.par_enum_map(|_, a_i| {
@@ -2897,14 +2966,49 @@ pub mod nonlinearities {
     pub fn sigmoid(a: &Tensor<i128>, scale_input: f64) -> Tensor<i128> {
         a.par_enum_map(|_, a_i| {
             let kix = (a_i as f64) / scale_input;
-            let fout = scale_input / (1.0 + (-kix).exp());
+            let fout = scale_input * logistic(kix);
             let rounded = fout.round();
             Ok::<_, TensorError>(rounded as i128)
         })
         .unwrap()
     }
 
+    /// Elementwise applies silu (swish) `x * sigmoid(x)` to a tensor of integers.
+    /// Large negative inputs map to 0 and large positive inputs to themselves.
+    /// # Arguments
+    ///
+    /// * `a` - Tensor
+    /// * `scale_input` - Single value
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::silu;
+    /// let x = Tensor::<i128>::new(
+    ///     Some(&[0, 128, -128, 2560, -2560]),
+    ///     &[5],
+    /// ).unwrap();
+    /// let result = silu(&x, 128.0);
+    /// let expected = Tensor::<i128>::new(Some(&[0, 94, -34, 2560, 0]), &[5]).unwrap();
+    /// assert_eq!(result, expected);
+    /// ```
+    pub fn silu(a: &Tensor<i128>, scale_input: f64) -> Tensor<i128> {
+        a.par_enum_map(|_, a_i| {
+            let kix = (a_i as f64) / scale_input;
+            let fout = (a_i as f64) * logistic(kix);
+            let rounded = fout.round();
+            Ok::<_, TensorError>(rounded as i128)
+        })
+        .unwrap()
+    }
+
+    /// The logistic function `1 / (1 + e^-x)` shared by [sigmoid] and [silu].
+    /// Saturates to 0 for large negative `x` rather than overflowing.
+    fn logistic(x: f64) -> f64 {
+        1.0 / (1.0 + (-x).exp())
+    }
+
     /// Elementwise applies exponential to a tensor of integers.
+    /// Outputs saturate at [i128::MAX] rather than overflowing for large inputs.
     /// # Arguments
     ///
     /// * `a` - Tensor
@@ -2932,18 +3036,25 @@ pub mod nonlinearities {
     /// let expected = Tensor::<i128>::new(Some(&[550, 524, 555]), &[3]).unwrap();
     ///
     /// assert_eq!(result, expected);
+    ///
+    /// // exp(0) maps to the scale and large inputs saturate
+    /// let x = Tensor::<i128>::new(Some(&[0, 1_000_000]), &[2]).unwrap();
+    /// let result = exp(&x, 512.0);
+    /// let expected = Tensor::<i128>::new(Some(&[512, i128::MAX]), &[2]).unwrap();
+    /// assert_eq!(result, expected);
     /// ```
     pub fn exp(a: &Tensor<i128>, scale_input: f64) -> Tensor<i128> {
         a.par_enum_map(|_, a_i| {
             let kix = (a_i as f64) / scale_input;
             let fout = scale_input * kix.exp();
-            let rounded = fout.round();
+            let rounded = fout.round().min(i128::MAX as f64);
             Ok::<_, TensorError>(rounded as i128)
         })
         .unwrap()
     }
 
-    /// Elementwise applies exponential to a tensor of integers.
+    /// Elementwise applies natural log to a tensor of integers.
+    /// As ln is undefined for non-positive inputs, these map to the output for the smallest positive input (1).
     /// # Arguments
     ///
     /// * `a` - Tensor
@@ -2971,10 +3082,21 @@ pub mod nonlinearities {
     /// let expected = Tensor::<i128>::new(Some(&[-1345, -1922, -1293]), &[3]).unwrap();
     ///
     /// assert_eq!(result, expected);
+    ///
+    /// // ln(scale) maps to 0 and non-positive inputs map to ln(1)
+    /// let x = Tensor::<i128>::new(Some(&[512, 1, 0, -5]), &[4]).unwrap();
+    /// let result = ln(&x, 512.0);
+    /// let expected = Tensor::<i128>::new(Some(&[0, -3194, -3194, -3194]), &[4]).unwrap();
+    /// assert_eq!(result, expected);
+    ///
+    /// // strictly increasing over the positive domain
+    /// let x = Tensor::<i128>::new(Some(&(1..=256).collect::<Vec<_>>()), &[256]).unwrap();
+    /// let result = ln(&x, 512.0);
+    /// assert!(result.windows(2).all(|w| w[0] < w[1]));
     /// ```
     pub fn ln(a: &Tensor<i128>, scale_input: f64) -> Tensor<i128> {
         a.par_enum_map(|_, a_i| {
-            let kix = (a_i as f64) / scale_input;
+            let kix = (a_i.max(1) as f64) / scale_input;
             let fout = scale_input * kix.ln();
             let rounded = fout.round();
             Ok::<_, TensorError>(rounded as i128)
@@ -3002,6 +3124,31 @@ pub mod nonlinearities {
             .unwrap()
     }
 
+    /// Elementwise maps values within `[0, 2^bits)` to themselves and all other values to 0.
+    /// As 0 is in range, no out of range value maps to itself.
+    /// # Arguments
+    /// * `a` - Tensor
+    /// * `bits` - number of bits values are checked to fit in
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::range_check;
+    /// let x = Tensor::<i128>::new(
+    ///    Some(&[-1, 0, 7, 15, 16, 3]),
+    ///  &[2, 3],
+    /// ).unwrap();
+    /// let result = range_check(&x, 4);
+    /// let expected = Tensor::<i128>::new(Some(&[0, 0, 7, 15, 0, 3]), &[2, 3]).unwrap();
+    /// assert_eq!(result, expected);
+    /// ```
+    pub fn range_check(a: &Tensor<i128>, bits: usize) -> Tensor<i128> {
+        a.par_enum_map(|_, a_i| {
+            let in_range = a_i >= 0 && (a_i >> bits.min(127)) == 0;
+            Ok::<_, TensorError>(if in_range { a_i } else { 0 })
+        })
+        .unwrap()
+    }
+
     /// softmax layout
     pub fn softmax_axes(
         a: &Tensor<i128>,
@@ -3130,6 +3277,7 @@ pub mod nonlinearities {
     }
 
     /// Elementwise applies square root to a tensor of integers.
+    /// Non-positive inputs are outside of the domain and map to 0.
     /// # Arguments
     ///
     /// * `a` - Tensor
@@ -3146,9 +3294,18 @@ pub mod nonlinearities {
     /// let result = sqrt(&x, 1.0);
     /// let expected = Tensor::<i128>::new(Some(&[2, 5, 3, 1, 1, 0]), &[2, 3]).unwrap();
     /// assert_eq!(result, expected);
+    ///
+    /// // sqrt(4) round trips at scale and the domain is guarded
+    /// let x = Tensor::<i128>::new(Some(&[4 * 128, -3]), &[2]).unwrap();
+    /// let result = sqrt(&x, 128.0);
+    /// let expected = Tensor::<i128>::new(Some(&[2 * 128, 0]), &[2]).unwrap();
+    /// assert_eq!(result, expected);
     /// ```
     pub fn sqrt(a: &Tensor<i128>, scale_input: f64) -> Tensor<i128> {
         a.par_enum_map(|_, a_i| {
+            if a_i <= 0 {
+                return Ok::<_, TensorError>(0);
+            }
             let kix = (a_i as f64) / scale_input;
             let fout = scale_input * kix.sqrt();
             let rounded = fout.round();
@@ -3158,6 +3315,7 @@ pub mod nonlinearities {
     }
 
     /// Elementwise applies reciprocal square root to a tensor of integers.
+    /// Non-positive inputs would blow up to an unrepresentable output so they map to 0.
     /// # Arguments
     ///
     /// * `a` - Tensor
@@ -3174,9 +3332,23 @@ pub mod nonlinearities {
     /// let result = rsqrt(&x, 1.0);
     /// let expected = Tensor::<i128>::new(Some(&[1, 0, 0, 1, 1, 1]), &[2, 3]).unwrap();
     /// assert_eq!(result, expected);
+    ///
+    /// // zero is clamped rather than producing an unbounded output
+    /// let x = Tensor::<i128>::new(Some(&[0, -4]), &[2]).unwrap();
+    /// let result = rsqrt(&x, 128.0);
+    /// let expected = Tensor::<i128>::new(Some(&[0, 0]), &[2]).unwrap();
+    /// assert_eq!(result, expected);
+    ///
+    /// // monotonically decreasing over positive inputs
+    /// let x = Tensor::<i128>::new(Some(&(1..=1024).collect::<Vec<_>>()), &[1024]).unwrap();
+    /// let result = rsqrt(&x, 128.0);
+    /// assert!(result.windows(2).all(|w| w[0] >= w[1]));
     /// ```
     pub fn rsqrt(a: &Tensor<i128>, scale_input: f64) -> Tensor<i128> {
         a.par_enum_map(|_, a_i| {
+            if a_i <= 0 {
+                return Ok::<_, TensorError>(0);
+            }
             let kix = (a_i as f64) / scale_input;
             let fout = scale_input * (1.0 / kix.sqrt());
             let rounded = fout.round();
@@ -3185,7 +3357,15 @@ pub mod nonlinearities {
         .unwrap()
     }
 
-    /// Elementwise applies cosine to a tensor of integers.
+    /// Reduces an angle (in radians) to `[0, 2π)` before evaluating periodic functions, so that
+    /// tables over large input domains don't lose precision. The reduction is applied to the
+    /// dequantized input without any intermediate rounding, only the final output is rounded (half away from zero).
+    /// As `2π * scale` is not an integer, fixed point inputs one period apart can differ by a unit after rounding.
+    fn reduce_angle(x: f64) -> f64 {
+        x.rem_euclid(std::f64::consts::TAU)
+    }
+
+    /// Elementwise applies cosine to a tensor of integers, after range reduction (see [reduce_angle]).
     /// # Arguments
     /// * `a` - Tensor
     /// * `scale_input` - Single value
@@ -3205,7 +3385,7 @@ pub mod nonlinearities {
     pub fn cos(a: &Tensor<i128>, scale_input: f64) -> Tensor<i128> {
         a.par_enum_map(|_, a_i| {
             let kix = (a_i as f64) / scale_input;
-            let fout = scale_input * kix.cos();
+            let fout = scale_input * reduce_angle(kix).cos();
             let rounded = fout.round();
             Ok::<_, TensorError>(rounded as i128)
         })
@@ -3293,7 +3473,7 @@ pub mod nonlinearities {
         .unwrap()
     }
 
-    /// Elementwise applies sine to a tensor of integers.
+    /// Elementwise applies sine to a tensor of integers, after range reduction (see [reduce_angle]).
     /// # Arguments
     /// * `a` - Tensor
     /// * `scale_input` - Single value
@@ -3313,7 +3493,7 @@ pub mod nonlinearities {
     pub fn sin(a: &Tensor<i128>, scale_input: f64) -> Tensor<i128> {
         a.par_enum_map(|_, a_i| {
             let kix = (a_i as f64) / scale_input;
-            let fout = scale_input * kix.sin();
+            let fout = scale_input * reduce_angle(kix).sin();
             let rounded = fout.round();
             Ok::<_, TensorError>(rounded as i128)
         })
@@ -3513,6 +3693,63 @@ pub mod nonlinearities {
         .unwrap()
     }
 
+    const NCOEF: usize = 28;
+    const COF: [f64; 28] = [
+        -1.3026537197817094,
+        6.419_697_923_564_902e-1,
+        1.9476473204185836e-2,
+        -9.561_514_786_808_63e-3,
+        -9.46595344482036e-4,
+        3.66839497852761e-4,
+        4.2523324806907e-5,
+        -2.0278578112534e-5,
+        -1.624290004647e-6,
+        1.303655835580e-6,
+        1.5626441722e-8,
+        -8.5238095915e-8,
+        6.529054439e-9,
+        5.059343495e-9,
+        -9.91364156e-10,
+        -2.27365122e-10,
+        9.6467911e-11,
+        2.394038e-12,
+        -6.886027e-12,
+        8.94487e-13,
+        3.13092e-13,
+        -1.12708e-13,
+        3.81e-16,
+        7.106e-15,
+        -1.523e-15,
+        -9.4e-17,
+        1.21e-16,
+        -2.8e-17,
+    ];
+
+    /// Chebyshev coefficients
+    fn erfccheb(z: f64) -> f64 {
+        let mut d = 0f64;
+        let mut dd = 0f64;
+
+        assert!(z >= 0f64, "erfccheb requires nonnegative argument");
+        let t = 2f64 / (2f64 + z);
+        let ty = 4f64 * t - 2f64;
+        for j in (1..NCOEF - 1).rev() {
+            let tmp = d;
+            d = ty * d - dd + COF[j];
+            dd = tmp;
+        }
+        t * (-z.powi(2) + 0.5 * (COF[0] + ty * d) - dd).exp()
+    }
+
+    /// Error function, saturating to ±1 for large `|x|`.
+    fn erf(x: f64) -> f64 {
+        if x >= 0f64 {
+            1.0 - erfccheb(x)
+        } else {
+            erfccheb(-x) - 1f64
+        }
+    }
+
     /// Applies error function (erf) on a tensor of integers.
     /// # Arguments
     ///
@@ -3532,71 +3769,253 @@ pub mod nonlinearities {
     /// assert_eq!(result, expected);
     /// ```
     pub fn erffunc(a: &Tensor<i128>, scale_input: f64) -> Tensor<i128> {
-        const NCOEF: usize = 28;
-        const COF: [f64; 28] = [
-            -1.3026537197817094,
-            6.419_697_923_564_902e-1,
-            1.9476473204185836e-2,
-            -9.561_514_786_808_63e-3,
-            -9.46595344482036e-4,
-            3.66839497852761e-4,
-            4.2523324806907e-5,
-            -2.0278578112534e-5,
-            -1.624290004647e-6,
-            1.303655835580e-6,
-            1.5626441722e-8,
-            -8.5238095915e-8,
-            6.529054439e-9,
-            5.059343495e-9,
-            -9.91364156e-10,
-            -2.27365122e-10,
-            9.6467911e-11,
-            2.394038e-12,
-            -6.886027e-12,
-            8.94487e-13,
-            3.13092e-13,
-            -1.12708e-13,
-            3.81e-16,
-            7.106e-15,
-            -1.523e-15,
-            -9.4e-17,
-            1.21e-16,
-            -2.8e-17,
-        ];
-
-        /// Chebyshev coefficients
-        fn erfccheb(z: f64) -> f64 {
-            let mut d = 0f64;
-            let mut dd = 0f64;
-
-            assert!(z >= 0f64, "erfccheb requires nonnegative argument");
-            let t = 2f64 / (2f64 + z);
-            let ty = 4f64 * t - 2f64;
-            for j in (1..NCOEF - 1).rev() {
-                let tmp = d;
-                d = ty * d - dd + COF[j];
-                dd = tmp;
-            }
-            t * (-z.powi(2) + 0.5 * (COF[0] + ty * d) - dd).exp()
-        }
+        a.par_enum_map(|_, a_i| {
+            let kix = (a_i as f64) / scale_input;
+            let fout = scale_input * erf(kix);
+            let rounded = fout.round();
+            Ok::<_, TensorError>(rounded as i128)
+        })
+        .unwrap()
+    }
 
-        pub fn erf(x: f64) -> f64 {
-            if x >= 0f64 {
-                1.0 - erfccheb(x)
-            } else {
-                erfccheb(-x) - 1f64
+    /// Elementwise applies GELU `0.5 * x * (1 + erf(x / sqrt(2)))` to a tensor of integers, using [erffunc]'s exact erf rather than the tanh approximation of [gelu].
+    /// # Arguments
+    ///
+    /// * `a` - Tensor
+    /// * `scale_input` - Single value
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::exact_gelu;
+    /// let x = Tensor::<i128>::new(
+    ///     Some(&[0, 128, 192, -192, -512]),
+    ///     &[5],
+    /// ).unwrap();
+    /// let result = exact_gelu(&x, 128.0);
+    /// let expected = Tensor::<i128>::new(Some(&[0, 108, 179, -13, 0]), &[5]).unwrap();
+    /// assert_eq!(result, expected);
+    /// ```
+    pub fn exact_gelu(a: &Tensor<i128>, scale_input: f64) -> Tensor<i128> {
+        a.par_enum_map(|_, a_i| {
+            let kix = (a_i as f64) / scale_input;
+            let fout = scale_input * 0.5 * kix * (1.0 + erf(kix / std::f64::consts::SQRT_2));
+            let rounded = fout.round();
+            Ok::<_, TensorError>(rounded as i128)
+        })
+        .unwrap()
+    }
+
+    /// Elementwise applies the tanh approximation of GELU to a tensor of integers.
+    /// # Arguments
+    ///
+    /// * `a` - Tensor
+    /// * `scale_input` - Single value
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::gelu;
+    /// let x = Tensor::<i128>::new(
+    ///     Some(&[0, 128, 512, -128, -512]),
+    ///     &[5],
+    /// ).unwrap();
+    /// let result = gelu(&x, 128.0);
+    /// let expected = Tensor::<i128>::new(Some(&[0, 108, 512, -20, 0]), &[5]).unwrap();
+    /// assert_eq!(result, expected);
+    /// ```
+    pub fn gelu(a: &Tensor<i128>, scale_input: f64) -> Tensor<i128> {
+        let coeff = (2.0 / std::f64::consts::PI).sqrt();
+        a.par_enum_map(|_, a_i| {
+            let kix = (a_i as f64) / scale_input;
+            let inner = coeff * (kix + 0.044715 * kix.powi(3));
+            let fout = scale_input * 0.5 * kix * (1.0 + inner.tanh());
+            let rounded = fout.round();
+            Ok::<_, TensorError>(rounded as i128)
+        })
+        .unwrap()
+    }
+
+    /// Elementwise applies hard sigmoid `clamp(0.2 * x + 0.5, 0, 1)` to a tensor of integers.
+    /// Computed as `(2x + 5) / 10` so that the knees at `x = ±2.5` land exactly on 0 and 1.
+    /// # Arguments
+    ///
+    /// * `a` - Tensor
+    /// * `scale_input` - Single value
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::hard_sigmoid;
+    /// let x = Tensor::<i128>::new(
+    ///     Some(&[-100, -40, -5, 0, 5, 40, 100]),
+    ///     &[7],
+    /// ).unwrap();
+    /// let result = hard_sigmoid(&x, 16.0);
+    /// let expected = Tensor::<i128>::new(Some(&[0, 0, 7, 8, 9, 16, 16]), &[7]).unwrap();
+    /// assert_eq!(result, expected);
+    /// ```
+    pub fn hard_sigmoid(a: &Tensor<i128>, scale_input: f64) -> Tensor<i128> {
+        let one = scale_input.round() as i128;
+        a.par_enum_map(|_, a_i| {
+            let fout = (2.0 * a_i as f64 + 5.0 * scale_input) / 10.0;
+            let rounded = fout.round() as i128;
+            Ok::<_, TensorError>(rounded.clamp(0, one))
+        })
+        .unwrap()
+    }
+
+    /// Elementwise applies hard tanh `clamp(x, -1, 1)` to a tensor of integers.
+    /// # Arguments
+    ///
+    /// * `a` - Tensor
+    /// * `scale_input` - Single value
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::hard_tanh;
+    /// let x = Tensor::<i128>::new(
+    ///     Some(&[-100, -16, -5, 0, 5, 16, 100]),
+    ///     &[7],
+    /// ).unwrap();
+    /// let result = hard_tanh(&x, 16.0);
+    /// let expected = Tensor::<i128>::new(Some(&[-16, -16, -5, 0, 5, 16, 16]), &[7]).unwrap();
+    /// assert_eq!(result, expected);
+    /// ```
+    pub fn hard_tanh(a: &Tensor<i128>, scale_input: f64) -> Tensor<i128> {
+        let one = scale_input.round() as i128;
+        a.par_enum_map(|_, a_i| Ok::<_, TensorError>(a_i.clamp(-one, one)))
+            .unwrap()
+    }
+
+    /// Inputs (in units of the scale) above which [softplus] is replaced by its asymptote `x`.
+    /// At this point `ln(1 + e^x) - x < 3e-9`, well below the rounding error of any practical scale,
+    /// and `e^x` is far from overflowing an [f64].
+    pub const SOFTPLUS_THRESHOLD: f64 = 20.0;
+
+    /// Elementwise applies softplus `ln(1 + e^x)` to a tensor of integers.
+    /// Inputs beyond [SOFTPLUS_THRESHOLD] map to themselves so that large inputs can't overflow.
+    /// # Arguments
+    ///
+    /// * `a` - Tensor
+    /// * `scale_input` - Single value
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::softplus;
+    /// let x = Tensor::<i128>::new(
+    ///     Some(&[0, 128, -128, 2560, 5120]),
+    ///     &[5],
+    /// ).unwrap();
+    /// let result = softplus(&x, 128.0);
+    /// let expected = Tensor::<i128>::new(Some(&[89, 168, 40, 2560, 5120]), &[5]).unwrap();
+    /// assert_eq!(result, expected);
+    /// ```
+    pub fn softplus(a: &Tensor<i128>, scale_input: f64) -> Tensor<i128> {
+        a.par_enum_map(|_, a_i| {
+            let kix = (a_i as f64) / scale_input;
+            if kix > SOFTPLUS_THRESHOLD {
+                return Ok::<_, TensorError>(a_i);
             }
+            let fout = scale_input * softplus_f64(kix);
+            let rounded = fout.round();
+            Ok::<_, TensorError>(rounded as i128)
+        })
+        .unwrap()
+    }
+
+    /// `ln(1 + e^x)`, replaced by its asymptote `x` beyond [SOFTPLUS_THRESHOLD]. Shared by [softplus] and [mish].
+    fn softplus_f64(x: f64) -> f64 {
+        if x > SOFTPLUS_THRESHOLD {
+            x
+        } else {
+            x.exp().ln_1p()
         }
+    }
 
+    /// Elementwise applies mish `x * tanh(softplus(x))` to a tensor of integers.
+    /// Large positive inputs approach `x` and large negative inputs approach 0, without overflowing.
+    /// # Arguments
+    ///
+    /// * `a` - Tensor
+    /// * `scale_input` - Single value
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::mish;
+    /// let x = Tensor::<i128>::new(
+    ///     Some(&[0, 128, -128, 2560, -2560]),
+    ///     &[5],
+    /// ).unwrap();
+    /// let result = mish(&x, 128.0);
+    /// let expected = Tensor::<i128>::new(Some(&[0, 111, -39, 2560, 0]), &[5]).unwrap();
+    /// assert_eq!(result, expected);
+    /// ```
+    pub fn mish(a: &Tensor<i128>, scale_input: f64) -> Tensor<i128> {
         a.par_enum_map(|_, a_i| {
             let kix = (a_i as f64) / scale_input;
-            let fout = scale_input * erf(kix);
+            let fout = scale_input * kix * softplus_f64(kix).tanh();
             let rounded = fout.round();
             Ok::<_, TensorError>(rounded as i128)
         })
         .unwrap()
     }
 
+    /// Elementwise applies elu `x if x > 0 else alpha * (e^x - 1)` to a tensor of integers.
+    /// The negative branch is clamped to its asymptote `-alpha` so that rounding can't undershoot it.
+    /// # Arguments
+    ///
+    /// * `a` - Tensor
+    /// * `scale_input` - Single value
+    /// * `alpha` - Single value
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::elu;
+    /// let x = Tensor::<i128>::new(
+    ///     Some(&[256, 0, -128, -1280]),
+    ///     &[4],
+    /// ).unwrap();
+    /// let result = elu(&x, 128.0, 1.0);
+    /// let expected = Tensor::<i128>::new(Some(&[256, 0, -81, -128]), &[4]).unwrap();
+    /// assert_eq!(result, expected);
+    /// ```
+    pub fn elu(a: &Tensor<i128>, scale_input: f64, alpha: f64) -> Tensor<i128> {
+        selu(a, scale_input, alpha, 1.0)
+    }
+
+    /// Elementwise applies selu, ie. [elu] scaled by `gamma`, to a tensor of integers.
+    /// The negative branch is clamped to its asymptote `-gamma * alpha`.
+    /// # Arguments
+    ///
+    /// * `a` - Tensor
+    /// * `scale_input` - Single value
+    /// * `alpha` - Single value
+    /// * `gamma` - Single value
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::selu;
+    /// let x = Tensor::<i128>::new(
+    ///     Some(&[256, 0, -128, -1280]),
+    ///     &[4],
+    /// ).unwrap();
+    /// let result = selu(&x, 128.0, 1.6732632, 1.050701);
+    /// let expected = Tensor::<i128>::new(Some(&[269, 0, -142, -225]), &[4]).unwrap();
+    /// assert_eq!(result, expected);
+    /// ```
+    pub fn selu(a: &Tensor<i128>, scale_input: f64, alpha: f64, gamma: f64) -> Tensor<i128> {
+        let floor = -(scale_input * gamma * alpha).round() as i128;
+        a.par_enum_map(|_, a_i| {
+            let kix = (a_i as f64) / scale_input;
+            let fout = if kix > 0.0 {
+                scale_input * gamma * kix
+            } else {
+                scale_input * gamma * alpha * kix.exp_m1()
+            };
+            let rounded = (fout.round() as i128).max(floor);
+            Ok::<_, TensorError>(rounded)
+        })
+        .unwrap()
+    }
+
     /// Elementwise applies leaky relu to a tensor of integers.
     /// # Arguments
     ///
@@ -3725,7 +4144,40 @@ pub mod nonlinearities {
         .unwrap()
     }
 
+    /// Elementwise divides a tensor by `2^bits`, rounding to the nearest integer with ties away from zero.
+    /// Matches [const_div] by `2^bits` but is computed exactly on integers.
+    /// # Arguments
+    ///
+    /// * `a` - Tensor
+    /// * `bits` - Number of bits to shift by
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::tensor::ops::nonlinearities::right_shift;
+    /// let x = Tensor::<i128>::new(
+    ///     Some(&[5, -5, 6, -6, 7, -7]),
+    ///     &[2, 3],
+    /// ).unwrap();
+    /// let result = right_shift(&x, 2);
+    /// let expected = Tensor::<i128>::new(Some(&[1, -1, 2, -2, 2, -2]), &[2, 3]).unwrap();
+    /// assert_eq!(result, expected);
+    /// ```
+    pub fn right_shift(a: &Tensor<i128>, bits: usize) -> Tensor<i128> {
+        a.par_enum_map(|_, a_i| {
+            if bits == 0 {
+                return Ok::<_, TensorError>(a_i);
+            } else if bits >= 127 {
+                return Ok(0);
+            }
+            let half = 1 << (bits - 1);
+            let magnitude = (a_i.unsigned_abs() + half) >> bits;
+            Ok(a_i.signum() * magnitude as i128)
+        })
+        .unwrap()
+    }
+
     /// Elementwise inverse.
+    /// The pole at 0 maps to 0 rather than saturating.
     /// # Arguments
     ///
     /// * `a` - Tensor
@@ -3742,9 +4194,18 @@ pub mod nonlinearities {
     /// let result = recip(&x, k);
     /// let expected = Tensor::<i128>::new(Some(&[1, 2, 1, 0, 2, 2]), &[2, 3]).unwrap();
     /// assert_eq!(result, expected);
+    ///
+    /// // reciprocal(scale) = scale, signs are preserved and 0 maps to 0
+    /// let x = Tensor::<i128>::new(Some(&[128, -128, -64, 0]), &[4]).unwrap();
+    /// let result = recip(&x, 128.0 * 128.0);
+    /// let expected = Tensor::<i128>::new(Some(&[128, -128, -256, 0]), &[4]).unwrap();
+    /// assert_eq!(result, expected);
     /// ```
     pub fn recip(a: &Tensor<i128>, scale: f64) -> Tensor<i128> {
         a.par_enum_map(|_, a_i| {
+            if a_i == 0 {
+                return Ok::<_, TensorError>(0);
+            }
             let denom = (1_f64) / (a_i as f64);
             let d_inv_x = scale * denom;
             Ok::<_, TensorError>(d_inv_x.round() as i128)
@@ -30,6 +30,56 @@ pub enum VarTensor {
     Empty,
 }
 
+/// A serializable snapshot of the columns a [VarTensor] occupies.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VarTensorLayout {
+    /// Advice column indices, grouped as in [VarTensor::Advice]
+    Advice {
+        ///
+        columns: Vec<Vec<usize>>,
+        ///
+        num_inner_cols: usize,
+        ///
+        col_size: usize,
+    },
+    /// Dummy var
+    Dummy {
+        ///
+        num_inner_cols: usize,
+        ///
+        col_size: usize,
+    },
+    /// Empty var
+    Empty,
+}
+
+impl From<&VarTensor> for VarTensorLayout {
+    fn from(var: &VarTensor) -> Self {
+        match var {
+            VarTensor::Advice {
+                inner,
+                num_inner_cols,
+                col_size,
+            } => VarTensorLayout::Advice {
+                columns: inner
+                    .iter()
+                    .map(|block| block.iter().map(|col| col.index()).collect())
+                    .collect(),
+                num_inner_cols: *num_inner_cols,
+                col_size: *col_size,
+            },
+            VarTensor::Dummy {
+                num_inner_cols,
+                col_size,
+            } => VarTensorLayout::Dummy {
+                num_inner_cols: *num_inner_cols,
+                col_size: *col_size,
+            },
+            VarTensor::Empty => VarTensorLayout::Empty,
+        }
+    }
+}
+
 impl VarTensor {
     ///
     pub fn max_rows<F: PrimeField>(cs: &ConstraintSystem<F>, logrows: usize) -> usize {
@@ -121,6 +171,38 @@ impl VarTensor {
         }
     }
 
+    /// Create a new VarTensor::Advice with fresh columns but the same shape (blocks, inner columns, and column size)
+    /// as `self`. Returns [VarTensor::Empty] if `self` isn't a VarTensor::Advice.
+    pub fn new_advice_like<F: PrimeField>(&self, cs: &mut ConstraintSystem<F>) -> Self {
+        match self {
+            VarTensor::Advice {
+                inner,
+                num_inner_cols,
+                col_size,
+            } => {
+                let advices = inner
+                    .iter()
+                    .map(|block| {
+                        block
+                            .iter()
+                            .map(|_| {
+                                let col = cs.advice_column();
+                                cs.enable_equality(col);
+                                col
+                            })
+                            .collect()
+                    })
+                    .collect();
+                VarTensor::Advice {
+                    inner: advices,
+                    num_inner_cols: *num_inner_cols,
+                    col_size: *col_size,
+                }
+            }
+            _ => VarTensor::Empty,
+        }
+    }
+
     /// Initializes fixed columns to support the VarTensor::Advice
     /// Arguments
     /// * `cs` - The constraint system